@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Reading and writing documents in the supported data formats.
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+use spdx_rs::{
+    cyclonedx::{Bom, ConversionReport, CycloneDxVersion},
+    models::SPDX,
+    parsers::spdx_from_tag_value,
+    writers::spdx_to_tag_value,
+};
+
+/// Path for reading from stdin or writing to stdout.
+pub const STDIO: &str = "-";
+
+/// Data formats of SPDX documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// SPDX JSON.
+    Json,
+    /// SPDX YAML.
+    Yaml,
+    /// SPDX tag-value.
+    TagValue,
+    /// `CycloneDX` 1.5 JSON.
+    CyclonedxJson,
+    /// `CycloneDX` 1.5 XML.
+    CyclonedxXml,
+}
+
+impl Format {
+    /// Guess the format from the file name, JSON if the name is not recognized.
+    pub fn from_path(path: &str) -> Self {
+        let path = Path::new(path);
+        let extension = |path: &Path| {
+            path.extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+        };
+        match extension(path).as_deref() {
+            Some("json") => match path.file_stem().and_then(|stem| extension(Path::new(stem))) {
+                Some(inner) if inner == "cdx" || inner == "bom" => Self::CyclonedxJson,
+                _ => Self::Json,
+            },
+            Some("xml") => Self::CyclonedxXml,
+            Some("yaml" | "yml") => Self::Yaml,
+            Some("spdx" | "tag" | "tv") => Self::TagValue,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Read a document. `-` reads from stdin. The format is guessed from the path if not given.
+pub fn read(path: &str, format: Option<Format>) -> Result<SPDX, Box<dyn Error>> {
+    let input = if path == STDIO {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        fs::read_to_string(Path::new(path)).map_err(|err| format!("{path}: {err}"))?
+    };
+
+    let spdx = match format.unwrap_or_else(|| Format::from_path(path)) {
+        Format::Json => serde_json::from_str(&input)?,
+        Format::Yaml => serde_yaml::from_str(&input)?,
+        Format::TagValue => spdx_from_tag_value(&input)?,
+        Format::CyclonedxJson => report_conversion(path, Bom::from_json(&input)?.to_spdx()),
+        Format::CyclonedxXml => report_conversion(path, Bom::from_xml(&input)?.to_spdx()),
+    };
+    Ok(spdx)
+}
+
+/// Write a document. `-` writes to stdout. The format is guessed from the path if not given.
+pub fn write(spdx: &SPDX, path: &str, format: Option<Format>) -> Result<(), Box<dyn Error>> {
+    let output = match format.unwrap_or_else(|| Format::from_path(path)) {
+        Format::Json => serde_json::to_string_pretty(spdx)?,
+        Format::Yaml => serde_yaml::to_string(spdx)?,
+        Format::TagValue => spdx_to_tag_value(spdx),
+        Format::CyclonedxJson => {
+            report_conversion(path, Bom::from_spdx(spdx, CycloneDxVersion::V1_5)).to_json()?
+        }
+        Format::CyclonedxXml => {
+            report_conversion(path, Bom::from_spdx(spdx, CycloneDxVersion::V1_5)).to_xml()?
+        }
+    };
+
+    if path == STDIO {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(output.as_bytes())?;
+        if !output.ends_with('\n') {
+            stdout.write_all(b"\n")?;
+        }
+    } else {
+        fs::write(path, output).map_err(|err| format!("{path}: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Print the fields that were lost in a conversion to stderr.
+fn report_conversion<T>(path: &str, (converted, report): (T, ConversionReport)) -> T {
+    for field in &report.unrepresented {
+        eprintln!(
+            "{path}: {} of {} can't be converted: {}",
+            field.field, field.element, field.value
+        );
+    }
+    converted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_is_guessed_from_path() {
+        assert_eq!(Format::from_path("sbom.spdx.json"), Format::Json);
+        assert_eq!(Format::from_path("sbom.cdx.json"), Format::CyclonedxJson);
+        assert_eq!(Format::from_path("bom.xml"), Format::CyclonedxXml);
+        assert_eq!(Format::from_path("sbom.spdx.yml"), Format::Yaml);
+        assert_eq!(Format::from_path("SBOM.SPDX"), Format::TagValue);
+        assert_eq!(Format::from_path(STDIO), Format::Json);
+    }
+}
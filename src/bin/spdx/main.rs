@@ -0,0 +1,280 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Command-line tool for working with SPDX documents.
+//!
+//! Exit codes: 0 on success, 1 if a check failed (the document is invalid, the documents differ
+//! or the query matched nothing) and 2 on errors.
+
+#![warn(clippy::all, clippy::pedantic, clippy::nursery, clippy::cargo)]
+#![allow(clippy::module_name_repetitions, clippy::multiple_crate_versions)]
+
+use std::{error::Error, process::ExitCode};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::{json, Value};
+use spdx_rs::{
+    models::SPDX,
+    query::{Query, QueryMatch},
+};
+
+use crate::format::Format;
+
+mod format;
+
+#[derive(Debug, Parser)]
+#[command(name = "spdx", version, about = "Work with SPDX documents")]
+struct Cli {
+    /// Print the results as JSON.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Convert a document to another format.
+    Convert {
+        /// Input document, `-` for stdin.
+        input: String,
+
+        /// Output document, `-` for stdout.
+        output: String,
+
+        /// Format of the input, guessed from the file name by default.
+        #[arg(long, value_enum)]
+        from: Option<Format>,
+
+        /// Format of the output, guessed from the file name by default.
+        #[arg(long, value_enum)]
+        to: Option<Format>,
+
+        /// Sort the elements of the output to a canonical order.
+        #[arg(long)]
+        canonical: bool,
+    },
+
+    /// Check the structure and the license identifiers of a document.
+    Validate {
+        input: String,
+
+        #[arg(long, value_enum)]
+        from: Option<Format>,
+    },
+
+    /// Show the differences between two documents.
+    Diff {
+        old: String,
+        new: String,
+
+        #[arg(long, value_enum)]
+        from: Option<Format>,
+    },
+
+    /// Merge documents into the first one.
+    Merge {
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<String>,
+
+        /// Output document, `-` for stdout.
+        #[arg(short, long)]
+        output: String,
+
+        /// Name of the merged document, the name of the first document by default.
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(long, value_enum)]
+        from: Option<Format>,
+
+        #[arg(long, value_enum)]
+        to: Option<Format>,
+
+        /// Sort the elements of the output to a canonical order.
+        #[arg(long)]
+        canonical: bool,
+    },
+
+    /// Show statistics of a document.
+    Summary {
+        input: String,
+
+        #[arg(long, value_enum)]
+        from: Option<Format>,
+    },
+
+    /// Find packages, files and snippets of a document with a query, e.g.
+    /// `packages where license contains "GPL-*" select name, versionInfo`.
+    Query {
+        input: String,
+
+        query: String,
+
+        #[arg(long, value_enum)]
+        from: Option<Format>,
+    },
+}
+
+/// Outcome of a command that succeeded.
+enum Outcome {
+    Passed,
+    Failed,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(Outcome::Passed) => ExitCode::SUCCESS,
+        Ok(Outcome::Failed) => ExitCode::from(1),
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<Outcome, Box<dyn Error>> {
+    let json = cli.json;
+    match cli.command {
+        Command::Convert {
+            input,
+            output,
+            from,
+            to,
+            canonical,
+        } => {
+            let mut spdx = format::read(&input, from)?;
+            if canonical {
+                spdx.canonicalize();
+            }
+            format::write(&spdx, &output, to)?;
+            Ok(Outcome::Passed)
+        }
+        Command::Validate { input, from } => validate(&format::read(&input, from)?, json),
+        Command::Diff { old, new, from } => {
+            let diff = format::read(&old, from)?.diff(&format::read(&new, from)?);
+            if json {
+                print_json(&diff)?;
+            } else {
+                print!("{diff}");
+            }
+            Ok(if diff.is_empty() {
+                Outcome::Passed
+            } else {
+                Outcome::Failed
+            })
+        }
+        Command::Merge {
+            inputs,
+            output,
+            name,
+            from,
+            to,
+            canonical,
+        } => {
+            let mut spdx = format::read(&inputs[0], from)?;
+            for input in &inputs[1..] {
+                let report = spdx.merge(&format::read(input, from)?);
+                for renamed in &report.renamed {
+                    eprintln!("{input}: renamed {} to {}", renamed.from, renamed.to);
+                }
+            }
+            if let Some(name) = name {
+                spdx.document_creation_information.document_name = name;
+            }
+            if canonical {
+                spdx.canonicalize();
+            }
+            format::write(&spdx, &output, to)?;
+            Ok(Outcome::Passed)
+        }
+        Command::Summary { input, from } => {
+            let summary = format::read(&input, from)?.summary();
+            if json {
+                print_json(&summary)?;
+            } else {
+                print!("{summary}");
+            }
+            Ok(Outcome::Passed)
+        }
+        Command::Query { input, query, from } => {
+            let query = Query::parse(&query)?;
+            let matches = query.evaluate(&format::read(&input, from)?);
+            print_matches(&query, &matches, json)?;
+            Ok(if matches.is_empty() {
+                Outcome::Failed
+            } else {
+                Outcome::Passed
+            })
+        }
+    }
+}
+
+fn validate(spdx: &SPDX, json: bool) -> Result<Outcome, Box<dyn Error>> {
+    let errors = spdx.validate();
+    #[cfg(feature = "license-list")]
+    let license_problems = spdx_rs::licenses::LicenseList::bundled().validate(spdx);
+    #[cfg(not(feature = "license-list"))]
+    let license_problems: Vec<Value> = Vec::new();
+
+    if json {
+        print_json(&json!({
+            "valid": errors.is_empty() && license_problems.is_empty(),
+            "errors": errors,
+            "licenseProblems": license_problems,
+        }))?;
+    } else {
+        for error in &errors {
+            println!("{error}");
+        }
+        #[cfg(feature = "license-list")]
+        for problem in &license_problems {
+            println!("{problem}");
+        }
+    }
+
+    Ok(if errors.is_empty() && license_problems.is_empty() {
+        Outcome::Passed
+    } else {
+        Outcome::Failed
+    })
+}
+
+/// Print the type and ID of each element, followed by the selected fields or the name.
+fn print_matches(query: &Query, matches: &[QueryMatch], json: bool) -> Result<(), Box<dyn Error>> {
+    if json {
+        return print_json(&matches);
+    }
+    for found in matches {
+        let element_type = serde_json::to_value(found.element_type)?;
+        print!(
+            "{} {}",
+            element_type.as_str().unwrap_or_default(),
+            found.spdx_id
+        );
+        if query.projection().is_empty() {
+            let name = found
+                .values
+                .get("name")
+                .or_else(|| found.values.get("fileName"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            println!(" {name}");
+        } else {
+            for (field, value) in &found.values {
+                print!(" {field}={value}");
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
@@ -0,0 +1,347 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Building SPDX documents with the relationships between the elements kept in sync.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use log::info;
+use uuid::Uuid;
+
+use crate::{
+    error::SpdxError,
+    models::{
+        sanitize_spdx_id, FileInformation, PackageInformation, Relationship, RelationshipType,
+        Snippet, SPDX,
+    },
+};
+
+/// Builder for an [`SPDX`] document.
+///
+/// The builder allocates the SPDX IDs of the added elements, so the IDs of the given packages,
+/// files and snippets are replaced. By default the IDs are numbered like the ones created by
+/// [`PackageInformation::new`] and [`FileInformation::new`]; with
+/// [`SpdxBuilder::ids_from_names`] they are derived from the names of the elements.
+///
+/// # Example
+///
+/// ```
+/// use spdx_rs::{
+///     builder::SpdxBuilder,
+///     models::{FileInformation, PackageInformation},
+/// };
+///
+/// let mut builder = SpdxBuilder::new("example").ids_from_names();
+/// let app = builder.add_package(PackageInformation::new("app", &mut 0));
+/// let lib = builder.add_package(PackageInformation::new("lib", &mut 0));
+/// builder.add_file_to(&app, FileInformation::new("./src/main.rs", &mut 0));
+/// builder.describes(&app).depends_on(&app, &lib);
+///
+/// let spdx = builder.build().unwrap();
+/// assert_eq!(spdx.package_information[0].files, vec!["SPDXRef-File-src-main.rs"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpdxBuilder {
+    spdx: SPDX,
+    ids: HashSet<String>,
+    ids_from_names: bool,
+}
+
+impl SpdxBuilder {
+    /// Start an SPDX 2.3 document created by this library.
+    pub fn new(document_name: &str) -> Self {
+        let mut spdx = SPDX::new(document_name);
+        let document = &mut spdx.document_creation_information;
+        document.spdx_version = "SPDX-2.3".to_string();
+        document.creation_info.creators = vec![format!(
+            "Tool: {}-{}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        )];
+
+        let ids = std::iter::once(document.spdx_identifier.clone()).collect();
+        Self {
+            spdx,
+            ids,
+            ids_from_names: false,
+        }
+    }
+
+    /// Derive the SPDX IDs from the names of the elements, e.g. `SPDXRef-Package-serde` for a
+    /// package named `serde`. Colliding IDs get a numeric suffix.
+    #[must_use]
+    pub fn ids_from_names(mut self) -> Self {
+        self.ids_from_names = true;
+        self
+    }
+
+    /// Add a creator of the document, e.g. `Person: Jane Doe`.
+    #[must_use]
+    pub fn creator(mut self, creator: &str) -> Self {
+        self.spdx
+            .document_creation_information
+            .creation_info
+            .creators
+            .push(creator.to_string());
+        self
+    }
+
+    /// Use the UUID in the document namespace instead of a random one, for reproducible
+    /// documents.
+    #[must_use]
+    pub fn namespace_uuid(mut self, uuid: Uuid) -> Self {
+        self.spdx.set_namespace_uuid(uuid);
+        self
+    }
+
+    /// Set the creation time of the document, the current time by default.
+    #[must_use]
+    pub fn created(mut self, created: DateTime<Utc>) -> Self {
+        self.spdx
+            .document_creation_information
+            .creation_info
+            .created = created;
+        self
+    }
+
+    /// Add a package. Returns the SPDX ID of the package.
+    pub fn add_package(&mut self, mut package: PackageInformation) -> String {
+        let id = self.allocate_id("Package", &package.package_name);
+        package.package_spdx_identifier.clone_from(&id);
+        package.files.clear();
+        self.spdx.package_information.push(package);
+        id
+    }
+
+    /// Add a file that doesn't belong to a package. Returns the SPDX ID of the file.
+    pub fn add_file(&mut self, mut file: FileInformation) -> String {
+        let id = self.allocate_id("File", file.file_name.trim_start_matches("./"));
+        file.file_spdx_identifier.clone_from(&id);
+        self.spdx.file_information.push(file);
+        id
+    }
+
+    /// Add a file to the package with the given SPDX ID. The file is added to the files of the
+    /// package and the package `CONTAINS` the file. Returns the SPDX ID of the file.
+    pub fn add_file_to(&mut self, package_id: &str, file: FileInformation) -> String {
+        let id = self.add_file(file);
+        if let Some(package) = self
+            .spdx
+            .package_information
+            .iter_mut()
+            .find(|package| package.package_spdx_identifier == package_id)
+        {
+            package.files.push(id.clone());
+        }
+        self.relationship(package_id, RelationshipType::Contains, &id);
+        id
+    }
+
+    /// Add a snippet from the file with the given SPDX ID. Returns the SPDX ID of the snippet.
+    pub fn add_snippet(&mut self, file_id: &str, mut snippet: Snippet) -> String {
+        let name = snippet
+            .snippet_name
+            .clone()
+            .unwrap_or_else(|| file_id.trim_start_matches("SPDXRef-").to_string());
+        let id = self.allocate_id("Snippet", &name);
+        snippet.snippet_spdx_identifier.clone_from(&id);
+        snippet.snippet_from_file_spdx_identifier = file_id.to_string();
+        for range in &mut snippet.ranges {
+            range.start_pointer.set_reference(file_id);
+            range.end_pointer.set_reference(file_id);
+        }
+        self.spdx.snippet_information.push(snippet);
+        id
+    }
+
+    /// The document describes the element with the given SPDX ID.
+    pub fn describes(&mut self, id: &str) -> &mut Self {
+        let document = &mut self.spdx.document_creation_information;
+        if !document
+            .document_describes
+            .iter()
+            .any(|described| described == id)
+        {
+            document.document_describes.push(id.to_string());
+        }
+        let document_id = document.spdx_identifier.clone();
+        self.relationship(&document_id, RelationshipType::Describes, id)
+    }
+
+    /// Element `a` depends on element `b`.
+    pub fn depends_on(&mut self, a: &str, b: &str) -> &mut Self {
+        self.relationship(a, RelationshipType::DependsOn, b)
+    }
+
+    /// Add a relationship between two elements. Duplicate relationships are ignored.
+    pub fn relationship(
+        &mut self,
+        spdx_element_id: &str,
+        relationship_type: RelationshipType,
+        related_spdx_element: &str,
+    ) -> &mut Self {
+        let relationship = Relationship::new(
+            spdx_element_id,
+            related_spdx_element,
+            relationship_type,
+            None,
+        );
+        if !self.spdx.relationships.contains(&relationship) {
+            self.spdx.relationships.push(relationship);
+        }
+        self
+    }
+
+    /// Validate and return the document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Validation`] with the problems found by [`SPDX::validate`], e.g. if
+    /// a relationship refers to an element that was not added.
+    pub fn build(self) -> Result<SPDX, SpdxError> {
+        info!("Building SPDX.");
+        let errors = self.spdx.validate();
+        if errors.is_empty() {
+            Ok(self.spdx)
+        } else {
+            Err(SpdxError::Validation(errors))
+        }
+    }
+
+    fn allocate_id(&mut self, kind: &str, name: &str) -> String {
+        let mut id = String::new();
+        if self.ids_from_names {
+            let base = format!("SPDXRef-{kind}-{}", sanitize_spdx_id(name));
+            let mut counter = 1;
+            id.clone_from(&base);
+            while self.ids.contains(&id) {
+                counter += 1;
+                id = format!("{base}-{counter}");
+            }
+        } else {
+            while id.is_empty() || self.ids.contains(&id) {
+                self.spdx.spdx_ref_counter += 1;
+                id = format!("SPDXRef-{}", self.spdx.spdx_ref_counter);
+            }
+        }
+        self.ids.insert(id.clone());
+        id
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use crate::models::{Pointer, Range};
+
+    use super::*;
+
+    #[test]
+    fn ids_are_numbered() {
+        let mut builder = SpdxBuilder::new("test");
+        let package = builder.add_package(PackageInformation::new("package", &mut 5));
+        let file = builder.add_file_to(&package, FileInformation::new("./file", &mut 0));
+        builder.describes(&package);
+
+        assert_eq!(package, "SPDXRef-1");
+        assert_eq!(file, "SPDXRef-2");
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn reproducible_documents_are_identical() {
+        let build = |names: &[&str]| {
+            let mut builder = SpdxBuilder::new("test")
+                .ids_from_names()
+                .namespace_uuid(Uuid::nil())
+                .created(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+            for name in names {
+                let id = builder.add_package(PackageInformation::new(name, &mut 0));
+                builder.describes(&id);
+            }
+            let mut spdx = builder.build().unwrap();
+            spdx.canonicalize();
+            serde_json::to_string(&spdx).unwrap()
+        };
+
+        let written = build(&["app", "lib"]);
+        assert_eq!(written, build(&["lib", "app"]));
+        assert!(written.contains(
+            "\"documentNamespace\":\"http://spdx.org/spdxdocs/test-00000000-0000-0000-0000-000000000000\""
+        ));
+        assert!(written.contains("\"created\":\"2024-01-01T00:00:00Z\""));
+    }
+
+    #[test]
+    fn ids_from_names_are_unique() {
+        let mut builder = SpdxBuilder::new("test").ids_from_names();
+        let first = builder.add_package(PackageInformation::new("serde json", &mut 0));
+        let second = builder.add_package(PackageInformation::new("serde_json", &mut 0));
+        let file = builder.add_file(FileInformation::new("./serde_json", &mut 0));
+
+        assert_eq!(first, "SPDXRef-Package-serde-json");
+        assert_eq!(second, "SPDXRef-Package-serde-json-2");
+        assert_eq!(file, "SPDXRef-File-serde-json");
+    }
+
+    #[test]
+    fn relationships_are_kept_in_sync() {
+        let mut builder = SpdxBuilder::new("test").ids_from_names();
+        let app = builder.add_package(PackageInformation::new("app", &mut 0));
+        let lib = builder.add_package(PackageInformation::new("lib", &mut 0));
+        let file = builder.add_file_to(&app, FileInformation::new("./main.c", &mut 0));
+        let snippet = builder.add_snippet(
+            &file,
+            Snippet {
+                ranges: vec![Range::new(
+                    Pointer::new_line(None, 1),
+                    Pointer::new_line(None, 10),
+                )],
+                ..Snippet::default()
+            },
+        );
+        builder
+            .describes(&app)
+            .describes(&app)
+            .depends_on(&app, &lib);
+        let spdx = builder.build().unwrap();
+
+        assert_eq!(
+            spdx.document_creation_information.document_describes,
+            vec![app.clone()]
+        );
+        assert_eq!(spdx.package_information[0].files, vec![file.clone()]);
+        assert_eq!(
+            spdx.relationships,
+            vec![
+                Relationship::new(&app, &file, RelationshipType::Contains, None),
+                Relationship::new("SPDXRef-DOCUMENT", &app, RelationshipType::Describes, None),
+                Relationship::new(&app, &lib, RelationshipType::DependsOn, None),
+            ]
+        );
+        assert_eq!(snippet, "SPDXRef-Snippet-File-main.c");
+        assert_eq!(
+            spdx.snippet_information[0].ranges[0].start_pointer,
+            Pointer::new_line(Some(file), 1)
+        );
+    }
+
+    #[test]
+    fn invalid_document_is_an_error() {
+        let mut builder = SpdxBuilder::new("test");
+        let package = builder.add_package(PackageInformation::new("package", &mut 0));
+        builder
+            .describes(&package)
+            .depends_on(&package, "SPDXRef-Missing");
+
+        let errors = match builder.build() {
+            Err(SpdxError::Validation(errors)) => errors,
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].spdx_id, package);
+    }
+}
@@ -0,0 +1,580 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Memory-efficient representation of SPDX documents.
+//!
+//! Large documents repeat the same strings over and over: SPDX IDs appear in packages, in the
+//! `files` of packages and in relationships, and license expressions and copyright texts are
+//! often identical for thousands of files. [`CompactSpdx`] stores packages, files and
+//! relationships with shared [`Arc<str>`] strings and [`Arc<SpdxExpression>`] license
+//! expressions, so each distinct value is kept in memory only once.
+//!
+//! The document can be converted to and from [`SPDX`], and it implements [`SpdxVisitor`], so it
+//! can be loaded with the streaming parsers without ever holding the owned document.
+//!
+//! # Usage
+//!
+//! ```
+//! # use spdx_rs::error::SpdxError;
+//! use std::{fs::File, io::BufReader};
+//!
+//! use spdx_rs::{compact::CompactSpdx, models::SPDX, parsers::visit_spdx_json};
+//! # fn main() -> Result<(), SpdxError> {
+//!
+//! let reader = BufReader::new(File::open("tests/data/SPDXJSONExample-v2.3.spdx.json")?);
+//! let mut compact = CompactSpdx::default();
+//! visit_spdx_json(reader, &mut compact)?;
+//!
+//! let package = compact.package("SPDXRef-Saxon").unwrap();
+//! assert_eq!(package.package_version.as_deref(), Some("8.8"));
+//!
+//! let spdx: SPDX = compact.into();
+//! assert_eq!(spdx.package_information.len(), 4);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use log::info;
+use serde_json::Value;
+
+use crate::{
+    error::SpdxError,
+    models::{
+        Annotation, Checksum, DocumentCreationInformation, ExternalPackageReference,
+        FileInformation, FileType, OtherLicensingInformationDetected, PackageInformation,
+        PackageVerificationCode, PrimaryPackagePurpose, Relationship, RelationshipType, Snippet,
+        SpdxExpression, SPDX,
+    },
+    parsers::SpdxVisitor,
+};
+
+/// Deduplicates strings and license expressions.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    strings: HashSet<Arc<str>>,
+
+    /// License expressions by their string representation.
+    expressions: HashMap<String, Arc<SpdxExpression>>,
+}
+
+impl Interner {
+    /// Get the shared copy of the string.
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(value) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(value);
+        self.strings.insert(Arc::clone(&interned));
+        interned
+    }
+
+    /// Get the shared copy of the license expression.
+    pub fn intern_expression(&mut self, expression: SpdxExpression) -> Arc<SpdxExpression> {
+        let key = expression.to_string();
+        match self.expressions.get(&key) {
+            Some(interned) if **interned == expression => Arc::clone(interned),
+            // Expressions that are written the same but parsed differently are not shared.
+            Some(_) => Arc::new(expression),
+            None => {
+                let interned = Arc::new(expression);
+                self.expressions.insert(key, Arc::clone(&interned));
+                interned
+            }
+        }
+    }
+
+    /// Number of distinct strings and license expressions.
+    pub fn len(&self) -> usize {
+        self.strings.len() + self.expressions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty() && self.expressions.is_empty()
+    }
+}
+
+/// Values that are stored with shared strings in the compact representation.
+trait Share: Sized {
+    type Shared;
+
+    fn share(self, interner: &mut Interner) -> Self::Shared;
+
+    fn unshare(shared: &Self::Shared) -> Self;
+}
+
+impl Share for String {
+    type Shared = Arc<str>;
+
+    fn share(self, interner: &mut Interner) -> Self::Shared {
+        interner.intern(&self)
+    }
+
+    fn unshare(shared: &Self::Shared) -> Self {
+        shared.to_string()
+    }
+}
+
+impl Share for SpdxExpression {
+    type Shared = Arc<SpdxExpression>;
+
+    fn share(self, interner: &mut Interner) -> Self::Shared {
+        interner.intern_expression(self)
+    }
+
+    fn unshare(shared: &Self::Shared) -> Self {
+        Self::clone(shared)
+    }
+}
+
+impl<T: Share> Share for Option<T> {
+    type Shared = Option<T::Shared>;
+
+    fn share(self, interner: &mut Interner) -> Self::Shared {
+        self.map(|value| value.share(interner))
+    }
+
+    fn unshare(shared: &Self::Shared) -> Self {
+        shared.as_ref().map(T::unshare)
+    }
+}
+
+impl<T: Share> Share for Vec<T> {
+    type Shared = Vec<T::Shared>;
+
+    fn share(self, interner: &mut Interner) -> Self::Shared {
+        self.into_iter()
+            .map(|value| value.share(interner))
+            .collect()
+    }
+
+    fn unshare(shared: &Self::Shared) -> Self {
+        shared.iter().map(T::unshare).collect()
+    }
+}
+
+fn unshare<T: Share>(shared: &T::Shared) -> T {
+    T::unshare(shared)
+}
+
+/// [`PackageInformation`] with shared strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactPackage {
+    pub package_name: Arc<str>,
+    pub package_spdx_identifier: Arc<str>,
+    pub package_version: Option<Arc<str>>,
+    pub package_file_name: Option<Arc<str>>,
+    pub package_supplier: Option<Arc<str>>,
+    pub package_originator: Option<Arc<str>>,
+    pub package_download_location: Arc<str>,
+    pub files_analyzed: Option<bool>,
+    pub package_verification_code: Option<PackageVerificationCode>,
+    pub package_checksum: Vec<Checksum>,
+    pub package_home_page: Option<Arc<str>>,
+    pub source_information: Option<Arc<str>>,
+    pub concluded_license: Option<Arc<SpdxExpression>>,
+    pub all_licenses_information_from_files: Vec<Arc<str>>,
+    pub declared_license: Option<Arc<SpdxExpression>>,
+    pub comments_on_license: Option<Arc<str>>,
+    pub copyright_text: Option<Arc<str>>,
+    pub package_summary_description: Option<Arc<str>>,
+    pub package_detailed_description: Option<Arc<str>>,
+    pub package_comment: Option<Arc<str>>,
+    pub external_reference: Vec<ExternalPackageReference>,
+    pub package_attribution_text: Vec<Arc<str>>,
+    pub files: Vec<Arc<str>>,
+    pub annotations: Vec<Annotation>,
+    pub built_date: Option<Arc<str>>,
+    pub release_date: Option<Arc<str>>,
+    pub valid_until_date: Option<Arc<str>>,
+    pub primary_package_purpose: Option<PrimaryPackagePurpose>,
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl CompactPackage {
+    pub fn new(package: PackageInformation, interner: &mut Interner) -> Self {
+        Self {
+            package_name: package.package_name.share(interner),
+            package_spdx_identifier: package.package_spdx_identifier.share(interner),
+            package_version: package.package_version.share(interner),
+            package_file_name: package.package_file_name.share(interner),
+            package_supplier: package.package_supplier.share(interner),
+            package_originator: package.package_originator.share(interner),
+            package_download_location: package.package_download_location.share(interner),
+            files_analyzed: package.files_analyzed,
+            package_verification_code: package.package_verification_code,
+            package_checksum: package.package_checksum,
+            package_home_page: package.package_home_page.share(interner),
+            source_information: package.source_information.share(interner),
+            concluded_license: package.concluded_license.share(interner),
+            all_licenses_information_from_files: package
+                .all_licenses_information_from_files
+                .share(interner),
+            declared_license: package.declared_license.share(interner),
+            comments_on_license: package.comments_on_license.share(interner),
+            copyright_text: package.copyright_text.share(interner),
+            package_summary_description: package.package_summary_description.share(interner),
+            package_detailed_description: package.package_detailed_description.share(interner),
+            package_comment: package.package_comment.share(interner),
+            external_reference: package.external_reference,
+            package_attribution_text: package.package_attribution_text.share(interner),
+            files: package.files.share(interner),
+            annotations: package.annotations,
+            built_date: package.built_date.share(interner),
+            release_date: package.release_date.share(interner),
+            valid_until_date: package.valid_until_date.share(interner),
+            primary_package_purpose: package.primary_package_purpose,
+            extensions: package.extensions,
+        }
+    }
+}
+
+impl From<&CompactPackage> for PackageInformation {
+    fn from(package: &CompactPackage) -> Self {
+        Self {
+            package_name: unshare(&package.package_name),
+            package_spdx_identifier: unshare(&package.package_spdx_identifier),
+            package_version: unshare(&package.package_version),
+            package_file_name: unshare(&package.package_file_name),
+            package_supplier: unshare(&package.package_supplier),
+            package_originator: unshare(&package.package_originator),
+            package_download_location: unshare(&package.package_download_location),
+            files_analyzed: package.files_analyzed,
+            package_verification_code: package.package_verification_code.clone(),
+            package_checksum: package.package_checksum.clone(),
+            package_home_page: unshare(&package.package_home_page),
+            source_information: unshare(&package.source_information),
+            concluded_license: unshare(&package.concluded_license),
+            all_licenses_information_from_files: unshare(
+                &package.all_licenses_information_from_files,
+            ),
+            declared_license: unshare(&package.declared_license),
+            comments_on_license: unshare(&package.comments_on_license),
+            copyright_text: unshare(&package.copyright_text),
+            package_summary_description: unshare(&package.package_summary_description),
+            package_detailed_description: unshare(&package.package_detailed_description),
+            package_comment: unshare(&package.package_comment),
+            external_reference: package.external_reference.clone(),
+            package_attribution_text: unshare(&package.package_attribution_text),
+            files: unshare(&package.files),
+            annotations: package.annotations.clone(),
+            built_date: unshare(&package.built_date),
+            release_date: unshare(&package.release_date),
+            valid_until_date: unshare(&package.valid_until_date),
+            primary_package_purpose: package.primary_package_purpose,
+            extensions: package.extensions.clone(),
+        }
+    }
+}
+
+/// [`FileInformation`] with shared strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactFile {
+    pub file_name: Arc<str>,
+    pub file_spdx_identifier: Arc<str>,
+    pub file_type: Vec<FileType>,
+    pub file_checksum: Vec<Checksum>,
+    pub concluded_license: Option<Arc<SpdxExpression>>,
+    pub license_information_in_file: Vec<Arc<SpdxExpression>>,
+    pub comments_on_license: Option<Arc<str>>,
+    pub copyright_text: Option<Arc<str>>,
+    pub file_comment: Option<Arc<str>>,
+    pub file_notice: Option<Arc<str>>,
+    pub file_contributor: Vec<Arc<str>>,
+    pub file_attribution_text: Option<Vec<Arc<str>>>,
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl CompactFile {
+    pub fn new(file: FileInformation, interner: &mut Interner) -> Self {
+        Self {
+            file_name: file.file_name.share(interner),
+            file_spdx_identifier: file.file_spdx_identifier.share(interner),
+            file_type: file.file_type,
+            file_checksum: file.file_checksum,
+            concluded_license: file.concluded_license.share(interner),
+            license_information_in_file: file.license_information_in_file.share(interner),
+            comments_on_license: file.comments_on_license.share(interner),
+            copyright_text: file.copyright_text.share(interner),
+            file_comment: file.file_comment.share(interner),
+            file_notice: file.file_notice.share(interner),
+            file_contributor: file.file_contributor.share(interner),
+            file_attribution_text: file.file_attribution_text.share(interner),
+            extensions: file.extensions,
+        }
+    }
+}
+
+impl From<&CompactFile> for FileInformation {
+    fn from(file: &CompactFile) -> Self {
+        Self {
+            file_name: unshare(&file.file_name),
+            file_spdx_identifier: unshare(&file.file_spdx_identifier),
+            file_type: file.file_type.clone(),
+            file_checksum: file.file_checksum.clone(),
+            concluded_license: unshare(&file.concluded_license),
+            license_information_in_file: unshare(&file.license_information_in_file),
+            comments_on_license: unshare(&file.comments_on_license),
+            copyright_text: unshare(&file.copyright_text),
+            file_comment: unshare(&file.file_comment),
+            file_notice: unshare(&file.file_notice),
+            file_contributor: unshare(&file.file_contributor),
+            file_attribution_text: unshare(&file.file_attribution_text),
+            extensions: file.extensions.clone(),
+        }
+    }
+}
+
+/// [`Relationship`] with shared strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactRelationship {
+    pub spdx_element_id: Arc<str>,
+    pub related_spdx_element: Arc<str>,
+    pub relationship_type: RelationshipType,
+    pub comment: Option<Arc<str>>,
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl CompactRelationship {
+    pub fn new(relationship: Relationship, interner: &mut Interner) -> Self {
+        Self {
+            spdx_element_id: relationship.spdx_element_id.share(interner),
+            related_spdx_element: relationship.related_spdx_element.share(interner),
+            relationship_type: relationship.relationship_type,
+            comment: relationship.comment.share(interner),
+            extensions: relationship.extensions,
+        }
+    }
+}
+
+impl From<&CompactRelationship> for Relationship {
+    fn from(relationship: &CompactRelationship) -> Self {
+        Self {
+            spdx_element_id: unshare(&relationship.spdx_element_id),
+            related_spdx_element: unshare(&relationship.related_spdx_element),
+            relationship_type: relationship.relationship_type.clone(),
+            comment: unshare(&relationship.comment),
+            extensions: relationship.extensions.clone(),
+        }
+    }
+}
+
+/// An SPDX document that shares the repeated strings of its packages, files and relationships.
+///
+/// Snippets, other licensing information and annotations are rare compared to files and are
+/// kept as they are.
+#[derive(Debug, Clone, Default)]
+pub struct CompactSpdx {
+    pub document_creation_information: DocumentCreationInformation,
+    pub package_information: Vec<CompactPackage>,
+    pub other_licensing_information_detected: Vec<OtherLicensingInformationDetected>,
+    pub file_information: Vec<CompactFile>,
+    pub snippet_information: Vec<Snippet>,
+    pub relationships: Vec<CompactRelationship>,
+    pub annotations: Vec<Annotation>,
+    interner: Interner,
+}
+
+impl CompactSpdx {
+    /// Interner shared by the elements of the document. Use it to create new elements.
+    pub fn interner(&mut self) -> &mut Interner {
+        &mut self.interner
+    }
+
+    /// Add a package, sharing its strings with the document.
+    pub fn push_package(&mut self, package: PackageInformation) {
+        let package = CompactPackage::new(package, &mut self.interner);
+        self.package_information.push(package);
+    }
+
+    /// Add a file, sharing its strings with the document.
+    pub fn push_file(&mut self, file: FileInformation) {
+        let file = CompactFile::new(file, &mut self.interner);
+        self.file_information.push(file);
+    }
+
+    /// Add a relationship, sharing its strings with the document.
+    pub fn push_relationship(&mut self, relationship: Relationship) {
+        let relationship = CompactRelationship::new(relationship, &mut self.interner);
+        self.relationships.push(relationship);
+    }
+
+    /// Find the package with the given SPDX ID.
+    pub fn package(&self, spdx_id: &str) -> Option<&CompactPackage> {
+        self.package_information
+            .iter()
+            .find(|package| &*package.package_spdx_identifier == spdx_id)
+    }
+
+    /// Find the file with the given SPDX ID.
+    pub fn file(&self, spdx_id: &str) -> Option<&CompactFile> {
+        self.file_information
+            .iter()
+            .find(|file| &*file.file_spdx_identifier == spdx_id)
+    }
+
+    /// Get all relationships where the given SPDX ID is the SPDX element id.
+    pub fn relationships_for_spdx_id(&self, spdx_id: &str) -> Vec<&CompactRelationship> {
+        self.relationships
+            .iter()
+            .filter(|relationship| &*relationship.spdx_element_id == spdx_id)
+            .collect()
+    }
+
+    /// Get all relationships where the given SPDX ID is the related SPDX element id.
+    pub fn relationships_for_related_spdx_id(&self, spdx_id: &str) -> Vec<&CompactRelationship> {
+        self.relationships
+            .iter()
+            .filter(|relationship| &*relationship.related_spdx_element == spdx_id)
+            .collect()
+    }
+
+    /// Convert to the owned representation.
+    pub fn to_spdx(&self) -> SPDX {
+        SPDX {
+            document_creation_information: self.document_creation_information.clone(),
+            package_information: self.package_information.iter().map(Into::into).collect(),
+            other_licensing_information_detected: self.other_licensing_information_detected.clone(),
+            file_information: self.file_information.iter().map(Into::into).collect(),
+            snippet_information: self.snippet_information.clone(),
+            relationships: self.relationships.iter().map(Into::into).collect(),
+            annotations: self.annotations.clone(),
+            spdx_ref_counter: 0,
+        }
+    }
+}
+
+impl From<SPDX> for CompactSpdx {
+    fn from(spdx: SPDX) -> Self {
+        info!("Converting SPDX to compact representation.");
+        let mut compact = Self {
+            document_creation_information: spdx.document_creation_information,
+            other_licensing_information_detected: spdx.other_licensing_information_detected,
+            snippet_information: spdx.snippet_information,
+            annotations: spdx.annotations,
+            ..Self::default()
+        };
+        for package in spdx.package_information {
+            compact.push_package(package);
+        }
+        for file in spdx.file_information {
+            compact.push_file(file);
+        }
+        for relationship in spdx.relationships {
+            compact.push_relationship(relationship);
+        }
+        compact
+    }
+}
+
+impl From<CompactSpdx> for SPDX {
+    fn from(compact: CompactSpdx) -> Self {
+        compact.to_spdx()
+    }
+}
+
+/// Collects the visited parts into the document, replacing its document creation information.
+impl SpdxVisitor for CompactSpdx {
+    fn document_creation_information(
+        &mut self,
+        document_creation_information: DocumentCreationInformation,
+    ) -> Result<(), SpdxError> {
+        self.document_creation_information = document_creation_information;
+        Ok(())
+    }
+
+    fn package(&mut self, package: PackageInformation) -> Result<(), SpdxError> {
+        self.push_package(package);
+        Ok(())
+    }
+
+    fn file(&mut self, file: FileInformation) -> Result<(), SpdxError> {
+        self.push_file(file);
+        Ok(())
+    }
+
+    fn snippet(&mut self, snippet: Snippet) -> Result<(), SpdxError> {
+        self.snippet_information.push(snippet);
+        Ok(())
+    }
+
+    fn other_licensing_information_detected(
+        &mut self,
+        license: OtherLicensingInformationDetected,
+    ) -> Result<(), SpdxError> {
+        self.other_licensing_information_detected.push(license);
+        Ok(())
+    }
+
+    fn relationship(&mut self, relationship: Relationship) -> Result<(), SpdxError> {
+        self.push_relationship(relationship);
+        Ok(())
+    }
+
+    fn annotation(&mut self, annotation: Annotation) -> Result<(), SpdxError> {
+        self.annotations.push(annotation);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+    use crate::parsers::{spdx_from_tag_value, visit_spdx_tag_value};
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn converts_to_and_from_spdx() {
+        let spdx = example();
+        let compact = CompactSpdx::from(spdx.clone());
+        assert_eq!(compact.to_spdx(), spdx);
+        assert_eq!(SPDX::from(compact), spdx);
+    }
+
+    #[test]
+    fn repeated_values_are_shared() {
+        let compact = CompactSpdx::from(example());
+        let package = compact.package("SPDXRef-Package").unwrap();
+        let relationship = compact.relationships_for_spdx_id("SPDXRef-Package")[0];
+        assert!(Arc::ptr_eq(
+            &package.package_spdx_identifier,
+            &relationship.spdx_element_id
+        ));
+
+        let mut interner = Interner::default();
+        let first = interner.intern_expression(SpdxExpression::parse("MIT OR Apache-2.0").unwrap());
+        let second =
+            interner.intern_expression(SpdxExpression::parse("MIT OR Apache-2.0").unwrap());
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(Arc::ptr_eq(
+            &interner.intern("MIT"),
+            &interner.intern("MIT")
+        ));
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn tag_value_documents_are_visited() {
+        let input = read_to_string("tests/data/SPDXTagExample-v2.2.spdx").unwrap();
+        let mut compact = CompactSpdx::default();
+        visit_spdx_tag_value(input.as_bytes(), &mut compact).unwrap();
+        let spdx = spdx_from_tag_value(&input).unwrap();
+        assert_eq!(
+            compact.to_spdx().package_information,
+            spdx.package_information
+        );
+        assert_eq!(compact.to_spdx().file_information, spdx.file_information);
+        assert!(compact.file("SPDXRef-DoapSource").is_some());
+    }
+}
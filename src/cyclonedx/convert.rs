@@ -0,0 +1,1415 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Mapping between [`SPDX`] documents and [`Bom`]s.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{
+    sanitize_spdx_id, Algorithm, Checksum, CreationInfo, ExternalPackageReference,
+    ExternalPackageReferenceCategory, FileInformation, OtherLicensingInformationDetected,
+    PackageInformation, PrimaryPackagePurpose, Relationship, RelationshipType, SpdxExpression,
+    SPDX,
+};
+
+use super::{
+    AttachedText, Bom, Component, ComponentType, ConversionReport, CycloneDxVersion, Dependency,
+    ExternalReference, Hash, License, LicenseChoice, Metadata, OrganizationalContact,
+    OrganizationalEntity, Tool,
+};
+
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+
+/// Checksum algorithms and the names `CycloneDX` uses for them.
+const HASH_ALGORITHMS: [(Algorithm, &str); 12] = [
+    (Algorithm::MD5, "MD5"),
+    (Algorithm::SHA1, "SHA-1"),
+    (Algorithm::SHA256, "SHA-256"),
+    (Algorithm::SHA384, "SHA-384"),
+    (Algorithm::SHA512, "SHA-512"),
+    (Algorithm::SHA3256, "SHA3-256"),
+    (Algorithm::SHA3384, "SHA3-384"),
+    (Algorithm::SHA3512, "SHA3-512"),
+    (Algorithm::BLAKE2B256, "BLAKE2b-256"),
+    (Algorithm::BLAKE2B384, "BLAKE2b-384"),
+    (Algorithm::BLAKE2B512, "BLAKE2b-512"),
+    (Algorithm::BLAKE3, "BLAKE3"),
+];
+
+/// Package purposes with a matching component type.
+const COMPONENT_TYPES: [(PrimaryPackagePurpose, ComponentType); 8] = [
+    (
+        PrimaryPackagePurpose::Application,
+        ComponentType::Application,
+    ),
+    (PrimaryPackagePurpose::Framework, ComponentType::Framework),
+    (PrimaryPackagePurpose::Library, ComponentType::Library),
+    (PrimaryPackagePurpose::Container, ComponentType::Container),
+    (
+        PrimaryPackagePurpose::OperatingSystem,
+        ComponentType::OperatingSystem,
+    ),
+    (PrimaryPackagePurpose::Device, ComponentType::Device),
+    (PrimaryPackagePurpose::Firmware, ComponentType::Firmware),
+    (PrimaryPackagePurpose::File, ComponentType::File),
+];
+
+/// Types of `CycloneDX` external references. SPDX references in the `OTHER` category with one of
+/// these types are converted to `CycloneDX` external references and back.
+const EXTERNAL_REFERENCE_TYPES: [&str; 16] = [
+    "vcs",
+    "issue-tracker",
+    "website",
+    "advisories",
+    "bom",
+    "mailing-list",
+    "social",
+    "chat",
+    "documentation",
+    "support",
+    "distribution",
+    "license",
+    "build-meta",
+    "build-system",
+    "release-notes",
+    "other",
+];
+
+impl Bom {
+    /// Convert an SPDX document to a BOM.
+    ///
+    /// Packages and files become components. Components are nested if the SPDX element has a
+    /// single containing package or file, and the package the document describes becomes the
+    /// component of the metadata. `DEPENDS_ON` and `DEPENDENCY_OF` relationships become the
+    /// dependency graph.
+    pub fn from_spdx(spdx: &SPDX, version: CycloneDxVersion) -> (Self, ConversionReport) {
+        let mut converter = SpdxToCycloneDx::new(spdx);
+
+        let mut bom = Self::new(version);
+        bom.serial_number = Some(format!("urn:uuid:{}", Uuid::new_v4()));
+        let mut metadata = converter.metadata();
+        let (metadata_component, components) = converter.components();
+        metadata.component = metadata_component.map(Box::new);
+        bom.metadata = Some(metadata);
+        bom.components = components;
+        bom.dependencies = converter.dependencies();
+        converter.report_document();
+
+        (bom, converter.report)
+    }
+
+    /// Convert the BOM to an SPDX 2.3 document.
+    ///
+    /// Components of type `file` become files, all others become packages. Nested components are
+    /// linked with `CONTAINS` relationships and the dependency graph with `DEPENDS_ON`
+    /// relationships. The component of the metadata, or all top level components if there is
+    /// none, are described by the document.
+    pub fn to_spdx(&self) -> (SPDX, ConversionReport) {
+        let mut converter = CycloneDxToSpdx::new(self);
+
+        let metadata_component = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.component.as_deref());
+        if let Some(component) = metadata_component {
+            let id = converter.add_component(component, None);
+            converter.describe(&id);
+        }
+        for component in &self.components {
+            let id = converter.add_component(component, None);
+            if metadata_component.is_none() {
+                converter.describe(&id);
+            }
+        }
+        converter.add_dependencies();
+
+        (converter.spdx, converter.report)
+    }
+}
+
+/// Value of an enum in the form used in SPDX and `CycloneDX` documents.
+fn serialized<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+/// Split `Jane Doe (jane@example.com)` to the name and the optional email.
+fn split_contact(value: &str) -> (String, Option<String>) {
+    match value
+        .trim()
+        .strip_suffix(')')
+        .and_then(|value| value.rsplit_once(" ("))
+    {
+        Some((name, email)) => (
+            name.trim().to_string(),
+            Some(email.trim().to_string()).filter(|email| !email.is_empty()),
+        ),
+        None => (value.trim().to_string(), None),
+    }
+}
+
+/// Returns `None` for `NOASSERTION` and `NONE`.
+fn meaningful(value: Option<&String>) -> Option<String> {
+    value
+        .filter(|value| *value != "NOASSERTION" && *value != "NONE")
+        .cloned()
+}
+
+#[derive(Clone, Copy)]
+enum SpdxElement<'a> {
+    Package(&'a PackageInformation),
+    File(&'a FileInformation),
+}
+
+struct SpdxToCycloneDx<'a> {
+    spdx: &'a SPDX,
+    report: ConversionReport,
+
+    /// SPDX IDs of packages and files in document order.
+    element_ids: Vec<&'a str>,
+    elements: HashMap<&'a str, SpdxElement<'a>>,
+
+    /// Elements that have a single containing element.
+    children: HashMap<&'a str, Vec<&'a str>>,
+    has_parent: HashSet<&'a str>,
+
+    visited: HashSet<&'a str>,
+    nested: HashSet<(&'a str, &'a str)>,
+    metadata_component: Option<&'a str>,
+    attached_licenses: HashSet<String>,
+}
+
+impl<'a> SpdxToCycloneDx<'a> {
+    fn new(spdx: &'a SPDX) -> Self {
+        let mut elements = HashMap::new();
+        let mut element_ids = Vec::new();
+        for package in &spdx.package_information {
+            element_ids.push(package.package_spdx_identifier.as_str());
+            elements.insert(
+                package.package_spdx_identifier.as_str(),
+                SpdxElement::Package(package),
+            );
+        }
+        for file in &spdx.file_information {
+            element_ids.push(file.file_spdx_identifier.as_str());
+            elements.insert(file.file_spdx_identifier.as_str(), SpdxElement::File(file));
+        }
+
+        let mut parents: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut add_parent = |parent: &'a str, child: &'a str| {
+            if parent != child && elements.contains_key(parent) && elements.contains_key(child) {
+                let parents = parents.entry(child).or_default();
+                if !parents.contains(&parent) {
+                    parents.push(parent);
+                }
+            }
+        };
+        for relationship in &spdx.relationships {
+            let (element, related) = (
+                relationship.spdx_element_id.as_str(),
+                relationship.related_spdx_element.as_str(),
+            );
+            match relationship.relationship_type {
+                RelationshipType::Contains => add_parent(element, related),
+                RelationshipType::ContainedBy => add_parent(related, element),
+                _ => {}
+            }
+        }
+        for package in &spdx.package_information {
+            for file in &package.files {
+                add_parent(&package.package_spdx_identifier, file);
+            }
+        }
+
+        let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut has_parent = HashSet::new();
+        for id in &element_ids {
+            if let Some([parent]) = parents.get(id).map(Vec::as_slice) {
+                children.entry(parent).or_default().push(id);
+                has_parent.insert(*id);
+            }
+        }
+
+        Self {
+            spdx,
+            report: ConversionReport::default(),
+            element_ids,
+            elements,
+            children,
+            has_parent,
+            visited: HashSet::new(),
+            nested: HashSet::new(),
+            metadata_component: None,
+            attached_licenses: HashSet::new(),
+        }
+    }
+
+    fn metadata(&mut self) -> Metadata {
+        let creation_info = &self.spdx.document_creation_information.creation_info;
+        let mut metadata = Metadata {
+            timestamp: Some(creation_info.created),
+            ..Metadata::default()
+        };
+
+        for creator in &creation_info.creators {
+            match creator.split_once(": ") {
+                Some(("Tool", tool)) => {
+                    let tool = tool.trim();
+                    let (name, version) = tool
+                        .rsplit_once('-')
+                        .filter(|(_, version)| version.starts_with(|c: char| c.is_ascii_digit()))
+                        .map_or((tool, None), |(name, version)| (name, Some(version)));
+                    metadata.tools.push(Tool {
+                        vendor: None,
+                        name: Some(name.to_string()),
+                        version: version.map(ToString::to_string),
+                    });
+                }
+                Some(("Person", person)) => {
+                    let (name, email) = split_contact(person);
+                    metadata.authors.push(OrganizationalContact {
+                        name: Some(name),
+                        email,
+                    });
+                }
+                Some(("Organization", organization)) if metadata.supplier.is_none() => {
+                    let (name, email) = split_contact(organization);
+                    if let Some(email) = email {
+                        self.report.add(DOCUMENT_ID, "creators", email);
+                    }
+                    metadata.supplier = Some(OrganizationalEntity { name: Some(name) });
+                }
+                _ => self.report.add(DOCUMENT_ID, "creators", creator.as_str()),
+            }
+        }
+
+        metadata
+    }
+
+    /// The elements the document describes.
+    fn described(&self) -> Vec<&'a str> {
+        let mut described: Vec<&str> = Vec::new();
+        let relationships = self.spdx.relationships.iter().filter_map(|relationship| {
+            match relationship.relationship_type {
+                RelationshipType::Describes if relationship.spdx_element_id == DOCUMENT_ID => {
+                    Some(relationship.related_spdx_element.as_str())
+                }
+                RelationshipType::DescribedBy
+                    if relationship.related_spdx_element == DOCUMENT_ID =>
+                {
+                    Some(relationship.spdx_element_id.as_str())
+                }
+                _ => None,
+            }
+        });
+        for id in self
+            .spdx
+            .document_creation_information
+            .document_describes
+            .iter()
+            .map(String::as_str)
+            .chain(relationships)
+        {
+            if !described.contains(&id) {
+                described.push(id);
+            }
+        }
+        described
+    }
+
+    /// The component of the metadata and the top level components.
+    fn components(&mut self) -> (Option<Component>, Vec<Component>) {
+        let metadata_component = match self.described().as_slice() {
+            [id] if self.elements.contains_key(id) => {
+                self.metadata_component = Some(id);
+                Some(self.component(id))
+            }
+            _ => None,
+        };
+
+        let mut components = Vec::new();
+        for id in self.element_ids.clone() {
+            if !self.has_parent.contains(id) && !self.visited.contains(id) {
+                components.push(self.component(id));
+            }
+        }
+        // Elements in containment cycles are not reachable from the top level.
+        for id in self.element_ids.clone() {
+            if !self.visited.contains(id) {
+                components.push(self.component(id));
+            }
+        }
+
+        (metadata_component, components)
+    }
+
+    fn component(&mut self, id: &'a str) -> Component {
+        self.visited.insert(id);
+        let mut component = match self.elements[id] {
+            SpdxElement::Package(package) => self.package_component(package),
+            SpdxElement::File(file) => self.file_component(file),
+        };
+
+        for child in self.children.get(id).cloned().unwrap_or_default() {
+            if !self.visited.contains(child) {
+                self.nested.insert((id, child));
+                component.components.push(self.component(child));
+            }
+        }
+        component
+    }
+
+    fn package_component(&mut self, package: &'a PackageInformation) -> Component {
+        let id = package.package_spdx_identifier.as_str();
+        let component_type =
+            package
+                .primary_package_purpose
+                .map_or(ComponentType::Library, |purpose| {
+                    COMPONENT_TYPES
+                        .iter()
+                        .find(|(spdx_purpose, _)| *spdx_purpose == purpose)
+                        .map_or_else(
+                            || {
+                                self.report
+                                    .add(id, "primaryPackagePurpose", serialized(&purpose));
+                                ComponentType::Library
+                            },
+                            |(_, component_type)| *component_type,
+                        )
+                });
+
+        let mut component = Component::new(component_type, &package.package_name);
+        component.bom_ref = Some(id.to_string());
+        component.version.clone_from(&package.package_version);
+        component.supplier = self
+            .actor(id, "supplier", package.package_supplier.as_ref())
+            .map(|name| OrganizationalEntity { name: Some(name) });
+        component.author = self.actor(id, "originator", package.package_originator.as_ref());
+        component.description = package
+            .package_detailed_description
+            .clone()
+            .or_else(|| package.package_summary_description.clone());
+        if package.package_detailed_description.is_some() {
+            if let Some(summary) = &package.package_summary_description {
+                self.report.add(id, "summary", summary.as_str());
+            }
+        }
+        component.hashes = self.hashes(id, &package.package_checksum);
+
+        // CycloneDX has a single license field, the declared license is what the package states.
+        component.licenses = self.licenses(
+            package
+                .declared_license
+                .as_ref()
+                .or(package.concluded_license.as_ref()),
+        );
+        if let (Some(declared), Some(concluded)) =
+            (&package.declared_license, &package.concluded_license)
+        {
+            if declared != concluded {
+                self.report
+                    .add(id, "licenseConcluded", concluded.to_string());
+            }
+        }
+        component.copyright = meaningful(package.copyright_text.as_ref());
+
+        self.package_references(package, &mut component);
+        self.report_package_fields(package);
+        component
+    }
+
+    /// Name of a supplier or originator without the type and the email.
+    fn actor(&mut self, id: &str, field: &str, actor: Option<&String>) -> Option<String> {
+        let actor = meaningful(actor)?;
+        let actor = actor
+            .split_once(": ")
+            .map_or(actor.as_str(), |(_, name)| name);
+        let (name, email) = split_contact(actor);
+        if let Some(email) = email {
+            self.report.add(id, field, email);
+        }
+        Some(name)
+    }
+
+    fn hashes(&mut self, id: &str, checksums: &[Checksum]) -> Vec<Hash> {
+        let mut hashes = Vec::new();
+        for checksum in checksums {
+            match HASH_ALGORITHMS
+                .iter()
+                .find(|(algorithm, _)| *algorithm == checksum.algorithm)
+            {
+                Some((_, alg)) => hashes.push(Hash {
+                    alg: (*alg).to_string(),
+                    content: checksum.value.clone(),
+                }),
+                None => self.report.add(
+                    id,
+                    "checksums",
+                    format!("{}: {}", serialized(&checksum.algorithm), checksum.value),
+                ),
+            }
+        }
+        hashes
+    }
+
+    fn licenses(&mut self, expression: Option<&SpdxExpression>) -> Vec<LicenseChoice> {
+        let expression = match expression {
+            Some(expression) => expression.to_string(),
+            None => return Vec::new(),
+        };
+        if expression == "NOASSERTION" || expression == "NONE" {
+            return Vec::new();
+        }
+        let is_simple = !expression.contains(|c: char| c.is_whitespace() || c == '(')
+            && !expression.ends_with('+');
+        if !is_simple {
+            return vec![LicenseChoice::Expression { expression }];
+        }
+
+        if expression.starts_with("LicenseRef-") || expression.starts_with("DocumentRef-") {
+            let text = self
+                .spdx
+                .other_licensing_information_detected
+                .iter()
+                .find(|license| license.license_identifier == expression)
+                .map(|license| AttachedText {
+                    content_type: None,
+                    content: license.extracted_text.clone(),
+                });
+            if text.is_some() {
+                self.attached_licenses.insert(expression.clone());
+            }
+            vec![LicenseChoice::License {
+                license: License {
+                    id: None,
+                    name: Some(expression),
+                    text,
+                    url: None,
+                },
+            }]
+        } else {
+            vec![LicenseChoice::License {
+                license: License {
+                    id: Some(expression),
+                    name: None,
+                    text: None,
+                    url: None,
+                },
+            }]
+        }
+    }
+
+    fn package_references(&mut self, package: &PackageInformation, component: &mut Component) {
+        let id = package.package_spdx_identifier.as_str();
+
+        if let Some(home_page) = meaningful(package.package_home_page.as_ref()) {
+            component
+                .external_references
+                .push(external_reference("website", home_page, None));
+        }
+        if let Some(download_location) = meaningful(Some(&package.package_download_location)) {
+            let is_vcs = ["git+", "hg+", "svn+", "bzr+"]
+                .iter()
+                .any(|prefix| download_location.starts_with(prefix));
+            let reference_type = if is_vcs { "vcs" } else { "distribution" };
+            component.external_references.push(external_reference(
+                reference_type,
+                download_location,
+                None,
+            ));
+        }
+
+        for reference in &package.external_reference {
+            let locator = reference.reference_locator.clone();
+            let comment = reference.reference_comment.clone();
+            if reference.purl().is_some() && component.purl.is_none() {
+                component.purl = Some(locator);
+            } else if reference.cpe().is_some() && component.cpe.is_none() {
+                component.cpe = Some(locator);
+            } else {
+                match (
+                    &reference.reference_category,
+                    reference.reference_type.as_str(),
+                ) {
+                    (ExternalPackageReferenceCategory::Security, "advisory") => component
+                        .external_references
+                        .push(external_reference("advisories", locator, comment)),
+                    (ExternalPackageReferenceCategory::Other, reference_type)
+                        if EXTERNAL_REFERENCE_TYPES.contains(&reference_type) =>
+                    {
+                        component.external_references.push(external_reference(
+                            reference_type,
+                            locator,
+                            comment,
+                        ));
+                    }
+                    _ => self.report.add(
+                        id,
+                        "externalRefs",
+                        format!(
+                            "{} {} {}",
+                            serialized(&reference.reference_category),
+                            reference.reference_type,
+                            reference.reference_locator
+                        ),
+                    ),
+                }
+            }
+        }
+    }
+
+    fn report_package_fields(&mut self, package: &PackageInformation) {
+        let id = package.package_spdx_identifier.as_str();
+        let fields = [
+            ("packageFileName", &package.package_file_name),
+            ("sourceInfo", &package.source_information),
+            ("licenseComments", &package.comments_on_license),
+            ("comment", &package.package_comment),
+            ("builtDate", &package.built_date),
+            ("releaseDate", &package.release_date),
+            ("validUntilDate", &package.valid_until_date),
+        ];
+        for (field, value) in &fields {
+            if let Some(value) = value {
+                self.report.add(id, field, value.as_str());
+            }
+        }
+
+        if let Some(verification_code) = &package.package_verification_code {
+            self.report.add(
+                id,
+                "packageVerificationCode",
+                verification_code.value.as_str(),
+            );
+        }
+        if !package.all_licenses_information_from_files.is_empty() {
+            self.report.add(
+                id,
+                "licenseInfoFromFiles",
+                package.all_licenses_information_from_files.join(" "),
+            );
+        }
+        for text in &package.package_attribution_text {
+            self.report.add(id, "attributionTexts", text.as_str());
+        }
+        for annotation in &package.annotations {
+            self.report
+                .add(id, "annotations", annotation.annotation_comment.as_str());
+        }
+    }
+
+    fn file_component(&mut self, file: &'a FileInformation) -> Component {
+        let id = file.file_spdx_identifier.as_str();
+        let mut component = Component::new(ComponentType::File, &file.file_name);
+        component.bom_ref = Some(id.to_string());
+        component.hashes = self.hashes(id, &file.file_checksum);
+        component.licenses = self.licenses(file.concluded_license.as_ref());
+        component.copyright = meaningful(file.copyright_text.as_ref());
+
+        let fields = [
+            ("licenseComments", &file.comments_on_license),
+            ("comment", &file.file_comment),
+            ("noticeText", &file.file_notice),
+        ];
+        for (field, value) in &fields {
+            if let Some(value) = value {
+                self.report.add(id, field, value.as_str());
+            }
+        }
+        for file_type in &file.file_type {
+            self.report.add(id, "fileTypes", serialized(file_type));
+        }
+        for license in &file.license_information_in_file {
+            self.report
+                .add(id, "licenseInfoInFiles", license.to_string());
+        }
+        for contributor in &file.file_contributor {
+            self.report
+                .add(id, "fileContributors", contributor.as_str());
+        }
+        for text in file.file_attribution_text.iter().flatten() {
+            self.report.add(id, "attributionTexts", text.as_str());
+        }
+        component
+    }
+
+    /// The dependency graph. Relationships that are neither dependencies nor represented by the
+    /// nesting of the components are reported.
+    fn dependencies(&mut self) -> Vec<Dependency> {
+        let mut dependencies: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        let mut add_dependency = |element: &'a str, depends_on: &str| {
+            let depends_on_list = dependencies.entry(element).or_default();
+            if !depends_on_list
+                .iter()
+                .any(|existing| existing == depends_on)
+            {
+                depends_on_list.push(depends_on.to_string());
+            }
+        };
+
+        for relationship in &self.spdx.relationships {
+            let (element, related) = (
+                relationship.spdx_element_id.as_str(),
+                relationship.related_spdx_element.as_str(),
+            );
+            let both_elements =
+                self.elements.contains_key(element) && self.elements.contains_key(related);
+            let represented = match relationship.relationship_type {
+                RelationshipType::DependsOn if both_elements => {
+                    add_dependency(element, related);
+                    true
+                }
+                RelationshipType::DependencyOf if both_elements => {
+                    add_dependency(related, element);
+                    true
+                }
+                RelationshipType::Contains => self.nested.contains(&(element, related)),
+                RelationshipType::ContainedBy => self.nested.contains(&(related, element)),
+                RelationshipType::Describes => {
+                    element == DOCUMENT_ID && self.metadata_component == Some(related)
+                }
+                RelationshipType::DescribedBy => {
+                    related == DOCUMENT_ID && self.metadata_component == Some(element)
+                }
+                _ => false,
+            };
+            if !represented {
+                self.report.add(
+                    element,
+                    "relationships",
+                    format!(
+                        "{element} {} {related}",
+                        serialized(&relationship.relationship_type)
+                    ),
+                );
+            }
+        }
+
+        dependencies
+            .into_iter()
+            .map(|(element, depends_on)| Dependency {
+                dependency_ref: element.to_string(),
+                depends_on,
+            })
+            .collect()
+    }
+
+    fn report_document(&mut self) {
+        let spdx = self.spdx;
+        let document = &spdx.document_creation_information;
+        self.report
+            .add(DOCUMENT_ID, "name", document.document_name.as_str());
+        self.report.add(
+            DOCUMENT_ID,
+            "documentNamespace",
+            document.spdx_document_namespace.as_str(),
+        );
+        let fields = [
+            ("comment", &document.document_comment),
+            (
+                "creationInfo.comment",
+                &document.creation_info.creator_comment,
+            ),
+            (
+                "creationInfo.licenseListVersion",
+                &document.creation_info.license_list_version,
+            ),
+        ];
+        for (field, value) in &fields {
+            if let Some(value) = value {
+                self.report.add(DOCUMENT_ID, field, value.as_str());
+            }
+        }
+        for reference in &document.external_document_references {
+            self.report.add(
+                DOCUMENT_ID,
+                "externalDocumentRefs",
+                format!("{} {}", reference.id_string, reference.spdx_document_uri),
+            );
+        }
+        for described in &document.document_describes {
+            if self.metadata_component != Some(described.as_str()) {
+                self.report
+                    .add(DOCUMENT_ID, "documentDescribes", described.as_str());
+            }
+        }
+
+        for license in &spdx.other_licensing_information_detected {
+            let id = license.license_identifier.as_str();
+            if !self.attached_licenses.contains(id) {
+                self.report
+                    .add(id, "extractedText", license.extracted_text.as_str());
+            } else if license.license_name != id && license.license_name != "NOASSERTION" {
+                self.report.add(id, "name", license.license_name.as_str());
+            }
+        }
+        for snippet in &spdx.snippet_information {
+            self.report.add(
+                &snippet.snippet_spdx_identifier,
+                "snippets",
+                snippet.snippet_name.as_deref().unwrap_or_default(),
+            );
+        }
+        for annotation in &spdx.annotations {
+            let element = annotation
+                .spdx_identifier_reference
+                .as_deref()
+                .unwrap_or(DOCUMENT_ID);
+            self.report.add(
+                element,
+                "annotations",
+                annotation.annotation_comment.as_str(),
+            );
+        }
+    }
+}
+
+fn external_reference(
+    reference_type: &str,
+    url: String,
+    comment: Option<String>,
+) -> ExternalReference {
+    ExternalReference {
+        reference_type: reference_type.to_string(),
+        url,
+        comment,
+    }
+}
+
+struct CycloneDxToSpdx<'a> {
+    bom: &'a Bom,
+    spdx: SPDX,
+    report: ConversionReport,
+
+    /// SPDX IDs of the `bom-ref`s.
+    ids: HashMap<&'a str, String>,
+    used_ids: HashSet<String>,
+}
+
+impl<'a> CycloneDxToSpdx<'a> {
+    fn new(bom: &'a Bom) -> Self {
+        let mut report = ConversionReport::default();
+        let metadata = bom.metadata.clone().unwrap_or_default();
+        let name = metadata
+            .component
+            .as_ref()
+            .map_or("cyclonedx-bom", |component| component.name.as_str());
+
+        let mut spdx = SPDX::new(name);
+        let document = &mut spdx.document_creation_information;
+        document.spdx_version = "SPDX-2.3".to_string();
+        if let Some(uuid) = bom
+            .serial_number
+            .as_deref()
+            .and_then(|serial_number| serial_number.strip_prefix("urn:uuid:"))
+        {
+            document.spdx_document_namespace = format!("http://spdx.org/spdxdocs/{name}-{uuid}");
+        }
+        if bom.version != 1 {
+            report.add("bom", "version", bom.version.to_string());
+        }
+
+        let mut creators = Vec::new();
+        for tool in &metadata.tools {
+            let name = tool.name.as_deref().unwrap_or("NOASSERTION");
+            creators.push(tool.version.as_ref().map_or_else(
+                || format!("Tool: {name}"),
+                |version| format!("Tool: {name}-{version}"),
+            ));
+            if let Some(vendor) = &tool.vendor {
+                report.add("metadata", "tools.vendor", vendor.as_str());
+            }
+        }
+        for author in &metadata.authors {
+            creators.push(format!(
+                "Person: {} ({})",
+                author.name.as_deref().unwrap_or_default(),
+                author.email.as_deref().unwrap_or_default()
+            ));
+        }
+        if let Some(name) = metadata
+            .supplier
+            .as_ref()
+            .and_then(|supplier| supplier.name.as_ref())
+        {
+            creators.push(format!("Organization: {name} ()"));
+        }
+        if creators.is_empty() {
+            creators.push(format!(
+                "Tool: {}-{}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+        document.creation_info = CreationInfo {
+            license_list_version: None,
+            creators,
+            created: metadata.timestamp.unwrap_or_else(Utc::now),
+            creator_comment: None,
+        };
+
+        Self {
+            bom,
+            spdx,
+            report,
+            ids: HashMap::new(),
+            used_ids: HashSet::new(),
+        }
+    }
+
+    /// A unique SPDX ID for the component, based on the `bom-ref` if there is one.
+    fn spdx_id(&mut self, bom_ref: Option<&'a str>) -> String {
+        let base = bom_ref.map_or_else(
+            || "SPDXRef-Component".to_string(),
+            |bom_ref| {
+                let id = sanitize_spdx_id(bom_ref.strip_prefix("SPDXRef-").unwrap_or(bom_ref));
+                format!("SPDXRef-{id}")
+            },
+        );
+
+        let mut counter = usize::from(bom_ref.is_none());
+        let mut id = if counter == 0 {
+            base.clone()
+        } else {
+            format!("{base}-{counter}")
+        };
+        while self.used_ids.contains(&id) {
+            counter += 1;
+            id = format!("{base}-{counter}");
+        }
+
+        self.used_ids.insert(id.clone());
+        if let Some(bom_ref) = bom_ref {
+            self.ids.entry(bom_ref).or_insert_with(|| id.clone());
+        }
+        id
+    }
+
+    fn describe(&mut self, id: &str) {
+        self.spdx.relationships.push(Relationship::new(
+            DOCUMENT_ID,
+            id,
+            RelationshipType::Describes,
+            None,
+        ));
+    }
+
+    fn add_component(&mut self, component: &'a Component, parent: Option<&str>) -> String {
+        let id = self.spdx_id(component.bom_ref.as_deref());
+        if component.component_type == ComponentType::File {
+            let file = self.file(&id, component);
+            self.spdx.file_information.push(file);
+        } else {
+            let package = self.package(&id, component);
+            self.spdx.package_information.push(package);
+        }
+
+        if let Some(parent) = parent {
+            self.spdx.relationships.push(Relationship::new(
+                parent,
+                &id,
+                RelationshipType::Contains,
+                None,
+            ));
+        }
+        for child in &component.components {
+            self.add_component(child, Some(&id));
+        }
+        id
+    }
+
+    fn package(&mut self, id: &str, component: &Component) -> PackageInformation {
+        let primary_package_purpose = COMPONENT_TYPES
+            .iter()
+            .find(|(_, component_type)| *component_type == component.component_type)
+            .map_or_else(
+                || {
+                    self.report
+                        .add(id, "type", component.component_type.as_str());
+                    PrimaryPackagePurpose::Other
+                },
+                |(purpose, _)| *purpose,
+            );
+
+        let mut package = PackageInformation {
+            package_name: component.name.clone(),
+            package_spdx_identifier: id.to_string(),
+            package_version: component.version.clone(),
+            package_supplier: component
+                .supplier
+                .as_ref()
+                .and_then(|supplier| supplier.name.as_ref())
+                .map(|name| format!("Organization: {name}")),
+            package_originator: component
+                .author
+                .as_ref()
+                .map(|author| format!("Person: {author}")),
+            files_analyzed: Some(false),
+            package_checksum: self.checksums(id, &component.hashes),
+            declared_license: self.license(id, &component.licenses),
+            copyright_text: component.copyright.clone(),
+            package_detailed_description: component.description.clone(),
+            primary_package_purpose: Some(primary_package_purpose),
+            ..PackageInformation::default()
+        };
+
+        if let Some(purl) = &component.purl {
+            package
+                .external_reference
+                .push(ExternalPackageReference::new(
+                    ExternalPackageReferenceCategory::PackageManager,
+                    "purl".to_string(),
+                    purl.clone(),
+                    None,
+                ));
+        }
+        if let Some(cpe) = &component.cpe {
+            let reference_type = if cpe.starts_with("cpe:2.3:") {
+                "cpe23Type"
+            } else {
+                "cpe22Type"
+            };
+            package
+                .external_reference
+                .push(ExternalPackageReference::new(
+                    ExternalPackageReferenceCategory::Security,
+                    reference_type.to_string(),
+                    cpe.clone(),
+                    None,
+                ));
+        }
+        for reference in &component.external_references {
+            add_external_reference(&mut package, reference);
+        }
+
+        let fields = [
+            ("group", &component.group),
+            ("publisher", &component.publisher),
+        ];
+        for (field, value) in &fields {
+            if let Some(value) = value {
+                self.report.add(id, field, value.as_str());
+            }
+        }
+        package
+    }
+
+    fn file(&mut self, id: &str, component: &Component) -> FileInformation {
+        let file = FileInformation {
+            file_name: component.name.clone(),
+            file_spdx_identifier: id.to_string(),
+            file_checksum: self.checksums(id, &component.hashes),
+            concluded_license: self.license(id, &component.licenses),
+            copyright_text: component.copyright.clone(),
+            ..FileInformation::default()
+        };
+
+        let fields = [
+            ("version", &component.version),
+            ("author", &component.author),
+            ("publisher", &component.publisher),
+            ("group", &component.group),
+            ("description", &component.description),
+            ("cpe", &component.cpe),
+            ("purl", &component.purl),
+        ];
+        for (field, value) in &fields {
+            if let Some(value) = value {
+                self.report.add(id, field, value.as_str());
+            }
+        }
+        if let Some(name) = component
+            .supplier
+            .as_ref()
+            .and_then(|supplier| supplier.name.as_ref())
+        {
+            self.report.add(id, "supplier", name.as_str());
+        }
+        for reference in &component.external_references {
+            self.report.add(
+                id,
+                "externalReferences",
+                format!("{} {}", reference.reference_type, reference.url),
+            );
+        }
+        file
+    }
+
+    fn checksums(&mut self, id: &str, hashes: &[Hash]) -> Vec<Checksum> {
+        let mut checksums = Vec::new();
+        for hash in hashes {
+            match HASH_ALGORITHMS.iter().find(|(_, alg)| *alg == hash.alg) {
+                Some((algorithm, _)) => checksums.push(Checksum::new(*algorithm, &hash.content)),
+                None => self
+                    .report
+                    .add(id, "hashes", format!("{}: {}", hash.alg, hash.content)),
+            }
+        }
+        checksums
+    }
+
+    /// Combine the license choices to a single expression. Licenses that are only named become
+    /// `LicenseRef`s with their text in the extracted licensing information.
+    fn license(&mut self, id: &str, licenses: &[LicenseChoice]) -> Option<SpdxExpression> {
+        let mut expressions = Vec::new();
+        for choice in licenses {
+            match choice {
+                LicenseChoice::Expression { expression } => expressions.push(expression.clone()),
+                LicenseChoice::License { license } => {
+                    if let Some(license_id) = &license.id {
+                        expressions.push(license_id.clone());
+                        if let Some(url) = &license.url {
+                            self.report.add(id, "licenses.url", url.as_str());
+                        }
+                    } else if let Some(name) = &license.name {
+                        expressions.push(self.license_ref(name, license));
+                    }
+                }
+            }
+        }
+
+        let expression = match expressions.as_slice() {
+            [] => return None,
+            [expression] => expression.clone(),
+            expressions => expressions
+                .iter()
+                .map(|expression| format!("({expression})"))
+                .collect::<Vec<_>>()
+                .join(" AND "),
+        };
+        let parsed = SpdxExpression::parse(&expression).ok();
+        if parsed.is_none() {
+            self.report.add(id, "licenses", expression);
+        }
+        parsed
+    }
+
+    fn license_ref(&mut self, name: &str, license: &License) -> String {
+        let license_identifier = if name.starts_with("LicenseRef-") {
+            name.to_string()
+        } else {
+            format!("LicenseRef-{}", sanitize_spdx_id(name))
+        };
+
+        let licenses = &mut self.spdx.other_licensing_information_detected;
+        if !licenses
+            .iter()
+            .any(|license| license.license_identifier == license_identifier)
+        {
+            licenses.push(OtherLicensingInformationDetected {
+                license_identifier: license_identifier.clone(),
+                extracted_text: license
+                    .text
+                    .as_ref()
+                    .map_or_else(|| "NOASSERTION".to_string(), |text| text.content.clone()),
+                license_name: name.to_string(),
+                license_cross_reference: license.url.iter().cloned().collect(),
+                license_comment: None,
+                ..OtherLicensingInformationDetected::default()
+            });
+        }
+        license_identifier
+    }
+
+    fn add_dependencies(&mut self) {
+        for dependency in &self.bom.dependencies {
+            for depends_on in &dependency.depends_on {
+                match (
+                    self.ids.get(dependency.dependency_ref.as_str()),
+                    self.ids.get(depends_on.as_str()),
+                ) {
+                    (Some(element), Some(related)) => {
+                        self.spdx.relationships.push(Relationship::new(
+                            element,
+                            related,
+                            RelationshipType::DependsOn,
+                            None,
+                        ));
+                    }
+                    _ => self.report.add(
+                        &dependency.dependency_ref,
+                        "dependencies",
+                        depends_on.as_str(),
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Add a `CycloneDX` external reference to a package. The first website becomes the home page and
+/// the first distribution or VCS reference the download location.
+fn add_external_reference(package: &mut PackageInformation, reference: &ExternalReference) {
+    match reference.reference_type.as_str() {
+        "website" if package.package_home_page.is_none() => {
+            package.package_home_page = Some(reference.url.clone());
+        }
+        "distribution" | "vcs" if package.package_download_location == "NOASSERTION" => {
+            package.package_download_location.clone_from(&reference.url);
+        }
+        "advisories" => package
+            .external_reference
+            .push(ExternalPackageReference::new(
+                ExternalPackageReferenceCategory::Security,
+                "advisory".to_string(),
+                reference.url.clone(),
+                reference.comment.clone(),
+            )),
+        reference_type => package
+            .external_reference
+            .push(ExternalPackageReference::new(
+                ExternalPackageReferenceCategory::Other,
+                reference_type.to_string(),
+                reference.url.clone(),
+                reference.comment.clone(),
+            )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example_spdx() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    fn example_bom() -> Bom {
+        Bom::from_json(&read_to_string("tests/data/cyclonedx/bom-1.4.json").unwrap()).unwrap()
+    }
+
+    fn component<'a>(bom: &'a Bom, bom_ref: &str) -> &'a Component {
+        bom.all_components()
+            .into_iter()
+            .find(|component| component.bom_ref.as_deref() == Some(bom_ref))
+            .unwrap()
+    }
+
+    #[test]
+    fn packages_are_converted_to_components() {
+        let (bom, _) = Bom::from_spdx(&example_spdx(), CycloneDxVersion::V1_5);
+
+        let jena = component(&bom, "SPDXRef-fromDoap-0");
+        assert_eq!(jena.name, "Jena");
+        assert_eq!(jena.version, Some("3.12.0".to_string()));
+        assert_eq!(
+            jena.purl,
+            Some("pkg:maven/org.apache.jena/apache-jena@3.12.0".to_string())
+        );
+        assert_eq!(jena.external_references[0].reference_type, "website");
+
+        let glibc = component(&bom, "SPDXRef-Package");
+        assert_eq!(
+            glibc
+                .hashes
+                .iter()
+                .map(|hash| hash.alg.as_str())
+                .collect::<Vec<_>>(),
+            vec!["MD5", "SHA-1", "SHA-256", "BLAKE2b-384"]
+        );
+        assert!(glibc.cpe.is_some());
+        assert!(matches!(
+            &glibc.licenses[..],
+            [LicenseChoice::Expression { .. }]
+        ));
+        assert_eq!(
+            glibc.supplier.as_ref().unwrap().name,
+            Some("Jane Doe".to_string())
+        );
+
+        let saxon = component(&bom, "SPDXRef-Saxon");
+        assert_eq!(
+            saxon.licenses,
+            vec![LicenseChoice::License {
+                license: License {
+                    id: Some("MPL-1.0".to_string()),
+                    name: None,
+                    text: None,
+                    url: None
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn files_are_nested_in_packages() {
+        let (bom, report) = Bom::from_spdx(&example_spdx(), CycloneDxVersion::V1_4);
+        let glibc = component(&bom, "SPDXRef-Package");
+        assert!(glibc
+            .components
+            .iter()
+            .any(|component| component.bom_ref.as_deref() == Some("SPDXRef-JenaLib")));
+        assert_eq!(
+            component(&bom, "SPDXRef-JenaLib").component_type,
+            ComponentType::File
+        );
+
+        // The file also contains the package, which can't be represented.
+        assert!(report.contains("SPDXRef-JenaLib", "relationships"));
+    }
+
+    #[test]
+    fn unrepresented_fields_are_reported() {
+        let (_, report) = Bom::from_spdx(&example_spdx(), CycloneDxVersion::V1_5);
+        assert!(report.contains("SPDXRef-Package", "primaryPackagePurpose"));
+        assert!(report.contains("SPDXRef-Package", "packageVerificationCode"));
+        assert!(report.contains("SPDXRef-Snippet", "snippets"));
+        assert!(report.contains("SPDXRef-Package", "relationships"));
+        assert!(report.contains(DOCUMENT_ID, "documentNamespace"));
+        assert!(!report.contains("SPDXRef-fromDoap-0", "externalRefs"));
+    }
+
+    #[test]
+    fn components_are_converted_to_packages() {
+        let (spdx, report) = example_bom().to_spdx();
+        assert_eq!(
+            spdx.document_creation_information.spdx_document_namespace,
+            "http://spdx.org/spdxdocs/example-app-3e671687-395b-41f5-a30f-a58921a69b79"
+        );
+        assert_eq!(
+            spdx.document_creation_information.creation_info.creators,
+            vec![
+                "Tool: bom-generator-1.2.0".to_string(),
+                "Person: Jane Doe (jane@example.com)".to_string()
+            ]
+        );
+        assert_eq!(spdx.package_information.len(), 3);
+        assert_eq!(spdx.file_information.len(), 1);
+
+        let jena = &spdx.package_information[1];
+        assert_eq!(
+            jena.package_spdx_identifier,
+            "SPDXRef-pkg-maven-org.apache.jena-apache-jena-3.12.0"
+        );
+        assert_eq!(
+            jena.package_home_page,
+            Some("http://www.openjena.org/".to_string())
+        );
+        assert_eq!(
+            jena.package_checksum[0],
+            Checksum::new(Algorithm::SHA1, "85ed0817af83a24ad8da68c2b5094de69833983c")
+        );
+        assert_eq!(jena.purls().unwrap().len(), 1);
+        assert!(report.contains(&jena.package_spdx_identifier, "group"));
+
+        let saxon = &spdx.package_information[2];
+        assert_eq!(
+            saxon.declared_license,
+            Some(SpdxExpression::parse("LicenseRef-Saxon-License").unwrap())
+        );
+        assert_eq!(saxon.cpes().unwrap().len(), 1);
+        assert_eq!(
+            spdx.other_licensing_information_detected[0].extracted_text,
+            "Licensed under the Saxon license."
+        );
+
+        let relationships = spdx
+            .relationships
+            .iter()
+            .map(|relationship| {
+                (
+                    relationship.spdx_element_id.as_str(),
+                    relationship.relationship_type.clone(),
+                    relationship.related_spdx_element.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(relationships.contains(&(
+            DOCUMENT_ID,
+            RelationshipType::Describes,
+            "SPDXRef-example-app"
+        )));
+        assert!(relationships.contains(&(
+            jena.package_spdx_identifier.as_str(),
+            RelationshipType::Contains,
+            "SPDXRef-jena-lib"
+        )));
+        assert!(relationships.contains(&(
+            "SPDXRef-example-app",
+            RelationshipType::DependsOn,
+            "SPDXRef-saxon"
+        )));
+    }
+
+    #[test]
+    fn bom_roundtrips_through_spdx() {
+        let bom = example_bom();
+        let (spdx, _) = bom.to_spdx();
+        let (converted, _) = Bom::from_spdx(&spdx, CycloneDxVersion::V1_4);
+
+        let names = |bom: &Bom| {
+            bom.all_components()
+                .iter()
+                .map(|component| (component.name.clone(), component.version.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&converted), names(&bom));
+        assert_eq!(
+            converted
+                .metadata
+                .as_ref()
+                .unwrap()
+                .component
+                .as_ref()
+                .unwrap()
+                .purl,
+            Some("pkg:cargo/example-app@1.0.0".to_string())
+        );
+        assert_eq!(converted.dependencies[0].depends_on.len(), 2);
+        assert_eq!(
+            converted.components[1].licenses,
+            bom.components[1]
+                .licenses
+                .iter()
+                .map(|choice| match choice {
+                    LicenseChoice::License { license } => LicenseChoice::License {
+                        license: License {
+                            name: Some("LicenseRef-Saxon-License".to_string()),
+                            ..license.clone()
+                        }
+                    },
+                    LicenseChoice::Expression { .. } => choice.clone(),
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn spdx_roundtrips_through_cyclonedx() {
+        let spdx = example_spdx();
+        let (bom, _) = Bom::from_spdx(&spdx, CycloneDxVersion::V1_5);
+        let bom = Bom::from_xml(&bom.to_xml().unwrap()).unwrap();
+        let (converted, _) = bom.to_spdx();
+
+        for package in &spdx.package_information {
+            let converted_package = converted
+                .package_information
+                .iter()
+                .find(|converted| {
+                    converted.package_spdx_identifier == package.package_spdx_identifier
+                })
+                .unwrap();
+            assert_eq!(converted_package.package_name, package.package_name);
+            assert_eq!(converted_package.package_version, package.package_version);
+            assert_eq!(converted_package.purls().unwrap(), package.purls().unwrap());
+            assert_eq!(
+                converted_package.cpes().unwrap().len(),
+                package.cpes().unwrap().len().min(1)
+            );
+        }
+        assert_eq!(
+            converted.file_information.len(),
+            spdx.file_information.len()
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Conversion between SPDX documents and [CycloneDX](https://cyclonedx.org) 1.4 and 1.5 BOMs.
+//!
+//! Both the JSON and the XML representation of `CycloneDX` are supported. The formats don't map to
+//! each other exactly, so every conversion also returns a [`ConversionReport`] listing the fields
+//! that could not be represented in the target format.
+
+use serde::Serialize;
+
+mod convert;
+mod model;
+mod xml;
+
+pub use model::*;
+
+/// Fields that were dropped during a conversion.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub unrepresented: Vec<UnrepresentedField>,
+}
+
+/// A field of the source document without a counterpart in the target format.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct UnrepresentedField {
+    /// Identifier of the element the field belongs to, e.g. an SPDX ID or a `bom-ref`.
+    pub element: String,
+
+    /// Name of the field in the source format.
+    pub field: String,
+
+    pub value: String,
+}
+
+impl ConversionReport {
+    /// Returns `true` if every field could be represented.
+    pub fn is_empty(&self) -> bool {
+        self.unrepresented.is_empty()
+    }
+
+    fn add(&mut self, element: &str, field: &str, value: impl Into<String>) {
+        self.unrepresented.push(UnrepresentedField {
+            element: element.to_string(),
+            field: field.to_string(),
+            value: value.into(),
+        });
+    }
+
+    /// Whether a field of an element was reported.
+    pub fn contains(&self, element: &str, field: &str) -> bool {
+        self.unrepresented
+            .iter()
+            .any(|unrepresented| unrepresented.element == element && unrepresented.field == field)
+    }
+}
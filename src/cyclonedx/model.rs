@@ -0,0 +1,474 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::error::SpdxError;
+
+/// Supported versions of the `CycloneDX` specification.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CycloneDxVersion {
+    #[serde(rename = "1.4")]
+    V1_4,
+    #[serde(rename = "1.5")]
+    V1_5,
+}
+
+impl CycloneDxVersion {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::V1_4 => "1.4",
+            Self::V1_5 => "1.5",
+        }
+    }
+
+    /// XML namespace of the version.
+    pub fn xml_namespace(self) -> String {
+        format!("http://cyclonedx.org/schema/bom/{}", self.as_str())
+    }
+}
+
+impl Display for CycloneDxVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `CycloneDX` bill of materials. Only the parts that have a counterpart in SPDX are supported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Bom {
+    /// Always `CycloneDX`.
+    pub bom_format: String,
+
+    pub spec_version: CycloneDxVersion,
+
+    /// Unique identifier of the BOM in the form `urn:uuid:<uuid>`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub serial_number: Option<String>,
+
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub metadata: Option<Metadata>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub components: Vec<Component>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub dependencies: Vec<Dependency>,
+}
+
+const fn default_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timestamp: Option<DateTime<Utc>>,
+
+    /// Tools used to create the BOM. `CycloneDX` 1.5 also allows listing the tools as components,
+    /// which are read into this list as well.
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        default,
+        deserialize_with = "deserialize_tools"
+    )]
+    pub tools: Vec<Tool>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub authors: Vec<OrganizationalContact>,
+
+    /// The component the BOM describes.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub component: Option<Box<Component>>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub supplier: Option<OrganizationalEntity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct Tool {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub vendor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+}
+
+fn deserialize_tools<'de, D>(deserializer: D) -> Result<Vec<Tool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tools {
+        Legacy(Vec<Tool>),
+        Components {
+            #[serde(default)]
+            components: Vec<Component>,
+        },
+    }
+
+    Ok(match Tools::deserialize(deserializer)? {
+        Tools::Legacy(tools) => tools,
+        Tools::Components { components } => components
+            .into_iter()
+            .map(|component| {
+                let Component {
+                    publisher,
+                    supplier,
+                    name,
+                    version,
+                    ..
+                } = component;
+                Tool {
+                    vendor: publisher.or_else(|| supplier.and_then(|supplier| supplier.name)),
+                    name: Some(name),
+                    version,
+                }
+            })
+            .collect(),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct OrganizationalContact {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct OrganizationalEntity {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Component {
+    #[serde(rename = "type")]
+    pub component_type: ComponentType,
+
+    #[serde(rename = "bom-ref", skip_serializing_if = "Option::is_none", default)]
+    pub bom_ref: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub supplier: Option<OrganizationalEntity>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub author: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub publisher: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub group: Option<String>,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub hashes: Vec<Hash>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub licenses: Vec<LicenseChoice>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub copyright: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cpe: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub purl: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub external_references: Vec<ExternalReference>,
+
+    /// Components included in this component.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    /// Create a component with only the type and the name set.
+    pub fn new(component_type: ComponentType, name: &str) -> Self {
+        Self {
+            component_type,
+            bom_ref: None,
+            supplier: None,
+            author: None,
+            publisher: None,
+            group: None,
+            name: name.to_string(),
+            version: None,
+            description: None,
+            hashes: Vec::new(),
+            licenses: Vec::new(),
+            copyright: None,
+            cpe: None,
+            purl: None,
+            external_references: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ComponentType {
+    Application,
+    Framework,
+    Library,
+    Container,
+    OperatingSystem,
+    Device,
+    Firmware,
+    File,
+    Platform,
+    DeviceDriver,
+    /// Only in `CycloneDX` 1.5.
+    MachineLearningModel,
+    /// Only in `CycloneDX` 1.5.
+    Data,
+}
+
+impl ComponentType {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Application => "application",
+            Self::Framework => "framework",
+            Self::Library => "library",
+            Self::Container => "container",
+            Self::OperatingSystem => "operating-system",
+            Self::Device => "device",
+            Self::Firmware => "firmware",
+            Self::File => "file",
+            Self::Platform => "platform",
+            Self::DeviceDriver => "device-driver",
+            Self::MachineLearningModel => "machine-learning-model",
+            Self::Data => "data",
+        }
+    }
+
+    /// Parse the type from its serialized form.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::CycloneDx`] if the type is unknown.
+    pub fn parse(value: &str) -> Result<Self, SpdxError> {
+        Ok(match value {
+            "application" => Self::Application,
+            "framework" => Self::Framework,
+            "library" => Self::Library,
+            "container" => Self::Container,
+            "operating-system" => Self::OperatingSystem,
+            "device" => Self::Device,
+            "firmware" => Self::Firmware,
+            "file" => Self::File,
+            "platform" => Self::Platform,
+            "device-driver" => Self::DeviceDriver,
+            "machine-learning-model" => Self::MachineLearningModel,
+            "data" => Self::Data,
+            _ => {
+                return Err(SpdxError::CycloneDx(format!(
+                    "unknown component type {value}"
+                )))
+            }
+        })
+    }
+}
+
+/// A hash of a component. The algorithm is kept as a string, e.g. `SHA-256`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Hash {
+    pub alg: String,
+    pub content: String,
+}
+
+/// Either a single license or an SPDX license expression.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LicenseChoice {
+    License { license: License },
+    Expression { expression: String },
+}
+
+/// A license identified by an SPDX license ID or by name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct License {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub text: Option<AttachedText>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachedText {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub content_type: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalReference {
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub comment: Option<String>,
+}
+
+/// Direct dependencies of the component identified by `ref`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependency {
+    #[serde(rename = "ref")]
+    pub dependency_ref: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub depends_on: Vec<String>,
+}
+
+impl Bom {
+    /// Create an empty BOM.
+    pub fn new(version: CycloneDxVersion) -> Self {
+        Self {
+            bom_format: "CycloneDX".to_string(),
+            spec_version: version,
+            serial_number: None,
+            version: 1,
+            metadata: None,
+            components: Vec::new(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Parse a BOM from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if the input is not a valid `CycloneDX` 1.4 or 1.5 BOM.
+    pub fn from_json(input: &str) -> Result<Self, SpdxError> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Serialize the BOM to JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, SpdxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// All components of the BOM, including the metadata component and nested components.
+    pub fn all_components(&self) -> Vec<&Component> {
+        fn collect<'a>(component: &'a Component, components: &mut Vec<&'a Component>) {
+            components.push(component);
+            for child in &component.components {
+                collect(child, components);
+            }
+        }
+
+        let mut components = Vec::new();
+        if let Some(component) = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.component.as_ref())
+        {
+            collect(component, &mut components);
+        }
+        for component in &self.components {
+            collect(component, &mut components);
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    #[test]
+    fn bom_is_parsed_from_json() {
+        let bom =
+            Bom::from_json(&read_to_string("tests/data/cyclonedx/bom-1.4.json").unwrap()).unwrap();
+        assert_eq!(bom.spec_version, CycloneDxVersion::V1_4);
+        assert_eq!(
+            bom.metadata.as_ref().unwrap().tools[0].name,
+            Some("bom-generator".to_string())
+        );
+        assert_eq!(bom.components.len(), 2);
+        assert_eq!(bom.components[0].hashes[0].alg, "SHA-1");
+        assert_eq!(
+            bom.components[1].licenses,
+            vec![LicenseChoice::License {
+                license: License {
+                    id: None,
+                    name: Some("Saxon License".to_string()),
+                    text: Some(AttachedText {
+                        content_type: None,
+                        content: "Licensed under the Saxon license.".to_string()
+                    }),
+                    url: None
+                }
+            }]
+        );
+        assert_eq!(bom.all_components().len(), 4);
+    }
+
+    #[test]
+    fn tools_as_components_are_parsed() {
+        let metadata: Metadata = serde_json::from_str(
+            r#"{"tools": {"components": [{"type": "application", "publisher": "Example", "name": "bom-generator"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            metadata.tools,
+            vec![Tool {
+                vendor: Some("Example".to_string()),
+                name: Some("bom-generator".to_string()),
+                version: None
+            }]
+        );
+    }
+
+    #[test]
+    fn bom_roundtrips_through_json() {
+        let bom =
+            Bom::from_json(&read_to_string("tests/data/cyclonedx/bom-1.4.json").unwrap()).unwrap();
+        assert_eq!(Bom::from_json(&bom.to_json().unwrap()).unwrap(), bom);
+    }
+
+    #[test]
+    fn unknown_component_type_is_an_error() {
+        assert!(ComponentType::parse("spaceship").is_err());
+        assert_eq!(
+            ComponentType::parse("operating-system").unwrap(),
+            ComponentType::OperatingSystem
+        );
+    }
+}
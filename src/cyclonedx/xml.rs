@@ -0,0 +1,583 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Reading and writing the XML representation of a [`Bom`].
+//!
+//! The XML is first read into a simple element tree, which is then mapped to the same structs
+//! that are used for JSON.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use quick_xml::{
+    events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event},
+    Reader, Writer,
+};
+
+use crate::error::SpdxError;
+
+use super::{
+    AttachedText, Bom, Component, ComponentType, CycloneDxVersion, Dependency, ExternalReference,
+    Hash, License, LicenseChoice, Metadata, OrganizationalContact, OrganizationalEntity, Tool,
+};
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn with_text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    fn push(&mut self, child: Self) {
+        self.children.push(child);
+    }
+
+    /// Add a child with text content if the value exists.
+    fn push_text(&mut self, name: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.push(Self::new(name).with_text(value));
+        }
+    }
+
+    /// Add a wrapper element for the children if there are any.
+    fn push_list(&mut self, name: &str, children: Vec<Self>) {
+        if !children.is_empty() {
+            self.push(Self {
+                children,
+                ..Self::new(name)
+            });
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&Self> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Self> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Children of the wrapper element `list` named `item`.
+    fn list<'a>(&'a self, list: &'a str, item: &'a str) -> impl Iterator<Item = &'a Self> + 'a {
+        self.child(list)
+            .into_iter()
+            .flat_map(move |list| list.children(item))
+    }
+
+    fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name).map(|child| child.text.clone())
+    }
+
+    fn required_text(&self, name: &str) -> Result<String, SpdxError> {
+        self.child_text(name)
+            .ok_or_else(|| SpdxError::CycloneDx(format!("<{}> is missing <{name}>", self.name)))
+    }
+
+    fn required_attribute(&self, name: &str) -> Result<&str, SpdxError> {
+        self.attribute(name).ok_or_else(|| {
+            SpdxError::CycloneDx(format!("<{}> is missing attribute {name}", self.name))
+        })
+    }
+}
+
+fn xml_error<E: std::fmt::Display>(error: E) -> SpdxError {
+    SpdxError::Xml(error.to_string())
+}
+
+fn local_name(name: &[u8]) -> String {
+    let name = String::from_utf8_lossy(name);
+    name.rsplit(':').next().unwrap_or(&name).to_string()
+}
+
+fn start_element(start: &BytesStart) -> Result<Element, SpdxError> {
+    let mut element = Element::new(&local_name(start.name().as_ref()));
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(xml_error)?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+        let value = attribute.unescape_value().map_err(xml_error)?.to_string();
+        element.attributes.push((key, value));
+    }
+    Ok(element)
+}
+
+fn parse_elements(input: &str) -> Result<Element, SpdxError> {
+    let mut reader = Reader::from_str(input);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Element> = Vec::new();
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(start) => stack.push(start_element(&start)?),
+            Event::Empty(start) => {
+                let element = start_element(&start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&text.unescape().map_err(xml_error)?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(current) = stack.last_mut() {
+                    current
+                        .text
+                        .push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| SpdxError::Xml("unexpected end tag".to_string()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Eof => return Err(SpdxError::Xml("unexpected end of document".to_string())),
+            _ => {}
+        }
+    }
+}
+
+fn write_element(writer: &mut Writer<Vec<u8>>, element: &Element) -> Result<(), SpdxError> {
+    let start = BytesStart::new(element.name.as_str()).with_attributes(
+        element
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str())),
+    );
+
+    if element.children.is_empty() && element.text.is_empty() {
+        writer.write_event(Event::Empty(start)).map_err(xml_error)?;
+        return Ok(());
+    }
+
+    writer.write_event(Event::Start(start)).map_err(xml_error)?;
+    if !element.text.is_empty() {
+        writer
+            .write_event(Event::Text(BytesText::new(&element.text)))
+            .map_err(xml_error)?;
+    }
+    for child in &element.children {
+        write_element(writer, child)?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new(element.name.as_str())))
+        .map_err(xml_error)?;
+    Ok(())
+}
+
+fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl Bom {
+    /// Parse a BOM from XML. The specification version is read from the namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Xml`] if the input is not valid XML, or [`SpdxError::CycloneDx`] if
+    /// it's not a valid `CycloneDX` 1.4 or 1.5 BOM.
+    pub fn from_xml(input: &str) -> Result<Self, SpdxError> {
+        let root = parse_elements(input)?;
+        if root.name != "bom" {
+            return Err(SpdxError::CycloneDx(format!(
+                "root element is <{}> instead of <bom>",
+                root.name
+            )));
+        }
+
+        let namespace = root.attribute("xmlns").unwrap_or_default();
+        let spec_version = [CycloneDxVersion::V1_4, CycloneDxVersion::V1_5]
+            .iter()
+            .copied()
+            .find(|version| version.xml_namespace() == namespace)
+            .ok_or_else(|| SpdxError::CycloneDx(format!("unsupported namespace {namespace:?}")))?;
+
+        Ok(Self {
+            bom_format: "CycloneDX".to_string(),
+            spec_version,
+            serial_number: root.attribute("serialNumber").map(ToString::to_string),
+            version: root
+                .attribute("version")
+                .map_or(Ok(1), str::parse)
+                .map_err(|_| SpdxError::CycloneDx("invalid BOM version".to_string()))?,
+            metadata: root.child("metadata").map(metadata_from_xml).transpose()?,
+            components: root
+                .list("components", "component")
+                .map(component_from_xml)
+                .collect::<Result<_, _>>()?,
+            dependencies: root
+                .list("dependencies", "dependency")
+                .map(|dependency| {
+                    Ok(Dependency {
+                        dependency_ref: dependency.required_attribute("ref")?.to_string(),
+                        depends_on: dependency
+                            .children("dependency")
+                            .map(|depends_on| {
+                                depends_on
+                                    .required_attribute("ref")
+                                    .map(ToString::to_string)
+                            })
+                            .collect::<Result<_, _>>()?,
+                    })
+                })
+                .collect::<Result<_, SpdxError>>()?,
+        })
+    }
+
+    /// Serialize the BOM to XML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Xml`] if writing fails.
+    pub fn to_xml(&self) -> Result<String, SpdxError> {
+        let mut root = Element::new("bom")
+            .with_attribute("xmlns", &self.spec_version.xml_namespace())
+            .with_attribute("version", &self.version.to_string());
+        if let Some(serial_number) = &self.serial_number {
+            root = root.with_attribute("serialNumber", serial_number);
+        }
+
+        if let Some(metadata) = &self.metadata {
+            root.push(metadata_to_xml(metadata));
+        }
+        root.push_list(
+            "components",
+            self.components.iter().map(component_to_xml).collect(),
+        );
+        root.push_list(
+            "dependencies",
+            self.dependencies
+                .iter()
+                .map(|dependency| Element {
+                    children: dependency
+                        .depends_on
+                        .iter()
+                        .map(|depends_on| {
+                            Element::new("dependency").with_attribute("ref", depends_on)
+                        })
+                        .collect(),
+                    ..Element::new("dependency").with_attribute("ref", &dependency.dependency_ref)
+                })
+                .collect(),
+        );
+
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+        writer
+            .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+            .map_err(xml_error)?;
+        write_element(&mut writer, &root)?;
+        String::from_utf8(writer.into_inner()).map_err(xml_error)
+    }
+}
+
+fn metadata_from_xml(element: &Element) -> Result<Metadata, SpdxError> {
+    let mut tools: Vec<Tool> = element
+        .list("tools", "tool")
+        .map(|tool| Tool {
+            vendor: tool.child_text("vendor"),
+            name: tool.child_text("name"),
+            version: tool.child_text("version"),
+        })
+        .collect();
+    // CycloneDX 1.5 lists tools as components.
+    if let Some(components) = element
+        .child("tools")
+        .and_then(|tools| tools.child("components"))
+    {
+        for component in components.children("component") {
+            let component = component_from_xml(component)?;
+            tools.push(Tool {
+                vendor: component.publisher,
+                name: Some(component.name),
+                version: component.version,
+            });
+        }
+    }
+
+    Ok(Metadata {
+        timestamp: element
+            .child_text("timestamp")
+            .map(|timestamp| DateTime::parse_from_rfc3339(&timestamp))
+            .transpose()?
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        tools,
+        authors: element
+            .list("authors", "author")
+            .map(|author| OrganizationalContact {
+                name: author.child_text("name"),
+                email: author.child_text("email"),
+            })
+            .collect(),
+        component: element
+            .child("component")
+            .map(component_from_xml)
+            .transpose()?
+            .map(Box::new),
+        supplier: element
+            .child("supplier")
+            .map(|supplier| OrganizationalEntity {
+                name: supplier.child_text("name"),
+            }),
+    })
+}
+
+fn metadata_to_xml(metadata: &Metadata) -> Element {
+    let mut element = Element::new("metadata");
+    element.push_text(
+        "timestamp",
+        metadata.timestamp.as_ref().map(format_timestamp).as_deref(),
+    );
+    element.push_list(
+        "tools",
+        metadata
+            .tools
+            .iter()
+            .map(|tool| {
+                let mut element = Element::new("tool");
+                element.push_text("vendor", tool.vendor.as_deref());
+                element.push_text("name", tool.name.as_deref());
+                element.push_text("version", tool.version.as_deref());
+                element
+            })
+            .collect(),
+    );
+    element.push_list(
+        "authors",
+        metadata
+            .authors
+            .iter()
+            .map(|author| {
+                let mut element = Element::new("author");
+                element.push_text("name", author.name.as_deref());
+                element.push_text("email", author.email.as_deref());
+                element
+            })
+            .collect(),
+    );
+    if let Some(component) = &metadata.component {
+        element.push(component_to_xml(component));
+    }
+    if let Some(supplier) = &metadata.supplier {
+        let mut supplier_element = Element::new("supplier");
+        supplier_element.push_text("name", supplier.name.as_deref());
+        element.push(supplier_element);
+    }
+    element
+}
+
+fn component_from_xml(element: &Element) -> Result<Component, SpdxError> {
+    Ok(Component {
+        component_type: ComponentType::parse(element.required_attribute("type")?)?,
+        bom_ref: element.attribute("bom-ref").map(ToString::to_string),
+        supplier: element
+            .child("supplier")
+            .map(|supplier| OrganizationalEntity {
+                name: supplier.child_text("name"),
+            }),
+        author: element.child_text("author"),
+        publisher: element.child_text("publisher"),
+        group: element.child_text("group"),
+        name: element.required_text("name")?,
+        version: element.child_text("version"),
+        description: element.child_text("description"),
+        hashes: element
+            .list("hashes", "hash")
+            .map(|hash| {
+                Ok(Hash {
+                    alg: hash.required_attribute("alg")?.to_string(),
+                    content: hash.text.clone(),
+                })
+            })
+            .collect::<Result<_, SpdxError>>()?,
+        licenses: element
+            .child("licenses")
+            .map(|licenses| {
+                licenses
+                    .children
+                    .iter()
+                    .filter_map(|choice| match choice.name.as_str() {
+                        "license" => Some(LicenseChoice::License {
+                            license: License {
+                                id: choice.child_text("id"),
+                                name: choice.child_text("name"),
+                                text: choice.child("text").map(|text| AttachedText {
+                                    content_type: text
+                                        .attribute("content-type")
+                                        .map(ToString::to_string),
+                                    content: text.text.clone(),
+                                }),
+                                url: choice.child_text("url"),
+                            },
+                        }),
+                        "expression" => Some(LicenseChoice::Expression {
+                            expression: choice.text.clone(),
+                        }),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        copyright: element.child_text("copyright"),
+        cpe: element.child_text("cpe"),
+        purl: element.child_text("purl"),
+        external_references: element
+            .list("externalReferences", "reference")
+            .map(|reference| {
+                Ok(ExternalReference {
+                    reference_type: reference.required_attribute("type")?.to_string(),
+                    url: reference.required_text("url")?,
+                    comment: reference.child_text("comment"),
+                })
+            })
+            .collect::<Result<_, SpdxError>>()?,
+        components: element
+            .list("components", "component")
+            .map(component_from_xml)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn component_to_xml(component: &Component) -> Element {
+    let mut element =
+        Element::new("component").with_attribute("type", component.component_type.as_str());
+    if let Some(bom_ref) = &component.bom_ref {
+        element = element.with_attribute("bom-ref", bom_ref);
+    }
+
+    // The order of the elements is defined by the schema.
+    if let Some(supplier) = &component.supplier {
+        let mut supplier_element = Element::new("supplier");
+        supplier_element.push_text("name", supplier.name.as_deref());
+        element.push(supplier_element);
+    }
+    element.push_text("author", component.author.as_deref());
+    element.push_text("publisher", component.publisher.as_deref());
+    element.push_text("group", component.group.as_deref());
+    element.push_text("name", Some(&component.name));
+    element.push_text("version", component.version.as_deref());
+    element.push_text("description", component.description.as_deref());
+    element.push_list(
+        "hashes",
+        component
+            .hashes
+            .iter()
+            .map(|hash| {
+                Element::new("hash")
+                    .with_attribute("alg", &hash.alg)
+                    .with_text(&hash.content)
+            })
+            .collect(),
+    );
+    element.push_list(
+        "licenses",
+        component
+            .licenses
+            .iter()
+            .map(|choice| match choice {
+                LicenseChoice::License { license } => {
+                    let mut element = Element::new("license");
+                    element.push_text("id", license.id.as_deref());
+                    element.push_text("name", license.name.as_deref());
+                    if let Some(text) = &license.text {
+                        let mut text_element = Element::new("text").with_text(&text.content);
+                        if let Some(content_type) = &text.content_type {
+                            text_element =
+                                text_element.with_attribute("content-type", content_type);
+                        }
+                        element.push(text_element);
+                    }
+                    element.push_text("url", license.url.as_deref());
+                    element
+                }
+                LicenseChoice::Expression { expression } => {
+                    Element::new("expression").with_text(expression)
+                }
+            })
+            .collect(),
+    );
+    element.push_text("copyright", component.copyright.as_deref());
+    element.push_text("cpe", component.cpe.as_deref());
+    element.push_text("purl", component.purl.as_deref());
+    element.push_list(
+        "externalReferences",
+        component
+            .external_references
+            .iter()
+            .map(|reference| {
+                let mut element =
+                    Element::new("reference").with_attribute("type", &reference.reference_type);
+                element.push_text("url", Some(&reference.url));
+                element.push_text("comment", reference.comment.as_deref());
+                element
+            })
+            .collect(),
+    );
+    element.push_list(
+        "components",
+        component.components.iter().map(component_to_xml).collect(),
+    );
+    element
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    #[test]
+    fn xml_matches_json() {
+        let from_xml =
+            Bom::from_xml(&read_to_string("tests/data/cyclonedx/bom-1.5.xml").unwrap()).unwrap();
+        let mut from_json =
+            Bom::from_json(&read_to_string("tests/data/cyclonedx/bom-1.4.json").unwrap()).unwrap();
+        assert_eq!(from_xml.spec_version, CycloneDxVersion::V1_5);
+        from_json.spec_version = CycloneDxVersion::V1_5;
+        assert_eq!(from_xml, from_json);
+    }
+
+    #[test]
+    fn bom_roundtrips_through_xml() {
+        let bom =
+            Bom::from_json(&read_to_string("tests/data/cyclonedx/bom-1.4.json").unwrap()).unwrap();
+        let xml = bom.to_xml().unwrap();
+        assert!(xml.contains(r#"<bom xmlns="http://cyclonedx.org/schema/bom/1.4""#));
+        assert_eq!(Bom::from_xml(&xml).unwrap(), bom);
+    }
+
+    #[test]
+    fn unknown_namespace_is_an_error() {
+        let result = Bom::from_xml(r#"<bom xmlns="http://cyclonedx.org/schema/bom/1.1"/>"#);
+        assert!(matches!(result, Err(SpdxError::CycloneDx(_))));
+    }
+}
@@ -5,6 +5,8 @@
 use std::io;
 use thiserror::Error;
 
+use crate::models::ValidationError;
+
 #[derive(Debug, Error)]
 pub enum SpdxError {
     #[error("Error parsing the SPDX Expression.")]
@@ -30,4 +32,43 @@ pub enum SpdxError {
 
     #[error("Error parsing tag-value: {0}")]
     TagValueParse(String),
+
+    #[error("Error parsing CPE: {0}")]
+    Cpe(String),
+
+    #[error("Error parsing purl: {0}")]
+    Purl(String),
+
+    #[error("Error converting CycloneDX: {0}")]
+    CycloneDx(String),
+
+    #[error("Error parsing XML: {0}")]
+    Xml(String),
+
+    #[error("Error writing SPDX: {0}")]
+    Write(String),
+
+    #[error("Error parsing query: {0}")]
+    Query(String),
+
+    #[error("Error importing packages: {0}")]
+    Import(String),
+
+    #[error(
+        "Invalid SPDX document: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    Validation(Vec<ValidationError>),
+
+    #[error("Error parsing TOML.")]
+    Toml {
+        #[from]
+        source: toml::de::Error,
+    },
+
+    #[error("Error parsing JSON.")]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
 }
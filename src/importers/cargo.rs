@@ -0,0 +1,507 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Import of Rust crates from `Cargo.lock` files and `cargo metadata` output.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use log::warn;
+use serde::Deserialize;
+
+use crate::{
+    error::SpdxError,
+    models::{Algorithm, Checksum, Purl},
+};
+
+use super::{DependencyKind, ImportedDependency, ImportedPackage, Importer};
+
+/// Sources of crates published on crates.io.
+const CRATES_IO_SOURCES: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// Creates SPDX documents from the crates of a Cargo project.
+///
+/// `Cargo.lock` includes the checksums of the crates but no license information or dependency
+/// kinds, while `cargo metadata` includes those but no checksums. Both can be combined with
+/// [`CargoImporter::with_checksums_from`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CargoImporter {
+    crates: Vec<Crate>,
+    dependencies: Vec<ImportedDependency>,
+}
+
+/// A crate of a [`CargoImporter`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Crate {
+    pub name: String,
+    pub version: String,
+
+    /// Registry or repository of the crate, `None` for local crates.
+    pub source: Option<String>,
+
+    /// SHA256 checksum of the crate archive.
+    pub checksum: Option<String>,
+
+    /// The license expression from the manifest.
+    pub license: Option<String>,
+
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+
+    /// Whether the crate is a member of the workspace.
+    pub workspace_member: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoLock {
+    #[serde(default, rename = "package")]
+    packages: Vec<LockPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    checksum: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoMetadata {
+    packages: Vec<MetadataPackage>,
+    #[serde(default)]
+    workspace_members: Vec<String>,
+    resolve: Option<Resolve>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataPackage {
+    id: String,
+    name: String,
+    version: String,
+    source: Option<String>,
+    license: Option<String>,
+    description: Option<String>,
+    homepage: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Resolve {
+    nodes: Vec<ResolveNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveNode {
+    id: String,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(default)]
+    deps: Vec<NodeDep>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeDep {
+    pkg: String,
+    #[serde(default)]
+    dep_kinds: Vec<DepKind>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DepKind {
+    kind: Option<String>,
+}
+
+impl CargoImporter {
+    /// Read the crates from a `Cargo.lock` file. Crates without a source are considered
+    /// workspace members and all dependencies are normal dependencies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read or parsed.
+    pub fn from_lockfile<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        Self::from_lockfile_str(&fs::read_to_string(path)?)
+    }
+
+    /// Read the crates from the content of a `Cargo.lock` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Toml`] if the input is not a valid lockfile.
+    pub fn from_lockfile_str(input: &str) -> Result<Self, SpdxError> {
+        let lockfile: CargoLock = toml::from_str(input)?;
+
+        let crates = lockfile
+            .packages
+            .iter()
+            .map(|package| Crate {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                source: package.source.clone(),
+                checksum: package.checksum.clone(),
+                workspace_member: package.source.is_none(),
+                ..Crate::default()
+            })
+            .collect::<Vec<_>>();
+
+        let mut dependencies = Vec::new();
+        for (from, package) in lockfile.packages.iter().enumerate() {
+            for dependency in &package.dependencies {
+                match find_locked_crate(&crates, dependency) {
+                    Some(to) => dependencies.push(ImportedDependency {
+                        from,
+                        to,
+                        kind: DependencyKind::Normal,
+                    }),
+                    None => warn!("Dependency {dependency} of {} not found.", package.name),
+                }
+            }
+        }
+
+        Ok(Self {
+            crates,
+            dependencies,
+        })
+    }
+
+    /// Read the crates from saved `cargo metadata --format-version 1` output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read or parsed.
+    pub fn from_metadata<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        Self::from_metadata_str(&fs::read_to_string(path)?)
+    }
+
+    /// Read the crates from `cargo metadata --format-version 1` output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if the input is not valid metadata.
+    pub fn from_metadata_str(input: &str) -> Result<Self, SpdxError> {
+        let metadata: CargoMetadata = serde_json::from_str(input)?;
+
+        let indices = metadata
+            .packages
+            .iter()
+            .enumerate()
+            .map(|(index, package)| (package.id.as_str(), index))
+            .collect::<HashMap<_, _>>();
+        let crates = metadata
+            .packages
+            .iter()
+            .map(|package| Crate {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                source: package.source.clone(),
+                checksum: None,
+                license: package.license.clone(),
+                description: package.description.clone(),
+                homepage: package.homepage.clone(),
+                workspace_member: metadata.workspace_members.contains(&package.id),
+            })
+            .collect();
+
+        let mut dependencies = Vec::new();
+        for node in metadata.resolve.iter().flat_map(|resolve| &resolve.nodes) {
+            let from = match indices.get(node.id.as_str()) {
+                Some(from) => *from,
+                None => continue,
+            };
+
+            // Older versions of Cargo only list the ids of the dependencies.
+            let deps = if node.deps.is_empty() {
+                node.dependencies
+                    .iter()
+                    .map(|id| (id.as_str(), vec![DependencyKind::Normal]))
+                    .collect::<Vec<_>>()
+            } else {
+                node.deps
+                    .iter()
+                    .map(|dep| (dep.pkg.as_str(), dependency_kinds(&dep.dep_kinds)))
+                    .collect()
+            };
+
+            for (id, kinds) in deps {
+                if let Some(to) = indices.get(id) {
+                    for kind in kinds {
+                        dependencies.push(ImportedDependency {
+                            from,
+                            to: *to,
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            crates,
+            dependencies,
+        })
+    }
+
+    /// Copy the checksums of the crates from another importer, typically one created from the
+    /// `Cargo.lock` of the same project.
+    #[must_use]
+    pub fn with_checksums_from(mut self, other: &Self) -> Self {
+        for krate in &mut self.crates {
+            if let Some(checksum) = other
+                .crates
+                .iter()
+                .find(|other| {
+                    other.name == krate.name
+                        && other.version == krate.version
+                        && other.source == krate.source
+                })
+                .and_then(|other| other.checksum.clone())
+            {
+                krate.checksum = Some(checksum);
+            }
+        }
+        self
+    }
+
+    /// The imported crates.
+    pub fn crates(&self) -> &[Crate] {
+        &self.crates
+    }
+}
+
+impl Importer for CargoImporter {
+    fn packages(&self) -> Vec<ImportedPackage> {
+        self.crates
+            .iter()
+            .map(|krate| ImportedPackage {
+                checksums: krate
+                    .checksum
+                    .iter()
+                    .map(|checksum| Checksum::new(Algorithm::SHA256, checksum))
+                    .collect(),
+                download_location: krate.download_location(),
+                // The deprecated `/` separator means `OR`.
+                license: krate
+                    .license
+                    .as_ref()
+                    .map(|license| license.replace('/', " OR ")),
+                description: krate.description.clone(),
+                homepage: krate.homepage.clone(),
+                root: krate.workspace_member,
+                ..ImportedPackage::new(&krate.name, &krate.version, krate.purl())
+            })
+            .collect()
+    }
+
+    fn dependencies(&self) -> Vec<ImportedDependency> {
+        self.dependencies.clone()
+    }
+}
+
+impl Crate {
+    /// The purl of the crate.
+    pub fn purl(&self) -> Purl {
+        Purl::new("cargo", &self.name, Some(&self.version))
+    }
+
+    /// Download location of the crate archive on crates.io, or the commit of a git dependency.
+    pub fn download_location(&self) -> String {
+        match self.source.as_deref() {
+            Some(source) if CRATES_IO_SOURCES.contains(&source) => format!(
+                "https://crates.io/api/v1/crates/{}/{}/download",
+                self.name, self.version
+            ),
+            Some(source) if source.starts_with("git+") => {
+                let (url, commit) = source
+                    .split_once('#')
+                    .map_or((source, None), |(url, commit)| (url, Some(commit)));
+                let url = url.split('?').next().unwrap_or(url);
+                commit.map_or_else(|| url.to_string(), |commit| format!("{url}@{commit}"))
+            }
+            _ => "NOASSERTION".to_string(),
+        }
+    }
+}
+
+/// Find the crate of a dependency in `Cargo.lock`. The dependency is either `name`,
+/// `name version` or `name version (source)`, depending on what is needed to make it unique.
+fn find_locked_crate(crates: &[Crate], dependency: &str) -> Option<usize> {
+    let mut parts = dependency.splitn(3, ' ');
+    let name = parts.next()?;
+    let version = parts.next();
+    let source = parts
+        .next()
+        .map(|source| source.trim_start_matches('(').trim_end_matches(')'));
+
+    crates.iter().position(|krate| {
+        krate.name == name
+            && version.is_none_or(|version| krate.version == version)
+            && source.is_none_or(|source| krate.source.as_deref() == Some(source))
+    })
+}
+
+fn dependency_kinds(dep_kinds: &[DepKind]) -> Vec<DependencyKind> {
+    let mut kinds = Vec::new();
+    for dep_kind in dep_kinds {
+        let kind = match dep_kind.kind.as_deref() {
+            Some("build") => DependencyKind::Build,
+            Some("dev") => DependencyKind::Dev,
+            _ => DependencyKind::Normal,
+        };
+        // The same kind is listed once per target platform.
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    if kinds.is_empty() {
+        kinds.push(DependencyKind::Normal);
+    }
+    kinds
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::{RelationshipType, SpdxExpression, SPDX};
+
+    use super::*;
+
+    fn relationships(spdx: &SPDX) -> Vec<(&str, RelationshipType, &str)> {
+        spdx.relationships
+            .iter()
+            .map(|relationship| {
+                (
+                    relationship.spdx_element_id.as_str(),
+                    relationship.relationship_type.clone(),
+                    relationship.related_spdx_element.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lockfile_is_imported() {
+        let spdx = CargoImporter::from_lockfile("tests/data/cargo/Cargo.lock")
+            .unwrap()
+            .to_spdx("example-app");
+        assert_eq!(spdx.package_information.len(), 7);
+
+        let serde = &spdx.package_information[3];
+        assert_eq!(
+            serde.package_spdx_identifier,
+            "SPDXRef-Package-cargo-serde-1.0.188"
+        );
+        assert_eq!(
+            serde.package_download_location,
+            "https://crates.io/api/v1/crates/serde/1.0.188/download"
+        );
+        assert_eq!(
+            serde.package_checksum,
+            vec![Checksum::new(
+                Algorithm::SHA256,
+                "cf9e0fcba69a370eed61bcf2b728575f726b50b55cba78064753d708ddc7549e"
+            )]
+        );
+        assert_eq!(
+            serde.purls().unwrap(),
+            vec![Purl::new("cargo", "serde", Some("1.0.188"))]
+        );
+
+        let relationships = relationships(&spdx);
+        assert!(relationships.contains(&(
+            "SPDXRef-DOCUMENT",
+            RelationshipType::Describes,
+            "SPDXRef-Package-cargo-example-app-0.1.0"
+        )));
+        assert!(relationships.contains(&(
+            "SPDXRef-Package-cargo-example-app-0.1.0",
+            RelationshipType::DependsOn,
+            "SPDXRef-Package-cargo-syn-2.0.29"
+        )));
+        assert!(relationships.contains(&(
+            "SPDXRef-Package-cargo-serde-1.0.188",
+            RelationshipType::DependsOn,
+            "SPDXRef-Package-cargo-syn-1.0.109"
+        )));
+    }
+
+    #[test]
+    fn git_dependency_has_commit_as_download_location() {
+        let importer = CargoImporter::from_lockfile("tests/data/cargo/Cargo.lock").unwrap();
+        assert_eq!(
+            importer.crates()[6].download_location(),
+            "git+https://github.com/rhysd/tinyjson@9ea4f5fd23d4c6e0a0b1a5d5c6d1e7f8a9b0c1d2"
+        );
+        assert_eq!(importer.crates()[1].download_location(), "NOASSERTION");
+    }
+
+    #[test]
+    fn metadata_is_imported_with_dependency_kinds() {
+        let spdx = CargoImporter::from_metadata("tests/data/cargo/metadata.json")
+            .unwrap()
+            .to_spdx("example-app");
+
+        let app = "SPDXRef-Package-cargo-example-app-0.1.0";
+        let relationships = relationships(&spdx);
+        assert!(relationships.contains(&(
+            "SPDXRef-Package-cargo-cc-1.0.83",
+            RelationshipType::BuildDependencyOf,
+            app
+        )));
+        assert!(relationships.contains(&(
+            app,
+            RelationshipType::DependsOn,
+            "SPDXRef-Package-cargo-serde-1.0.188"
+        )));
+        assert!(relationships.contains(&(
+            "SPDXRef-Package-cargo-serde-1.0.188",
+            RelationshipType::DevDependencyOf,
+            app
+        )));
+        assert!(!relationships.contains(&(
+            app,
+            RelationshipType::DependsOn,
+            "SPDXRef-Package-cargo-syn-2.0.29"
+        )));
+
+        let serde = &spdx.package_information[3];
+        assert_eq!(
+            serde.declared_license,
+            Some(SpdxExpression::parse("MIT OR Apache-2.0").unwrap())
+        );
+        assert_eq!(
+            serde.package_home_page,
+            Some("https://serde.rs".to_string())
+        );
+        assert!(serde.package_checksum.is_empty());
+
+        // The legacy separator is converted to OR.
+        assert_eq!(
+            spdx.package_information[5].declared_license,
+            Some(SpdxExpression::parse("MIT OR Apache-2.0").unwrap())
+        );
+    }
+
+    #[test]
+    fn checksums_are_added_from_lockfile() {
+        let lockfile = CargoImporter::from_lockfile("tests/data/cargo/Cargo.lock").unwrap();
+        let importer = CargoImporter::from_metadata("tests/data/cargo/metadata.json")
+            .unwrap()
+            .with_checksums_from(&lockfile);
+        assert_eq!(
+            importer.crates()[0].checksum,
+            Some("f1174fb0b6ec23863f8b971027804a42614e347eafb0a95bf0b12cdae21fc4d0".to_string())
+        );
+        assert_eq!(importer.crates()[1].checksum, None);
+    }
+
+    #[test]
+    fn invalid_lockfile_is_an_error() {
+        assert!(matches!(
+            CargoImporter::from_lockfile_str("[[package]]\nname = 1"),
+            Err(SpdxError::Toml { .. })
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Import of Go modules from `go.mod` and `go.sum` files.
+
+use std::{fs, path::Path};
+
+use log::warn;
+
+use crate::{
+    error::SpdxError,
+    models::{Algorithm, Purl},
+};
+
+use super::{base64_checksum, DependencyKind, ImportedDependency, ImportedPackage, Importer};
+
+/// Creates SPDX documents from the modules required in `go.mod`.
+///
+/// `go.mod` doesn't include the dependency graph between the required modules, so the main
+/// module depends on all of them, including the indirect ones. The checksums are read from
+/// `go.sum` with [`GoImporter::with_go_sum`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoImporter {
+    packages: Vec<ImportedPackage>,
+    dependencies: Vec<ImportedDependency>,
+}
+
+impl GoImporter {
+    /// Read the modules from a `go.mod` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read or parsed.
+    pub fn from_go_mod<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        Self::from_go_mod_str(&fs::read_to_string(path)?)
+    }
+
+    /// Read the modules from the content of a `go.mod` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Import`] if the file doesn't declare the main module.
+    pub fn from_go_mod_str(input: &str) -> Result<Self, SpdxError> {
+        let mut module = None;
+        let mut requirements = Vec::new();
+        let mut block: Option<&str> = None;
+
+        for line in input.lines() {
+            let line = line.split("//").next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(current) = block {
+                if line == ")" {
+                    block = None;
+                } else if current == "require" {
+                    requirements.extend(requirement(line));
+                }
+                continue;
+            }
+
+            let (directive, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            match directive {
+                "module" => module = Some(rest.trim_matches('"').to_string()),
+                _ if rest == "(" => block = Some(directive),
+                "require" => requirements.extend(requirement(rest)),
+                "replace" => warn!("Replace directive {rest} is ignored."),
+                _ => {}
+            }
+        }
+
+        let module = module
+            .ok_or_else(|| SpdxError::Import("go.mod doesn't declare a module".to_string()))?;
+        let mut main = ImportedPackage::new(&module, "", go_purl(&module, None));
+        main.root = true;
+
+        let mut packages = vec![main];
+        let mut dependencies = Vec::new();
+        for (path, version) in requirements {
+            dependencies.push(ImportedDependency {
+                from: 0,
+                to: packages.len(),
+                kind: DependencyKind::Normal,
+            });
+            let mut package = ImportedPackage::new(&path, &version, go_purl(&path, Some(&version)));
+            package.download_location = format!(
+                "https://proxy.golang.org/{}/@v/{}.zip",
+                escape_module_path(&path),
+                escape_module_path(&version)
+            );
+            packages.push(package);
+        }
+
+        Ok(Self {
+            packages,
+            dependencies,
+        })
+    }
+
+    /// Add the checksums of the modules from a `go.sum` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read.
+    pub fn with_go_sum<P: AsRef<Path>>(self, path: P) -> Result<Self, SpdxError> {
+        Ok(self.with_go_sum_str(&fs::read_to_string(path)?))
+    }
+
+    /// Add the checksums of the modules from the content of a `go.sum` file.
+    ///
+    /// The `h1:` hashes are SHA-256 digests of a listing of the files in the module, so they are
+    /// stored as SHA256 checksums even though they are not checksums of the module archive.
+    #[must_use]
+    pub fn with_go_sum_str(mut self, input: &str) -> Self {
+        for line in input.lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (path, version, hash) = match fields.as_slice() {
+                [path, version, hash] => (*path, *version, *hash),
+                _ => continue,
+            };
+            // The hash of the `go.mod` file only.
+            if version.ends_with("/go.mod") {
+                continue;
+            }
+
+            let checksum = hash
+                .strip_prefix("h1:")
+                .and_then(|digest| base64_checksum(Algorithm::SHA256, digest));
+            if checksum.is_none() {
+                warn!("Unsupported hash {hash} for {path}.");
+            }
+            if let Some(package) = self
+                .packages
+                .iter_mut()
+                .find(|package| package.name == path && package.version == version)
+            {
+                package.checksums.extend(checksum);
+            }
+        }
+        self
+    }
+}
+
+impl Importer for GoImporter {
+    fn packages(&self) -> Vec<ImportedPackage> {
+        self.packages.clone()
+    }
+
+    fn dependencies(&self) -> Vec<ImportedDependency> {
+        self.dependencies.clone()
+    }
+}
+
+/// Parse `<module path> <version>` of a require directive.
+fn requirement(line: &str) -> Option<(String, String)> {
+    let mut fields = line.split_whitespace();
+    let path = fields.next()?.trim_matches('"');
+    let version = fields.next()?;
+    Some((path.to_string(), version.to_string()))
+}
+
+fn go_purl(path: &str, version: Option<&str>) -> Purl {
+    match path.rsplit_once('/') {
+        Some((namespace, name)) => Purl::new("golang", name, version).with_namespace(namespace),
+        None => Purl::new("golang", path, version),
+    }
+}
+
+/// Escape a module path or version for the module proxy by replacing uppercase letters with `!`
+/// followed by the lowercase letter.
+fn escape_module_path(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::{Checksum, RelationshipType};
+
+    use super::*;
+
+    fn importer() -> GoImporter {
+        GoImporter::from_go_mod("tests/data/go/go.mod")
+            .unwrap()
+            .with_go_sum("tests/data/go/go.sum")
+            .unwrap()
+    }
+
+    #[test]
+    fn go_mod_is_imported() {
+        let packages = importer().packages();
+        assert_eq!(packages.len(), 4);
+
+        assert!(packages[0].root);
+        assert_eq!(
+            packages[0].purl,
+            Purl::new("golang", "app", None).with_namespace("github.com/example")
+        );
+        assert_eq!(
+            packages[1].purl,
+            Purl::new("golang", "toml", Some("v1.3.2")).with_namespace("github.com/BurntSushi")
+        );
+        assert_eq!(
+            packages[1].download_location,
+            "https://proxy.golang.org/github.com/!burnt!sushi/toml/@v/v1.3.2.zip"
+        );
+        assert_eq!(packages[2].name, "golang.org/x/text");
+        assert_eq!(packages[3].name, "github.com/google/uuid");
+    }
+
+    #[test]
+    fn checksums_are_read_from_go_sum() {
+        let packages = importer().packages();
+        assert_eq!(
+            packages[3].checksums,
+            vec![Checksum::new(
+                Algorithm::SHA256,
+                "cd642ee419a0ab8b50a2a0cf23a0762c5a50a907b3d082ae70cfbb99a83e8e1c"
+            )]
+        );
+        assert!(packages[0].checksums.is_empty());
+    }
+
+    #[test]
+    fn main_module_depends_on_requirements() {
+        let spdx = importer().to_spdx("app");
+        let main = &spdx.package_information[0];
+        assert_eq!(
+            main.package_spdx_identifier,
+            "SPDXRef-Package-golang-github.com-example-app"
+        );
+        assert_eq!(main.package_version, None);
+        assert_eq!(
+            spdx.relationships
+                .iter()
+                .filter(|relationship| relationship.relationship_type
+                    == RelationshipType::DependsOn
+                    && relationship.spdx_element_id == main.package_spdx_identifier)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn go_mod_without_module_is_an_error() {
+        assert!(matches!(
+            GoImporter::from_go_mod_str("go 1.21\n"),
+            Err(SpdxError::Import(_))
+        ));
+    }
+}
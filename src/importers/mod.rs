@@ -0,0 +1,277 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Creation of SPDX documents from the lockfiles and metadata of package managers.
+//!
+//! The importers only read the given files, so they work offline. Every importer implements
+//! [`Importer`], which turns the packages and the dependencies between them into an [`SPDX`]
+//! document.
+
+use std::{collections::HashSet, fmt::Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use log::warn;
+
+use crate::models::{
+    sanitize_spdx_id, Algorithm, Checksum, CreationInfo, ExternalPackageReference,
+    ExternalPackageReferenceCategory, PackageInformation, PrimaryPackagePurpose, Purl,
+    Relationship, RelationshipType, SpdxExpression, SPDX,
+};
+
+mod cargo;
+mod go;
+mod npm;
+mod python;
+
+pub use cargo::*;
+pub use go::*;
+pub use npm::*;
+pub use python::*;
+
+/// Common interface of the importers.
+pub trait Importer {
+    /// The imported packages.
+    fn packages(&self) -> Vec<ImportedPackage>;
+
+    /// The dependencies between the packages returned by [`Importer::packages`].
+    fn dependencies(&self) -> Vec<ImportedDependency>;
+
+    /// Create an SPDX document with a package for every imported package. The document
+    /// describes the root packages, or the packages no other package depends on if there are no
+    /// roots.
+    fn to_spdx(&self, document_name: &str) -> SPDX {
+        build_document(document_name, &self.packages(), &self.dependencies())
+    }
+}
+
+/// A package read by an [`Importer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedPackage {
+    pub name: String,
+    pub version: String,
+    pub purl: Purl,
+
+    /// `NOASSERTION` if not known.
+    pub download_location: String,
+
+    pub checksums: Vec<Checksum>,
+
+    /// License expression as given by the package manager.
+    pub license: Option<String>,
+
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+
+    /// Whether the package is the project itself rather than a dependency.
+    pub root: bool,
+}
+
+impl ImportedPackage {
+    /// Create a package without checksums, license or description.
+    pub fn new(name: &str, version: &str, purl: Purl) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            purl,
+            download_location: "NOASSERTION".to_string(),
+            checksums: Vec::new(),
+            license: None,
+            description: None,
+            homepage: None,
+            root: false,
+        }
+    }
+
+    /// The license as an SPDX expression, `None` if it's missing or not a valid expression.
+    pub fn license_expression(&self) -> Option<SpdxExpression> {
+        let license = self.license.as_ref()?;
+        match SpdxExpression::parse(license) {
+            Ok(expression) => Some(expression),
+            Err(error) => {
+                warn!("Invalid license {license} for {}: {error}", self.name);
+                None
+            }
+        }
+    }
+
+    fn package_information(&self, id: &str) -> PackageInformation {
+        let purpose = if self.root {
+            PrimaryPackagePurpose::Application
+        } else {
+            PrimaryPackagePurpose::Library
+        };
+
+        PackageInformation {
+            package_name: self.name.clone(),
+            package_spdx_identifier: id.to_string(),
+            package_version: Some(self.version.clone()).filter(|version| !version.is_empty()),
+            package_download_location: self.download_location.clone(),
+            files_analyzed: Some(false),
+            package_checksum: self.checksums.clone(),
+            package_home_page: self.homepage.clone(),
+            declared_license: self.license_expression(),
+            package_summary_description: self.description.clone(),
+            external_reference: vec![ExternalPackageReference::new(
+                ExternalPackageReferenceCategory::PackageManager,
+                "purl".to_string(),
+                self.purl.to_string(),
+                None,
+            )],
+            primary_package_purpose: Some(purpose),
+            ..PackageInformation::default()
+        }
+    }
+}
+
+/// A dependency between two [`ImportedPackage`]s, given as indices to [`Importer::packages`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportedDependency {
+    pub from: usize,
+    pub to: usize,
+    pub kind: DependencyKind,
+}
+
+/// Kinds of dependencies, each converted to a different relationship type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    /// `DEPENDS_ON`
+    Normal,
+    /// `BUILD_DEPENDENCY_OF`
+    Build,
+    /// `DEV_DEPENDENCY_OF`
+    Dev,
+    /// `OPTIONAL_DEPENDENCY_OF`
+    Optional,
+}
+
+impl ImportedDependency {
+    fn relationship(&self, from: &str, to: &str) -> Relationship {
+        match self.kind {
+            DependencyKind::Normal => {
+                Relationship::new(from, to, RelationshipType::DependsOn, None)
+            }
+            DependencyKind::Build => {
+                Relationship::new(to, from, RelationshipType::BuildDependencyOf, None)
+            }
+            DependencyKind::Dev => {
+                Relationship::new(to, from, RelationshipType::DevDependencyOf, None)
+            }
+            DependencyKind::Optional => {
+                Relationship::new(to, from, RelationshipType::OptionalDependencyOf, None)
+            }
+        }
+    }
+}
+
+fn build_document(
+    document_name: &str,
+    packages: &[ImportedPackage],
+    dependencies: &[ImportedDependency],
+) -> SPDX {
+    let mut spdx = new_document(document_name);
+
+    let mut used_ids = HashSet::new();
+    let mut ids = Vec::with_capacity(packages.len());
+    for package in packages {
+        // The version is missing for example from the main module of a Go project.
+        let name = if package.version.is_empty() {
+            package.name.clone()
+        } else {
+            format!("{}-{}", package.name, package.version)
+        };
+        let base = format!(
+            "SPDXRef-Package-{}-{}",
+            package.purl.purl_type,
+            sanitize_spdx_id(&name)
+        );
+        let mut id = base.clone();
+        let mut counter = 1;
+        while used_ids.contains(&id) {
+            counter += 1;
+            id = format!("{base}-{counter}");
+        }
+        spdx.package_information
+            .push(package.package_information(&id));
+        used_ids.insert(id.clone());
+        ids.push(id);
+    }
+
+    let has_roots = packages.iter().any(|package| package.root);
+    let dependency_targets = dependencies
+        .iter()
+        .map(|dependency| dependency.to)
+        .collect::<HashSet<_>>();
+    for (index, package) in packages.iter().enumerate() {
+        let described = if has_roots {
+            package.root
+        } else {
+            !dependency_targets.contains(&index)
+        };
+        if described {
+            spdx.relationships.push(Relationship::new(
+                "SPDXRef-DOCUMENT",
+                &ids[index],
+                RelationshipType::Describes,
+                None,
+            ));
+        }
+    }
+
+    let mut relationships = HashSet::new();
+    for dependency in dependencies {
+        let relationship = dependency.relationship(&ids[dependency.from], &ids[dependency.to]);
+        if relationships.insert(relationship.clone()) {
+            spdx.relationships.push(relationship);
+        }
+    }
+
+    spdx
+}
+
+/// An empty SPDX 2.3 document created by this library.
+fn new_document(name: &str) -> SPDX {
+    let mut spdx = SPDX::new(name);
+    let document = &mut spdx.document_creation_information;
+    document.spdx_version = "SPDX-2.3".to_string();
+    document.creation_info = CreationInfo {
+        license_list_version: None,
+        creators: vec![format!(
+            "Tool: {}-{}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        )],
+        created: Utc::now(),
+        creator_comment: None,
+    };
+    spdx
+}
+
+/// Convert a base64 encoded digest, e.g. from an npm integrity string, to a checksum.
+fn base64_checksum(algorithm: Algorithm, digest: &str) -> Option<Checksum> {
+    let bytes = STANDARD.decode(digest).ok()?;
+    let mut value = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(value, "{byte:02x}");
+    }
+    Some(Checksum::new(algorithm, &value))
+}
+
+/// Checksum from a hash in the form `<algorithm>:<hex digest>`, as used by pip and Poetry.
+fn prefixed_checksum(hash: &str) -> Option<Checksum> {
+    let (algorithm, value) = hash.split_once(':')?;
+    let algorithm = match algorithm {
+        "md5" => Algorithm::MD5,
+        "sha1" => Algorithm::SHA1,
+        "sha224" => Algorithm::SHA224,
+        "sha256" => Algorithm::SHA256,
+        "sha384" => Algorithm::SHA384,
+        "sha512" => Algorithm::SHA512,
+        _ => {
+            warn!("Unsupported hash algorithm {algorithm}.");
+            return None;
+        }
+    };
+    Some(Checksum::new(algorithm, value))
+}
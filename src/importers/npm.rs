@@ -0,0 +1,330 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Import of npm packages from `package-lock.json` files.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+
+use log::warn;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    error::SpdxError,
+    models::{Algorithm, Checksum, Purl},
+};
+
+use super::{base64_checksum, DependencyKind, ImportedDependency, ImportedPackage, Importer};
+
+/// Creates SPDX documents from `package-lock.json` files of version 2 or 3.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NpmImporter {
+    packages: Vec<ImportedPackage>,
+    dependencies: Vec<ImportedDependency>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageLock {
+    #[serde(default)]
+    lockfile_version: u32,
+    #[serde(default)]
+    packages: BTreeMap<String, LockEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockEntry {
+    name: Option<String>,
+    version: Option<String>,
+    resolved: Option<String>,
+    integrity: Option<String>,
+    license: Option<Value>,
+    #[serde(default)]
+    link: bool,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, String>,
+}
+
+impl NpmImporter {
+    /// Read the packages from a `package-lock.json` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read or parsed.
+    pub fn from_lockfile<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        Self::from_lockfile_str(&fs::read_to_string(path)?)
+    }
+
+    /// Read the packages from the content of a `package-lock.json` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if the input is not valid JSON, or [`SpdxError::Import`] if
+    /// the lockfile is of version 1, which doesn't list the packages by their location.
+    pub fn from_lockfile_str(input: &str) -> Result<Self, SpdxError> {
+        let lockfile: PackageLock = serde_json::from_str(input)?;
+        if lockfile.lockfile_version < 2 {
+            return Err(SpdxError::Import(format!(
+                "package-lock.json version {} is not supported",
+                lockfile.lockfile_version
+            )));
+        }
+
+        let mut packages = Vec::new();
+        let mut indices = HashMap::new();
+        for (path, entry) in &lockfile.packages {
+            if entry.link {
+                continue;
+            }
+            indices.insert(path.as_str(), packages.len());
+            packages.push(package(path, entry));
+        }
+
+        let mut dependencies = Vec::new();
+        for (path, entry) in &lockfile.packages {
+            let from = match indices.get(path.as_str()) {
+                Some(from) => *from,
+                None => continue,
+            };
+            // Optional and peer dependencies are not always installed.
+            let kinds = [
+                (&entry.dependencies, DependencyKind::Normal, true),
+                (&entry.peer_dependencies, DependencyKind::Normal, false),
+                (&entry.dev_dependencies, DependencyKind::Dev, true),
+                (
+                    &entry.optional_dependencies,
+                    DependencyKind::Optional,
+                    false,
+                ),
+            ];
+            for (names, kind, required) in &kinds {
+                for name in names.keys() {
+                    match resolve(&lockfile.packages, path, name)
+                        .and_then(|resolved| indices.get(resolved))
+                    {
+                        Some(to) => dependencies.push(ImportedDependency {
+                            from,
+                            to: *to,
+                            kind: *kind,
+                        }),
+                        None if *required => warn!("Dependency {name} of {path:?} not found."),
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            packages,
+            dependencies,
+        })
+    }
+}
+
+impl Importer for NpmImporter {
+    fn packages(&self) -> Vec<ImportedPackage> {
+        self.packages.clone()
+    }
+
+    fn dependencies(&self) -> Vec<ImportedDependency> {
+        self.dependencies.clone()
+    }
+}
+
+fn package(path: &str, entry: &LockEntry) -> ImportedPackage {
+    // The location is `node_modules/<name>` unless the package is installed with an alias.
+    let name = entry.name.clone().unwrap_or_else(|| {
+        path.rsplit_once("node_modules/")
+            .map_or(path, |(_, name)| name)
+            .to_string()
+    });
+    let version = entry.version.clone().unwrap_or_default();
+
+    let purl_version = Some(version.as_str()).filter(|version| !version.is_empty());
+    let purl = match name.split_once('/') {
+        Some((scope, unscoped)) if scope.starts_with('@') => {
+            Purl::new("npm", unscoped, purl_version).with_namespace(scope)
+        }
+        _ => Purl::new("npm", &name, purl_version),
+    };
+
+    let mut package = ImportedPackage::new(&name, &version, purl);
+    if let Some(resolved) = &entry.resolved {
+        package.download_location.clone_from(resolved);
+    }
+    package.checksums = entry
+        .integrity
+        .iter()
+        .flat_map(|integrity| integrity.split_whitespace())
+        .filter_map(integrity_checksum)
+        .collect();
+    package.license = entry.license.as_ref().and_then(|license| {
+        // Old packages may have the license as an object with a type.
+        license
+            .as_str()
+            .or_else(|| license.get("type").and_then(Value::as_str))
+            .map(ToString::to_string)
+    });
+    // The root package and workspace members are not installed to `node_modules`.
+    package.root = !path.contains("node_modules/");
+    package
+}
+
+/// Convert a Subresource Integrity string like `sha512-<base64>` to a checksum.
+fn integrity_checksum(integrity: &str) -> Option<Checksum> {
+    let (algorithm, digest) = integrity.split_once('-')?;
+    let algorithm = match algorithm {
+        "sha1" => Algorithm::SHA1,
+        "sha256" => Algorithm::SHA256,
+        "sha384" => Algorithm::SHA384,
+        "sha512" => Algorithm::SHA512,
+        _ => {
+            warn!("Unsupported integrity algorithm {algorithm}.");
+            return None;
+        }
+    };
+    base64_checksum(algorithm, digest)
+}
+
+/// Find the location of the package `name` required from the package at `path`, following the
+/// Node.js module resolution: the closest `node_modules` directory up the tree wins. Links are
+/// resolved to their targets.
+fn resolve<'a>(
+    packages: &'a BTreeMap<String, LockEntry>,
+    path: &str,
+    name: &str,
+) -> Option<&'a str> {
+    let mut base = path;
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{name}")
+        } else {
+            format!("{base}/node_modules/{name}")
+        };
+        if let Some((location, entry)) = packages.get_key_value(&candidate) {
+            return match (&entry.resolved, entry.link) {
+                (Some(target), true) => packages
+                    .get_key_value(target)
+                    .map(|(target, _)| target.as_str()),
+                _ => Some(location.as_str()),
+            };
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = base
+            .rfind("/node_modules/")
+            .map_or("", |index| &base[..index]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::{RelationshipType, SpdxExpression};
+
+    use super::*;
+
+    #[test]
+    fn lockfile_is_imported() {
+        let importer = NpmImporter::from_lockfile("tests/data/npm/package-lock.json").unwrap();
+        let packages = importer.packages();
+        assert_eq!(packages.len(), 6);
+
+        assert!(packages[0].root);
+        assert_eq!(packages[0].name, "example-app");
+        assert_eq!(
+            packages[1].purl,
+            Purl::new("npm", "code-frame", Some("7.22.13")).with_namespace("@babel")
+        );
+        assert_eq!(
+            packages[2].download_location,
+            "https://registry.npmjs.org/debug/-/debug-4.3.4.tgz"
+        );
+        assert_eq!(packages[2].checksums[0].algorithm, Algorithm::SHA512);
+        assert!(packages[2].checksums[0]
+            .value
+            .starts_with("225d05b918519458"));
+        assert_eq!(packages[5].checksums[0].algorithm, Algorithm::SHA1);
+        assert_eq!(
+            packages[5].license_expression(),
+            Some(SpdxExpression::parse("MIT").unwrap())
+        );
+    }
+
+    #[test]
+    fn dependencies_are_resolved_from_nested_node_modules() {
+        let importer = NpmImporter::from_lockfile("tests/data/npm/package-lock.json").unwrap();
+        let dependencies = importer.dependencies();
+
+        // debug uses its own ms 2.1.2, the root package the hoisted ms 2.1.3.
+        assert!(dependencies.contains(&ImportedDependency {
+            from: 2,
+            to: 3,
+            kind: DependencyKind::Normal
+        }));
+        assert!(dependencies.contains(&ImportedDependency {
+            from: 0,
+            to: 5,
+            kind: DependencyKind::Dev
+        }));
+        assert!(dependencies.contains(&ImportedDependency {
+            from: 0,
+            to: 4,
+            kind: DependencyKind::Optional
+        }));
+    }
+
+    #[test]
+    fn document_is_created() {
+        let spdx = NpmImporter::from_lockfile("tests/data/npm/package-lock.json")
+            .unwrap()
+            .to_spdx("example-app");
+        let relationships = spdx
+            .relationships
+            .iter()
+            .map(|relationship| {
+                (
+                    relationship.spdx_element_id.as_str(),
+                    relationship.relationship_type.clone(),
+                    relationship.related_spdx_element.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert!(relationships.contains(&(
+            "SPDXRef-DOCUMENT",
+            RelationshipType::Describes,
+            "SPDXRef-Package-npm-example-app-1.0.0"
+        )));
+        assert!(relationships.contains(&(
+            "SPDXRef-Package-npm-example-app-1.0.0",
+            RelationshipType::DependsOn,
+            "SPDXRef-Package-npm--babel-code-frame-7.22.13"
+        )));
+        assert!(relationships.contains(&(
+            "SPDXRef-Package-npm-ms-2.1.3",
+            RelationshipType::DevDependencyOf,
+            "SPDXRef-Package-npm-example-app-1.0.0"
+        )));
+    }
+
+    #[test]
+    fn lockfile_version_1_is_an_error() {
+        assert!(matches!(
+            NpmImporter::from_lockfile_str(r#"{"lockfileVersion": 1, "dependencies": {}}"#),
+            Err(SpdxError::Import(_))
+        ));
+    }
+}
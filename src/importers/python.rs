@@ -0,0 +1,419 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Import of Python packages from `poetry.lock` and `requirements.txt` files.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+
+use log::warn;
+use serde::Deserialize;
+use toml::Value;
+
+use crate::{error::SpdxError, models::Purl};
+
+use super::{prefixed_checksum, DependencyKind, ImportedDependency, ImportedPackage, Importer};
+
+/// Creates SPDX documents from the locked packages of a Python project.
+///
+/// `poetry.lock` files and `requirements.txt` files with pinned versions and hashes, as created
+/// by `pip-compile --generate-hashes`, are supported. The `# via` comments of `pip-compile` are
+/// read as dependencies.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PythonImporter {
+    packages: Vec<ImportedPackage>,
+    dependencies: Vec<ImportedDependency>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoetryLock {
+    #[serde(default, rename = "package")]
+    packages: Vec<PoetryPackage>,
+    metadata: Option<PoetryMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoetryPackage {
+    name: String,
+    version: String,
+    description: Option<String>,
+    #[serde(default)]
+    files: Vec<PoetryFile>,
+    source: Option<PoetrySource>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoetryMetadata {
+    /// The files of the packages in lockfiles older than version 2.0.
+    #[serde(default)]
+    files: HashMap<String, Vec<PoetryFile>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PoetryFile {
+    file: String,
+    hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoetrySource {
+    #[serde(rename = "type")]
+    source_type: String,
+    url: String,
+    resolved_reference: Option<String>,
+}
+
+impl PythonImporter {
+    /// Read the packages from a `poetry.lock` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read or parsed.
+    pub fn from_poetry_lock<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        Self::from_poetry_lock_str(&fs::read_to_string(path)?)
+    }
+
+    /// Read the packages from the content of a `poetry.lock` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Toml`] if the input is not a valid lockfile.
+    pub fn from_poetry_lock_str(input: &str) -> Result<Self, SpdxError> {
+        let lockfile: PoetryLock = toml::from_str(input)?;
+        let legacy_files = lockfile
+            .metadata
+            .map(|metadata| metadata.files)
+            .unwrap_or_default();
+
+        let mut importer = Self::default();
+        for package in &lockfile.packages {
+            let files = if package.files.is_empty() {
+                legacy_files.get(&package.name).cloned().unwrap_or_default()
+            } else {
+                package.files.clone()
+            };
+            importer.packages.push(poetry_package(package, &files));
+        }
+
+        let indices = importer.indices();
+        for (from, package) in lockfile.packages.iter().enumerate() {
+            for (name, constraint) in &package.dependencies {
+                // The constraint is a version, a table or a list of tables for different
+                // environments.
+                let optional = constraint
+                    .get("optional")
+                    .and_then(Value::as_bool)
+                    .unwrap_or_default();
+                let kind = if optional {
+                    DependencyKind::Optional
+                } else {
+                    DependencyKind::Normal
+                };
+                match indices.get(&normalize_name(name)) {
+                    Some(to) => importer.dependencies.push(ImportedDependency {
+                        from,
+                        to: *to,
+                        kind,
+                    }),
+                    None if !optional => {
+                        warn!("Dependency {name} of {} not found.", package.name);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        Ok(importer)
+    }
+
+    /// Read the packages from a `requirements.txt` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read.
+    pub fn from_requirements<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        Ok(Self::from_requirements_str(&fs::read_to_string(path)?))
+    }
+
+    /// Read the packages from the content of a `requirements.txt` file. Requirements without a
+    /// pinned version are skipped.
+    pub fn from_requirements_str(input: &str) -> Self {
+        let mut importer = Self::default();
+        // Names of the packages that require the package, from the `# via` comments.
+        let mut required_by: Vec<(String, usize)> = Vec::new();
+        let mut in_via_list = false;
+
+        for line in logical_lines(input) {
+            let line = line.trim();
+            if let Some(comment) = line.strip_prefix('#') {
+                let comment = comment.trim();
+                let via = comment
+                    .strip_prefix("via")
+                    .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+                    .map(str::trim);
+                let parent = match via {
+                    Some(via) => {
+                        in_via_list = via.is_empty();
+                        Some(via)
+                    }
+                    None if in_via_list => Some(comment),
+                    None => None,
+                };
+                if let (Some(parent), Some(current)) =
+                    (parent, importer.packages.len().checked_sub(1))
+                {
+                    // `-r requirements.in` marks a direct requirement of the project.
+                    if !parent.is_empty() && !parent.starts_with('-') {
+                        required_by.push((normalize_name(parent), current));
+                    }
+                }
+                continue;
+            }
+
+            in_via_list = false;
+            if line.is_empty() || line.starts_with('-') {
+                continue;
+            }
+            if let Some(package) = requirement_package(line) {
+                importer.packages.push(package);
+            }
+        }
+
+        let indices = importer.indices();
+        for (name, to) in required_by {
+            match indices.get(&name) {
+                Some(from) => importer.dependencies.push(ImportedDependency {
+                    from: *from,
+                    to,
+                    kind: DependencyKind::Normal,
+                }),
+                None => warn!("Package {name} from a via comment not found."),
+            }
+        }
+
+        importer
+    }
+
+    /// Indices of the packages by their normalized names.
+    fn indices(&self) -> HashMap<String, usize> {
+        self.packages
+            .iter()
+            .enumerate()
+            .map(|(index, package)| (normalize_name(&package.name), index))
+            .collect()
+    }
+}
+
+impl Importer for PythonImporter {
+    fn packages(&self) -> Vec<ImportedPackage> {
+        self.packages.clone()
+    }
+
+    fn dependencies(&self) -> Vec<ImportedDependency> {
+        self.dependencies.clone()
+    }
+}
+
+/// Normalize a package name as specified in PEP 503.
+fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+fn pypi_purl(name: &str, version: &str) -> Purl {
+    Purl::new("pypi", &normalize_name(name), Some(version))
+}
+
+fn poetry_package(package: &PoetryPackage, files: &[PoetryFile]) -> ImportedPackage {
+    let mut imported = ImportedPackage::new(
+        &package.name,
+        &package.version,
+        pypi_purl(&package.name, &package.version),
+    );
+    imported.description = package
+        .description
+        .clone()
+        .filter(|description| !description.is_empty());
+
+    // The checksum of the source distribution identifies the package best, wheels are platform
+    // specific.
+    let source_distribution = files.iter().find(|file| {
+        Path::new(&file.file).extension().is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("gz") || extension.eq_ignore_ascii_case("zip")
+        })
+    });
+    if let Some(file) = source_distribution.or_else(|| files.first()) {
+        imported.checksums = prefixed_checksum(&file.hash).into_iter().collect();
+    }
+
+    match &package.source {
+        Some(source) if source.source_type == "git" => {
+            imported.download_location = source.resolved_reference.as_ref().map_or_else(
+                || format!("git+{}", source.url),
+                |reference| format!("git+{}@{reference}", source.url),
+            );
+        }
+        Some(_) => {}
+        None => {
+            if let Some(file) = source_distribution {
+                imported.download_location = format!(
+                    "https://files.pythonhosted.org/packages/source/{}/{}/{}",
+                    package.name.chars().next().unwrap_or_default(),
+                    package.name,
+                    file.file
+                );
+            }
+        }
+    }
+    imported
+}
+
+/// Join lines that end with a backslash.
+fn logical_lines(input: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for line in input.lines() {
+        if let Some(continued) = line.trim_end().strip_suffix('\\') {
+            current.push_str(continued);
+            current.push(' ');
+        } else {
+            current.push_str(line);
+            lines.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Parse a requirement like `name[extra]==1.0 ; python_version >= "3.8" --hash=sha256:...`.
+fn requirement_package(line: &str) -> Option<ImportedPackage> {
+    let mut tokens = line.split_whitespace();
+    let requirement = tokens.next()?;
+    // The hashes are of the different distributions of the package, so all of them are kept.
+    let hashes = tokens.filter_map(|token| token.strip_prefix("--hash="));
+
+    let Some((name, version)) = requirement
+        .split(';')
+        .next()
+        .and_then(|requirement| requirement.split_once("=="))
+    else {
+        warn!("Requirement {requirement} doesn't have a pinned version.");
+        return None;
+    };
+    let version = version.trim_start_matches('=');
+    let name = name.split('[').next().unwrap_or(name);
+
+    let mut package = ImportedPackage::new(name, version, pypi_purl(name, version));
+    package.checksums = hashes.filter_map(prefixed_checksum).collect();
+    Some(package)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::{Algorithm, Checksum};
+
+    use super::*;
+
+    #[test]
+    fn poetry_lock_is_imported() {
+        let importer = PythonImporter::from_poetry_lock("tests/data/python/poetry.lock").unwrap();
+        let packages = importer.packages();
+        assert_eq!(packages.len(), 4);
+
+        let certifi = &packages[0];
+        assert_eq!(
+            certifi.purl,
+            Purl::new("pypi", "certifi", Some("2023.7.22"))
+        );
+        assert_eq!(
+            certifi.checksums,
+            vec![Checksum::new(
+                Algorithm::SHA256,
+                "539cc1d13202e33ca466e88b2807e29f4c13049d6d87031a3c110744495cb082"
+            )]
+        );
+        assert_eq!(
+            certifi.download_location,
+            "https://files.pythonhosted.org/packages/source/c/certifi/certifi-2023.7.22.tar.gz"
+        );
+        assert_eq!(
+            packages[1].purl,
+            Purl::new("pypi", "pysocks", Some("1.7.1"))
+        );
+        assert_eq!(
+            packages[3].download_location,
+            "git+https://github.com/pytoolz/toolz.git@d7a4a6b1e3f4f5b7c7e8d6c5b4a3f2e1d0c9b8a7"
+        );
+        assert_eq!(packages[3].description, None);
+
+        assert_eq!(
+            importer.dependencies(),
+            vec![
+                ImportedDependency {
+                    from: 2,
+                    to: 1,
+                    kind: DependencyKind::Optional
+                },
+                ImportedDependency {
+                    from: 2,
+                    to: 0,
+                    kind: DependencyKind::Normal
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn requirements_are_imported() {
+        let importer =
+            PythonImporter::from_requirements("tests/data/python/requirements.txt").unwrap();
+        let packages = importer.packages();
+        assert_eq!(packages.len(), 4);
+
+        assert_eq!(packages[0].checksums.len(), 2);
+        assert_eq!(packages[2].name, "requests");
+        assert_eq!(
+            packages[2].purl,
+            Purl::new("pypi", "requests", Some("2.31.0"))
+        );
+
+        let dependencies = importer.dependencies();
+        assert_eq!(dependencies.len(), 3);
+        for to in &[0, 1, 3] {
+            assert!(dependencies.contains(&ImportedDependency {
+                from: 2,
+                to: *to,
+                kind: DependencyKind::Normal
+            }));
+        }
+    }
+
+    #[test]
+    fn unpinned_requirements_are_skipped() {
+        let importer = PythonImporter::from_requirements_str("requests>=2.0\nidna==3.4\n");
+        assert_eq!(importer.packages().len(), 1);
+        assert_eq!(importer.packages()[0].name, "idna");
+    }
+
+    #[test]
+    fn names_are_normalized() {
+        assert_eq!(normalize_name("Foo.Bar__baz"), "foo-bar-baz");
+    }
+}
@@ -12,6 +12,19 @@
     clippy::use_self
 )]
 
+pub mod builder;
+#[cfg(feature = "compact")]
+pub mod compact;
+pub mod cyclonedx;
 pub mod error;
+pub mod importers;
+pub mod licenses;
 pub mod models;
+mod parallel;
 pub mod parsers;
+pub mod policy;
+pub mod quality;
+pub mod query;
+mod version;
+pub mod vulnerability;
+pub mod writers;
@@ -0,0 +1,246 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use log::info;
+use serde::Serialize;
+
+use crate::models::{
+    FileInformation, LicenseTree, PackageInformation, RelationshipType, SpdxExpression,
+    SpecialValue, SPDX,
+};
+
+/// Kinds of inconsistencies between the license fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InconsistencyKind {
+    /// The concluded license of a package doesn't include licenses found in its files.
+    PackageLicenseMissesFileLicenses,
+
+    /// The concluded license of a file doesn't include licenses found in the file.
+    FileLicenseContradictsFile,
+
+    /// `licenseInfoFromFiles` of a package lists licenses that are not found in its files.
+    UnexpectedLicenseInfoFromFiles,
+
+    /// `licenseInfoFromFiles` of a package doesn't list licenses found in its files.
+    MissingLicenseInfoFromFiles,
+}
+
+/// An inconsistency between the license fields of an element.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseInconsistency {
+    pub spdx_id: String,
+    pub kind: InconsistencyKind,
+
+    /// The licenses that are inconsistent.
+    pub licenses: Vec<String>,
+}
+
+/// Inconsistencies between the declared, concluded and detected licenses of a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyReport {
+    pub inconsistencies: Vec<LicenseInconsistency>,
+}
+
+impl ConsistencyReport {
+    /// Cross-check the license fields of the packages and files of the document.
+    ///
+    /// The licenses detected in the files are the `licenseInfoInFiles` of the files. A concluded
+    /// license covers a detected license if the license appears anywhere in the expression.
+    /// `NOASSERTION` and `NONE` are not treated as detected licenses, and `NOASSERTION` as a
+    /// concluded license is not checked.
+    pub fn new(spdx: &SPDX) -> Self {
+        info!("Checking the consistency of the licenses in SPDX.");
+        let mut report = Self::default();
+
+        for package in &spdx.package_information {
+            let detected = package_files(spdx, package)
+                .iter()
+                .flat_map(|file| detected_licenses(file))
+                .collect::<BTreeMap<_, _>>();
+
+            if let Some(concluded) = concluded_licenses(package.concluded_license.as_ref()) {
+                report.add(
+                    &package.package_spdx_identifier,
+                    InconsistencyKind::PackageLicenseMissesFileLicenses,
+                    missing(&detected, &concluded),
+                );
+            }
+
+            let listed = package
+                .all_licenses_information_from_files
+                .iter()
+                .filter(|license| SpecialValue::of(license).is_none())
+                .map(|license| (license.to_ascii_lowercase(), license.clone()))
+                .collect::<BTreeMap<_, _>>();
+            if package.files_analyzed != Some(false) && !listed.is_empty() {
+                report.add(
+                    &package.package_spdx_identifier,
+                    InconsistencyKind::UnexpectedLicenseInfoFromFiles,
+                    missing(&listed, &detected),
+                );
+                report.add(
+                    &package.package_spdx_identifier,
+                    InconsistencyKind::MissingLicenseInfoFromFiles,
+                    missing(&detected, &listed),
+                );
+            }
+        }
+
+        for file in &spdx.file_information {
+            if let Some(concluded) = concluded_licenses(file.concluded_license.as_ref()) {
+                report.add(
+                    &file.file_spdx_identifier,
+                    InconsistencyKind::FileLicenseContradictsFile,
+                    missing(&detected_licenses(file), &concluded),
+                );
+            }
+        }
+
+        report
+    }
+
+    /// Inconsistencies of the element with the given SPDX ID.
+    pub fn for_spdx_id(&self, spdx_id: &str) -> Vec<&LicenseInconsistency> {
+        self.inconsistencies
+            .iter()
+            .filter(|inconsistency| inconsistency.spdx_id == spdx_id)
+            .collect()
+    }
+
+    fn add(&mut self, spdx_id: &str, kind: InconsistencyKind, licenses: Vec<String>) {
+        if !licenses.is_empty() {
+            self.inconsistencies.push(LicenseInconsistency {
+                spdx_id: spdx_id.to_string(),
+                kind,
+                licenses,
+            });
+        }
+    }
+}
+
+/// Licenses keyed by their lowercase identifiers, as license identifiers are case insensitive.
+type Licenses = BTreeMap<String, String>;
+
+fn licenses_of(expression: &SpdxExpression) -> Licenses {
+    LicenseTree::from(expression)
+        .terms()
+        .into_iter()
+        .filter(|term| SpecialValue::of(&term.license).is_none())
+        .map(|term| (term.license.to_ascii_lowercase(), term.license.clone()))
+        .collect()
+}
+
+/// The licenses of a concluded license, `None` if it's missing or `NOASSERTION`.
+fn concluded_licenses(concluded: Option<&SpdxExpression>) -> Option<Licenses> {
+    let concluded = concluded?;
+    if SpecialValue::is_noassertion(&concluded.to_string()) {
+        None
+    } else {
+        Some(licenses_of(concluded))
+    }
+}
+
+fn detected_licenses(file: &FileInformation) -> Licenses {
+    file.license_information_in_file
+        .iter()
+        .flat_map(licenses_of)
+        .collect()
+}
+
+/// Licenses in `licenses` that are not in `covered`.
+fn missing(licenses: &Licenses, covered: &Licenses) -> Vec<String> {
+    licenses
+        .iter()
+        .filter(|(key, _)| !covered.contains_key(*key))
+        .map(|(_, license)| license.clone())
+        .collect()
+}
+
+/// Files of the package, from the files of the package and the `CONTAINS` relationships.
+pub(super) fn package_files<'a>(
+    spdx: &'a SPDX,
+    package: &PackageInformation,
+) -> Vec<&'a FileInformation> {
+    let id = &package.package_spdx_identifier;
+    spdx.file_information
+        .iter()
+        .filter(|file| {
+            let file_id = &file.file_spdx_identifier;
+            package.files.contains(file_id)
+                || spdx.relationships.iter().any(|relationship| {
+                    relationship.relationship_type == RelationshipType::Contains
+                        && &relationship.spdx_element_id == id
+                        && &relationship.related_spdx_element == file_id
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn example_document_is_checked() {
+        let report = ConsistencyReport::new(&example());
+
+        assert_eq!(
+            report.for_spdx_id("SPDXRef-Package"),
+            vec![
+                &LicenseInconsistency {
+                    spdx_id: "SPDXRef-Package".to_string(),
+                    kind: InconsistencyKind::PackageLicenseMissesFileLicenses,
+                    licenses: vec!["Apache-2.0".to_string(), "LicenseRef-1".to_string()],
+                },
+                &LicenseInconsistency {
+                    spdx_id: "SPDXRef-Package".to_string(),
+                    kind: InconsistencyKind::UnexpectedLicenseInfoFromFiles,
+                    licenses: vec!["GPL-2.0-only".to_string(), "LicenseRef-2".to_string()],
+                },
+                &LicenseInconsistency {
+                    spdx_id: "SPDXRef-Package".to_string(),
+                    kind: InconsistencyKind::MissingLicenseInfoFromFiles,
+                    licenses: vec!["Apache-2.0".to_string()],
+                },
+            ]
+        );
+        assert_eq!(
+            report.for_spdx_id("SPDXRef-File"),
+            vec![&LicenseInconsistency {
+                spdx_id: "SPDXRef-File".to_string(),
+                kind: InconsistencyKind::FileLicenseContradictsFile,
+                licenses: vec!["GPL-2.0-only".to_string()],
+            }]
+        );
+        assert_eq!(report.inconsistencies.len(), 4);
+    }
+
+    #[test]
+    fn consistent_licenses_are_not_reported() {
+        let mut spdx = example();
+        let file = spdx
+            .file_information
+            .iter_mut()
+            .find(|file| file.file_spdx_identifier == "SPDXRef-File")
+            .unwrap();
+        file.concluded_license =
+            Some(SpdxExpression::parse("gpl-2.0-only AND LicenseRef-2").unwrap());
+
+        assert!(ConsistencyReport::new(&spdx)
+            .for_spdx_id("SPDXRef-File")
+            .is_empty());
+    }
+}
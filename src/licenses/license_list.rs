@@ -0,0 +1,643 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{collections::HashMap, fmt, fs::read_to_string, path::Path, sync::OnceLock};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::SpdxError,
+    models::{LicenseTree, SpdxExpression, SPDX},
+};
+
+/// Deprecated identifiers whose replacement doesn't follow the `-only` and `-or-later` naming.
+const REPLACEMENTS: &[(&str, &str)] = &[
+    ("BSD-2-Clause-FreeBSD", "BSD-2-Clause"),
+    ("BSD-2-Clause-NetBSD", "BSD-2-Clause"),
+    ("bzip2-1.0.5", "bzip2-1.0.6"),
+    ("eCos-2.0", "GPL-2.0-or-later WITH eCos-exception-2.0"),
+    (
+        "GPL-2.0-with-autoconf-exception",
+        "GPL-2.0-only WITH Autoconf-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-bison-exception",
+        "GPL-2.0-or-later WITH Bison-exception-2.2",
+    ),
+    (
+        "GPL-2.0-with-classpath-exception",
+        "GPL-2.0-only WITH Classpath-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-font-exception",
+        "GPL-2.0-only WITH Font-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-GCC-exception",
+        "GPL-2.0-only WITH GCC-exception-2.0",
+    ),
+    (
+        "GPL-3.0-with-autoconf-exception",
+        "GPL-3.0-only WITH Autoconf-exception-3.0",
+    ),
+    (
+        "GPL-3.0-with-GCC-exception",
+        "GPL-3.0-only WITH GCC-exception-3.1",
+    ),
+    ("Nunit", "zlib-acknowledgement"),
+    ("StandardML-NJ", "SMLNJ"),
+    (
+        "wxWindows",
+        "LGPL-2.0-or-later WITH WxWindows-exception-3.1",
+    ),
+];
+
+/// Texts and templates of the most common licenses, bundled in the format of the `text` and
+/// `template` directories of the license list data.
+macro_rules! bundled_licenses {
+    ($($license_id:literal),* $(,)?) => {
+        &[$((
+            $license_id,
+            include_str!(concat!("../../data/license-list/text/", $license_id, ".txt")),
+            include_str!(concat!(
+                "../../data/license-list/template/",
+                $license_id,
+                ".template.txt"
+            )),
+        )),*]
+    };
+}
+
+const BUNDLED_LICENSES: &[(&str, &str, &str)] = bundled_licenses!(
+    "0BSD",
+    "Apache-2.0",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSL-1.0",
+    "ISC",
+    "MIT",
+    "MIT-0",
+    "Unlicense",
+    "Zlib",
+);
+
+/// A license of the SPDX License List.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct License {
+    pub license_id: String,
+    pub name: String,
+
+    /// URL of the license on the SPDX website.
+    pub reference: String,
+
+    /// Other URLs of the license text.
+    #[serde(default)]
+    pub see_also: Vec<String>,
+
+    #[serde(default)]
+    pub is_osi_approved: bool,
+
+    #[serde(default)]
+    pub is_fsf_libre: bool,
+
+    pub is_deprecated_license_id: bool,
+
+    /// Full text of the license, if it has been loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_text: Option<String>,
+
+    /// Template of the license text with the variable and optional parts marked, if it has been
+    /// loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub standard_license_template: Option<String>,
+}
+
+/// A license exception of the SPDX License List.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseException {
+    pub license_exception_id: String,
+    pub name: String,
+
+    /// URL of the exception on the SPDX website.
+    pub reference: String,
+
+    /// Other URLs of the exception text.
+    #[serde(default)]
+    pub see_also: Vec<String>,
+
+    pub is_deprecated_license_id: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LicensesFile {
+    license_list_version: String,
+    licenses: Vec<License>,
+}
+
+#[derive(Deserialize)]
+struct ExceptionsFile {
+    exceptions: Vec<LicenseException>,
+}
+
+/// Kinds of problems with the license identifiers of a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LicenseListProblemKind {
+    /// The license is not on the license list.
+    UnknownLicense,
+
+    /// The exception is not on the license list.
+    UnknownException,
+
+    /// The license identifier is deprecated. The replacement is an expression.
+    DeprecatedLicense { replacement: Option<String> },
+
+    /// The exception identifier is deprecated.
+    DeprecatedException,
+
+    /// The identifier is on the license list with different case.
+    WrongCase { correct: String },
+
+    /// The `LicenseRef-` is not defined in the other licensing information of the document.
+    UndefinedLicenseRef,
+
+    /// The document was created with a different version of the license list.
+    LicenseListVersionMismatch {
+        document: Option<String>,
+        bundled: String,
+    },
+}
+
+/// A problem with a license identifier found by [`LicenseList::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseListProblem {
+    pub spdx_id: String,
+
+    /// The license or exception identifier with the problem, `None` for problems of the document.
+    pub license: Option<String>,
+    pub kind: LicenseListProblemKind,
+}
+
+impl fmt::Display for LicenseListProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let license = self.license.as_deref().unwrap_or_default();
+        write!(f, "{}: ", self.spdx_id)?;
+        match &self.kind {
+            LicenseListProblemKind::UnknownLicense => write!(f, "unknown license {license}"),
+            LicenseListProblemKind::UnknownException => write!(f, "unknown exception {license}"),
+            LicenseListProblemKind::DeprecatedLicense { replacement } => {
+                write!(f, "deprecated license {license}")?;
+                replacement.as_ref().map_or(Ok(()), |replacement| {
+                    write!(f, ", replaced by {replacement}")
+                })
+            }
+            LicenseListProblemKind::DeprecatedException => {
+                write!(f, "deprecated exception {license}")
+            }
+            LicenseListProblemKind::WrongCase { correct } => {
+                write!(f, "{license} should be written as {correct}")
+            }
+            LicenseListProblemKind::UndefinedLicenseRef => {
+                write!(f, "{license} is not defined in the document")
+            }
+            LicenseListProblemKind::LicenseListVersionMismatch { document, bundled } => write!(
+                f,
+                "license list version {} doesn't match {bundled}",
+                document.as_deref().unwrap_or("NOASSERTION")
+            ),
+        }
+    }
+}
+
+/// The SPDX License List.
+///
+/// A snapshot of the license list is bundled with the library and available with
+/// [`LicenseList::bundled`]. Other versions can be read from the `licenses.json` and
+/// `exceptions.json` files of the [license-list-data] repository.
+///
+/// [license-list-data]: https://github.com/spdx/license-list-data
+#[derive(Debug, Clone)]
+pub struct LicenseList {
+    pub version: String,
+    pub licenses: Vec<License>,
+    pub exceptions: Vec<LicenseException>,
+
+    /// Indices of the licenses and exceptions keyed by their lowercase identifiers.
+    license_index: HashMap<String, usize>,
+    exception_index: HashMap<String, usize>,
+}
+
+impl LicenseList {
+    /// The license list bundled with the library, with the texts and templates of the most
+    /// common licenses.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bundled data files are invalid, which is caught by the tests.
+    pub fn bundled() -> &'static Self {
+        static BUNDLED: OnceLock<LicenseList> = OnceLock::new();
+        BUNDLED.get_or_init(|| {
+            let mut list = Self::from_json(
+                include_str!("../../data/license-list/licenses.json"),
+                include_str!("../../data/license-list/exceptions.json"),
+            )
+            .expect("the bundled license list is valid");
+            for (license_id, text, template) in BUNDLED_LICENSES {
+                if let Some(&index) = list.license_index.get(&license_id.to_ascii_lowercase()) {
+                    let license = &mut list.licenses[index];
+                    license.license_text = Some((*text).to_string());
+                    license.standard_license_template = Some((*template).to_string());
+                }
+            }
+            list
+        })
+    }
+
+    /// Read the license list from the contents of `licenses.json` and `exceptions.json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if the files are not in the format of the license list.
+    pub fn from_json(licenses: &str, exceptions: &str) -> Result<Self, SpdxError> {
+        let licenses: LicensesFile = serde_json::from_str(licenses)?;
+        let exceptions: ExceptionsFile = serde_json::from_str(exceptions)?;
+        let license_index = licenses
+            .licenses
+            .iter()
+            .enumerate()
+            .map(|(index, license)| (license.license_id.to_ascii_lowercase(), index))
+            .collect();
+        let exception_index = exceptions
+            .exceptions
+            .iter()
+            .enumerate()
+            .map(|(index, exception)| (exception.license_exception_id.to_ascii_lowercase(), index))
+            .collect();
+
+        Ok(Self {
+            version: licenses.license_list_version,
+            licenses: licenses.licenses,
+            exceptions: exceptions.exceptions,
+            license_index,
+            exception_index,
+        })
+    }
+
+    /// Find a license by its identifier. Identifiers are case insensitive.
+    pub fn license(&self, license_id: &str) -> Option<&License> {
+        self.license_index
+            .get(&license_id.to_ascii_lowercase())
+            .map(|&index| &self.licenses[index])
+    }
+
+    /// Full text of a license, if it has been loaded.
+    pub fn text(&self, license_id: &str) -> Option<&str> {
+        self.license(license_id)?.license_text.as_deref()
+    }
+
+    /// Load the texts of the licenses from a directory with a `<license id>.txt` file for each
+    /// license, like the `text` directory of the license list data. Licenses without a file keep
+    /// their current text.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Io`] if a text file exists but can't be read.
+    pub fn load_texts(&mut self, directory: &Path) -> Result<(), SpdxError> {
+        for license in &mut self.licenses {
+            let path = directory.join(format!("{}.txt", license.license_id));
+            if path.is_file() {
+                license.license_text = Some(read_to_string(path)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the templates of the licenses from a directory with a `<license id>.template.txt`
+    /// file for each license, like the `template` directory of the license list data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Io`] if a template file exists but can't be read.
+    pub fn load_templates(&mut self, directory: &Path) -> Result<(), SpdxError> {
+        for license in &mut self.licenses {
+            let path = directory.join(format!("{}.template.txt", license.license_id));
+            if path.is_file() {
+                license.standard_license_template = Some(read_to_string(path)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Find an exception by its identifier. Identifiers are case insensitive.
+    pub fn exception(&self, exception_id: &str) -> Option<&LicenseException> {
+        self.exception_index
+            .get(&exception_id.to_ascii_lowercase())
+            .map(|&index| &self.exceptions[index])
+    }
+
+    /// Replacement for a deprecated license identifier, e.g. `GPL-2.0-only` for `GPL-2.0`.
+    pub fn replacement(&self, license_id: &str) -> Option<String> {
+        let license = self.license(license_id)?;
+        if !license.is_deprecated_license_id {
+            return None;
+        }
+        let id = &license.license_id;
+        if let Some((_, replacement)) = REPLACEMENTS.iter().find(|(deprecated, _)| deprecated == id)
+        {
+            return Some((*replacement).to_string());
+        }
+        let candidate = id
+            .strip_suffix('+')
+            .map_or_else(|| format!("{id}-only"), |base| format!("{base}-or-later"));
+        self.license(&candidate)
+            .map(|license| license.license_id.clone())
+    }
+
+    /// Check the license identifiers of the document against the license list.
+    ///
+    /// All license expressions of the packages, files and snippets are checked. `LicenseRef-`s
+    /// must be defined in the document, and references to other documents, `NONE` and
+    /// `NOASSERTION` are not checked. The license list version of the document must match the
+    /// version of this list.
+    pub fn validate(&self, spdx: &SPDX) -> Vec<LicenseListProblem> {
+        info!("Validating the licenses of SPDX against the SPDX License List.");
+        let mut problems = Vec::new();
+
+        let document = &spdx.document_creation_information;
+        let document_version = &document.creation_info.license_list_version;
+        if document_version.as_deref() != Some(self.version.as_str()) {
+            problems.push(LicenseListProblem {
+                spdx_id: document.spdx_identifier.clone(),
+                license: None,
+                kind: LicenseListProblemKind::LicenseListVersionMismatch {
+                    document: document_version.clone(),
+                    bundled: self.version.clone(),
+                },
+            });
+        }
+
+        let mut checker = Checker {
+            list: self,
+            spdx,
+            problems,
+        };
+        for package in &spdx.package_information {
+            let id = &package.package_spdx_identifier;
+            checker.expression(id, package.concluded_license.as_ref());
+            checker.expression(id, package.declared_license.as_ref());
+            checker.identifiers(id, &package.all_licenses_information_from_files);
+        }
+        for file in &spdx.file_information {
+            let id = &file.file_spdx_identifier;
+            checker.expression(id, file.concluded_license.as_ref());
+            for expression in &file.license_information_in_file {
+                checker.expression(id, Some(expression));
+            }
+        }
+        for snippet in &spdx.snippet_information {
+            let id = &snippet.snippet_spdx_identifier;
+            checker.expression(id, snippet.snippet_concluded_license.as_ref());
+            checker.identifiers(id, &snippet.license_information_in_snippet);
+        }
+
+        checker.problems
+    }
+
+    /// Check a single license identifier, which may end with `+`.
+    fn check_license(&self, license_id: &str) -> Option<LicenseListProblemKind> {
+        let (license, suffix) = if let Some(license) = self.license(license_id) {
+            (license, "")
+        } else if let Some(license) = license_id
+            .strip_suffix('+')
+            .and_then(|base| self.license(base))
+        {
+            (license, "+")
+        } else {
+            return Some(LicenseListProblemKind::UnknownLicense);
+        };
+        let correct = format!("{}{suffix}", license.license_id);
+
+        if license.is_deprecated_license_id {
+            Some(LicenseListProblemKind::DeprecatedLicense {
+                replacement: self.replacement(&license.license_id),
+            })
+        } else if correct != license_id {
+            Some(LicenseListProblemKind::WrongCase { correct })
+        } else {
+            None
+        }
+    }
+
+    fn check_exception(&self, exception_id: &str) -> Option<LicenseListProblemKind> {
+        match self.exception(exception_id) {
+            None => Some(LicenseListProblemKind::UnknownException),
+            Some(exception) if exception.is_deprecated_license_id => {
+                Some(LicenseListProblemKind::DeprecatedException)
+            }
+            Some(exception) if exception.license_exception_id != exception_id => {
+                Some(LicenseListProblemKind::WrongCase {
+                    correct: exception.license_exception_id.clone(),
+                })
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+/// Collects the problems of the license identifiers of a document.
+struct Checker<'a> {
+    list: &'a LicenseList,
+    spdx: &'a SPDX,
+    problems: Vec<LicenseListProblem>,
+}
+
+impl Checker<'_> {
+    fn expression(&mut self, spdx_id: &str, expression: Option<&SpdxExpression>) {
+        if let Some(expression) = expression {
+            for term in LicenseTree::from(expression).terms() {
+                self.license(spdx_id, &term.license);
+                if let Some(exception) = &term.exception {
+                    let kind = self.list.check_exception(exception);
+                    self.push(spdx_id, exception, kind);
+                }
+            }
+        }
+    }
+
+    /// Check a list of license identifiers, e.g. `licenseInfoFromFiles`.
+    fn identifiers(&mut self, spdx_id: &str, licenses: &[String]) {
+        for license in licenses {
+            match SpdxExpression::parse(license) {
+                Ok(expression) => self.expression(spdx_id, Some(&expression)),
+                Err(_) => self.license(spdx_id, license),
+            }
+        }
+    }
+
+    fn license(&mut self, spdx_id: &str, license: &str) {
+        let kind =
+            if license == "NONE" || license == "NOASSERTION" || license.starts_with("DocumentRef-")
+            {
+                None
+            } else if license.starts_with("LicenseRef-") {
+                let defined = self
+                    .spdx
+                    .other_licensing_information_detected
+                    .iter()
+                    .any(|other| other.license_identifier == license);
+                (!defined).then_some(LicenseListProblemKind::UndefinedLicenseRef)
+            } else {
+                self.list.check_license(license)
+            };
+        self.push(spdx_id, license, kind);
+    }
+
+    fn push(&mut self, spdx_id: &str, license: &str, kind: Option<LicenseListProblemKind>) {
+        if let Some(kind) = kind {
+            self.problems.push(LicenseListProblem {
+                spdx_id: spdx_id.to_string(),
+                license: Some(license.to_string()),
+                kind,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    fn problems(spdx: &SPDX, spdx_id: &str) -> Vec<(Option<String>, LicenseListProblemKind)> {
+        LicenseList::bundled()
+            .validate(spdx)
+            .into_iter()
+            .filter(|problem| problem.spdx_id == spdx_id)
+            .map(|problem| (problem.license, problem.kind))
+            .collect()
+    }
+
+    #[test]
+    fn bundled_list_has_metadata() {
+        let list = LicenseList::bundled();
+        let apache = list.license("apache-2.0").unwrap();
+        assert_eq!(apache.license_id, "Apache-2.0");
+        assert_eq!(apache.name, "Apache License 2.0");
+        assert!(apache.is_osi_approved);
+        assert!(!apache.see_also.is_empty());
+        assert!(list.exception("Classpath-exception-2.0").is_some());
+        assert!(list.license("Not-A-License").is_none());
+        assert!(list.text("MIT").unwrap().starts_with("MIT License"));
+        assert_eq!(list.text("MPL-2.0"), None);
+    }
+
+    #[test]
+    fn texts_are_loaded_from_directory() {
+        let mut list = LicenseList::bundled().clone();
+        list.load_texts(Path::new("data/license-list/text"))
+            .unwrap();
+        assert!(list
+            .text("Apache-2.0")
+            .unwrap()
+            .starts_with("Apache License"));
+        assert_eq!(list.text("GPL-2.0-only"), None);
+    }
+
+    #[test]
+    fn replacements_of_deprecated_licenses() {
+        let list = LicenseList::bundled();
+        assert_eq!(list.replacement("GPL-2.0").unwrap(), "GPL-2.0-only");
+        assert_eq!(list.replacement("LGPL-2.1+").unwrap(), "LGPL-2.1-or-later");
+        assert_eq!(
+            list.replacement("GPL-2.0-with-classpath-exception")
+                .unwrap(),
+            "GPL-2.0-only WITH Classpath-exception-2.0"
+        );
+        assert_eq!(list.replacement("MIT"), None);
+    }
+
+    #[test]
+    fn example_document_has_older_license_list() {
+        let problems = LicenseList::bundled().validate(&example());
+        assert_eq!(
+            problems,
+            vec![LicenseListProblem {
+                spdx_id: "SPDXRef-DOCUMENT".to_string(),
+                license: None,
+                kind: LicenseListProblemKind::LicenseListVersionMismatch {
+                    document: Some("3.17".to_string()),
+                    bundled: "3.21".to_string(),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn problems_are_found() {
+        let mut spdx = example();
+        spdx.document_creation_information
+            .creation_info
+            .license_list_version = None;
+        let package = &mut spdx.package_information[0];
+        package.concluded_license = Some(
+            SpdxExpression::parse(
+                "GPL-2.0 OR mit OR Foo-1.0 OR Apache-2.0+ OR LicenseRef-Undefined \
+                 OR GPL-2.0-only WITH Foo-exception",
+            )
+            .unwrap(),
+        );
+        let package_id = package.package_spdx_identifier.clone();
+
+        assert_eq!(
+            problems(&spdx, &package_id),
+            vec![
+                (
+                    Some("GPL-2.0".to_string()),
+                    LicenseListProblemKind::DeprecatedLicense {
+                        replacement: Some("GPL-2.0-only".to_string())
+                    }
+                ),
+                (
+                    Some("mit".to_string()),
+                    LicenseListProblemKind::WrongCase {
+                        correct: "MIT".to_string()
+                    }
+                ),
+                (
+                    Some("Foo-1.0".to_string()),
+                    LicenseListProblemKind::UnknownLicense
+                ),
+                (
+                    Some("LicenseRef-Undefined".to_string()),
+                    LicenseListProblemKind::UndefinedLicenseRef
+                ),
+                (
+                    Some("Foo-exception".to_string()),
+                    LicenseListProblemKind::UnknownException
+                ),
+            ]
+        );
+        assert_eq!(
+            problems(&spdx, "SPDXRef-DOCUMENT"),
+            vec![(
+                None,
+                LicenseListProblemKind::LicenseListVersionMismatch {
+                    document: None,
+                    bundled: "3.21".to_string()
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,359 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashSet;
+
+use log::info;
+use serde::Serialize;
+
+use super::{LicenseList, LicenseRewrite, RewriteReport};
+use crate::models::SPDX;
+
+/// Maximum number of words matched by a variable part of a template.
+const MAX_VAR_WORDS: usize = 500;
+
+/// Words that are equivalent according to the SPDX matching guidelines.
+const EQUIVALENT_WORDS: &[(&str, &str)] = &[
+    ("acknowledgement", "acknowledgment"),
+    ("analogue", "analog"),
+    ("authorisation", "authorization"),
+    ("authorised", "authorized"),
+    ("https", "http"),
+    ("licence", "license"),
+    ("licences", "licenses"),
+    ("licensor", "licenser"),
+    ("organisation", "organization"),
+    ("sublicence", "sublicense"),
+];
+
+/// A `LicenseRef-` whose extracted text matches a license of the SPDX License List.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseRefMatch {
+    pub license_ref: String,
+    pub license_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Op {
+    Word(String),
+
+    /// Any text, `<<var>>` of a template.
+    Var,
+
+    /// Start of an optional part, `end` is the index of the op after the part.
+    Optional {
+        end: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Template {
+    license_id: String,
+    ops: Vec<Op>,
+
+    /// Number of words that must match, for preferring the most specific template.
+    words: usize,
+}
+
+/// Matches license texts against the templates of the SPDX License List.
+///
+/// The texts are compared as described in the [SPDX matching guidelines]: case, whitespace,
+/// punctuation, bullets and numbering at the start of lines, and equivalent words such as
+/// `licence` and `license` are ignored. `<<var>>` parts of the templates match any text and
+/// `<<beginOptional>>` parts may be missing. Licenses without a template are matched against
+/// their text.
+///
+/// [SPDX matching guidelines]: https://spdx.github.io/spdx-spec/v2.3/license-matching-guidelines-and-templates/
+#[derive(Debug, Clone)]
+pub struct LicenseMatcher {
+    templates: Vec<Template>,
+}
+
+impl LicenseMatcher {
+    /// Matcher for the licenses of the list that have a template or a text. Deprecated
+    /// licenses are not matched.
+    pub fn new(license_list: &LicenseList) -> Self {
+        let templates = license_list
+            .licenses
+            .iter()
+            .filter(|license| !license.is_deprecated_license_id)
+            .filter_map(|license| {
+                let template = license
+                    .standard_license_template
+                    .as_ref()
+                    .or(license.license_text.as_ref())?;
+                let ops = compile(template);
+                Some(Template {
+                    license_id: license.license_id.clone(),
+                    words: ops.iter().filter(|op| matches!(op, Op::Word(_))).count(),
+                    ops,
+                })
+            })
+            .collect();
+        Self { templates }
+    }
+
+    /// Identifier of the license the text matches. If the text matches multiple licenses, the
+    /// one with the longest template wins. Copyright notices before the license text are
+    /// ignored.
+    pub fn find(&self, text: &str) -> Option<&str> {
+        self.find_exact(text).or_else(|| {
+            let without_copyright = text
+                .lines()
+                .skip_while(|line| {
+                    let line = line.trim().to_lowercase();
+                    line.is_empty()
+                        || line.starts_with("copyright")
+                        || line.starts_with("(c)")
+                        || line.starts_with('©')
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            self.find_exact(&without_copyright)
+        })
+    }
+
+    fn find_exact(&self, text: &str) -> Option<&str> {
+        let words = words(text);
+        self.templates
+            .iter()
+            .filter(|template| matches(&template.ops, &words))
+            .max_by_key(|template| template.words)
+            .map(|template| template.license_id.as_str())
+    }
+
+    /// Find the `LicenseRef-`s of the document that are licenses of the SPDX License List.
+    pub fn suggest(&self, spdx: &SPDX) -> Vec<LicenseRefMatch> {
+        info!("Matching the extracted licenses of SPDX against the SPDX License List.");
+        spdx.other_licensing_information_detected
+            .iter()
+            .filter_map(|other| {
+                self.find(&other.extracted_text)
+                    .map(|license_id| LicenseRefMatch {
+                        license_ref: other.license_identifier.clone(),
+                        license_id: license_id.to_string(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Replace the `LicenseRef-`s of the document that are licenses of the SPDX License List
+    /// with the license identifiers with [`LicenseRewrite`].
+    pub fn apply(&self, spdx: &mut SPDX) -> (Vec<LicenseRefMatch>, RewriteReport) {
+        let matches = self.suggest(spdx);
+        let mut rewrite = LicenseRewrite::new();
+        for license_match in &matches {
+            // The identifiers come from the license list, so they are valid expressions.
+            let _ = rewrite.map(&license_match.license_ref, &license_match.license_id);
+        }
+        let report = rewrite.apply(spdx);
+        (matches, report)
+    }
+}
+
+fn is_bullet(token: &str) -> bool {
+    if matches!(token, "*" | "-" | "•" | "·" | "–") {
+        return true;
+    }
+    let Some(marker) = token.strip_suffix('.').or_else(|| token.strip_suffix(')')) else {
+        return false;
+    };
+    let marker = marker.strip_prefix('(').unwrap_or(marker);
+    (!marker.is_empty() && marker.len() <= 3 && marker.chars().all(|c| c.is_ascii_digit()))
+        || (marker.len() == 1 && marker.chars().all(|c| c.is_ascii_alphabetic()))
+        || matches!(
+            marker,
+            "i" | "ii" | "iii" | "iv" | "v" | "vi" | "vii" | "viii" | "ix" | "x"
+        )
+}
+
+/// The normalized words of a text.
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for line in text.lines() {
+        let mut tokens = line.split_whitespace().peekable();
+        if tokens.peek().is_some_and(|token| is_bullet(token)) {
+            tokens.next();
+        }
+        for token in tokens {
+            let token = token.to_lowercase().replace('©', " c ");
+            for word in token
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+            {
+                let word = EQUIVALENT_WORDS
+                    .iter()
+                    .find(|(variant, _)| *variant == word)
+                    .map_or(word, |(_, equivalent)| equivalent);
+                words.push(word.to_string());
+            }
+        }
+    }
+    words
+}
+
+fn compile(template: &str) -> Vec<Op> {
+    let mut ops = Vec::new();
+    let mut optionals = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("<<") {
+        let Some(length) = rest[start..].find(">>") else {
+            break;
+        };
+        ops.extend(words(&rest[..start]).into_iter().map(Op::Word));
+        let tag = &rest[start + 2..start + length];
+        if tag.starts_with("beginOptional") {
+            optionals.push(ops.len());
+            ops.push(Op::Optional { end: 0 });
+        } else if tag.starts_with("endOptional") {
+            if let Some(index) = optionals.pop() {
+                ops[index] = Op::Optional { end: ops.len() };
+            }
+        } else if tag.starts_with("var") {
+            ops.push(Op::Var);
+        }
+        rest = &rest[start + length + 2..];
+    }
+    ops.extend(words(rest).into_iter().map(Op::Word));
+    ops
+}
+
+fn matches(ops: &[Op], words: &[String]) -> bool {
+    matches_from(ops, words, 0, 0, &mut HashSet::new())
+}
+
+fn matches_from(
+    ops: &[Op],
+    words: &[String],
+    mut op: usize,
+    mut word: usize,
+    failed: &mut HashSet<(usize, usize)>,
+) -> bool {
+    let start = (op, word);
+    if failed.contains(&start) {
+        return false;
+    }
+    while let Some(Op::Word(expected)) = ops.get(op) {
+        if words.get(word) != Some(expected) {
+            failed.insert(start);
+            return false;
+        }
+        op += 1;
+        word += 1;
+    }
+
+    let matched = match ops.get(op) {
+        None => word == words.len(),
+        Some(Op::Optional { end }) => {
+            matches_from(ops, words, op + 1, word, failed)
+                || matches_from(ops, words, *end, word, failed)
+        }
+        Some(Op::Var) => (word..=words.len().min(word + MAX_VAR_WORDS))
+            .any(|next| matches_from(ops, words, op + 1, next, failed)),
+        Some(Op::Word(_)) => unreachable!("words are matched above"),
+    };
+    if !matched {
+        failed.insert(start);
+    }
+    matched
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::models::{OtherLicensingInformationDetected, SpdxExpression};
+
+    use super::*;
+
+    fn matcher() -> LicenseMatcher {
+        LicenseMatcher::new(LicenseList::bundled())
+    }
+
+    #[test]
+    fn text_matches_with_different_formatting() {
+        let text = "Copyright (C) 2021 Jane Doe <jane@example.com>\n\n\
+                    Permission is hereby granted, free of charge, to any person obtaining a copy \
+                    of this software and associated documentation files (the \u{201c}Software\
+                    \u{201d}), to deal\n  in the Software without restriction, including without \
+                    limitation the rights to use, copy, modify, merge, publish, distribute, \
+                    sublicense, and/or sell copies of the Software, and to permit persons to \
+                    whom the Software is furnished to do so, subject to the following \
+                    conditions:\n\n* The above copyright notice and this permission notice \
+                    shall be included in all copies or substantial portions of the Software.\n\n\
+                    THE SOFTWARE IS PROVIDED \"AS IS\", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR \
+                    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, \
+                    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE \
+                    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER \
+                    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING \
+                    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER \
+                    DEALINGS IN THE SOFTWARE.";
+        assert_eq!(matcher().find(text), Some("MIT"));
+    }
+
+    #[test]
+    fn most_specific_template_wins() {
+        let matcher = matcher();
+        let bsd_3 = read_to_string("data/license-list/text/BSD-3-Clause.txt").unwrap();
+        assert_eq!(matcher.find(&bsd_3), Some("BSD-3-Clause"));
+
+        let bsd_2 = read_to_string("data/license-list/text/BSD-2-Clause.txt").unwrap();
+        let isc = read_to_string("data/license-list/text/ISC.txt").unwrap();
+        assert_eq!(matcher.find(&bsd_2), Some("BSD-2-Clause"));
+        assert_eq!(matcher.find(&isc), Some("ISC"));
+        assert_eq!(matcher.find(&format!("{bsd_2}\nExtra terms.")), None);
+    }
+
+    #[test]
+    fn optional_parts_and_leading_copyright() {
+        let apache = read_to_string("data/license-list/text/Apache-2.0.txt").unwrap();
+        let terms = &apache[apache.find("TERMS AND CONDITIONS").unwrap()
+            ..apache.find("END OF TERMS AND CONDITIONS").unwrap()];
+        assert_eq!(
+            matcher().find(&format!("Copyright 2021 Example Ltd.\n\n{terms}")),
+            Some("Apache-2.0")
+        );
+    }
+
+    #[test]
+    fn license_refs_are_replaced() {
+        let mut spdx: SPDX = serde_json::from_str(
+            &read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap(),
+        )
+        .unwrap();
+        spdx.other_licensing_information_detected
+            .push(OtherLicensingInformationDetected {
+                license_identifier: "LicenseRef-zlib".to_string(),
+                extracted_text: read_to_string("data/license-list/text/Zlib.txt").unwrap(),
+                ..OtherLicensingInformationDetected::default()
+            });
+        spdx.package_information[0].concluded_license =
+            Some(SpdxExpression::parse("LicenseRef-zlib OR (MIT AND LicenseRef-3)").unwrap());
+        spdx.snippet_information[0].license_information_in_snippet =
+            vec!["LicenseRef-zlib".to_string()];
+
+        let (matches, report) = matcher().apply(&mut spdx);
+        assert_eq!(
+            matches,
+            vec![LicenseRefMatch {
+                license_ref: "LicenseRef-zlib".to_string(),
+                license_id: "Zlib".to_string(),
+            }]
+        );
+        assert_eq!(
+            spdx.package_information[0]
+                .concluded_license
+                .as_ref()
+                .unwrap()
+                .to_string(),
+            "Zlib OR (MIT AND LicenseRef-3)"
+        );
+        assert_eq!(
+            spdx.snippet_information[0].license_information_in_snippet,
+            vec!["Zlib"]
+        );
+        assert_eq!(report.removed_license_refs, vec!["LicenseRef-zlib"]);
+    }
+}
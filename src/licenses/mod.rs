@@ -0,0 +1,21 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Analysis of the licenses of SPDX documents.
+
+mod consistency;
+#[cfg(feature = "license-list")]
+mod license_list;
+#[cfg(feature = "license-list")]
+mod matcher;
+mod notices;
+mod rewrite;
+
+pub use consistency::*;
+#[cfg(feature = "license-list")]
+pub use license_list::*;
+#[cfg(feature = "license-list")]
+pub use matcher::*;
+pub use notices::*;
+pub use rewrite::*;
@@ -0,0 +1,445 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use log::info;
+use serde::Serialize;
+
+use super::consistency::package_files;
+#[cfg(feature = "license-list")]
+use super::LicenseList;
+use crate::models::{
+    FileInformation, LicenseTree, PackageInformation, SpdxExpression, SpecialValue, SPDX,
+};
+
+/// Notices of a file of a package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileNotice {
+    pub spdx_id: String,
+    pub file_name: String,
+    pub copyright_text: Option<String>,
+    pub attribution_texts: Vec<String>,
+    pub notice: Option<String>,
+}
+
+/// Notices of a package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageNotice {
+    pub spdx_id: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub copyright_text: Option<String>,
+    pub attribution_texts: Vec<String>,
+
+    /// Files of the package with a copyright, attribution text or notice.
+    pub files: Vec<FileNotice>,
+}
+
+/// Text of a license in the notices.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseText {
+    pub license: String,
+    pub name: Option<String>,
+
+    /// Full text of the license, `None` if the text is not available.
+    pub text: Option<String>,
+    pub see_also: Vec<String>,
+}
+
+/// Packages that share a license and the texts of the licenses of the expression.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseGroup {
+    pub license: String,
+    pub packages: Vec<PackageNotice>,
+    pub license_texts: Vec<LicenseText>,
+}
+
+/// Third-party notices of the packages of a document, grouped by license.
+///
+/// The license of a package is the concluded license, or the declared license if the concluded
+/// license is missing or `NOASSERTION`. Texts of `LicenseRef-`s come from the extracted texts
+/// of the document. With the `license-list` feature, texts of the licenses on the SPDX License
+/// List come from the bundled license list.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notices {
+    pub groups: Vec<LicenseGroup>,
+}
+
+impl Notices {
+    /// Collect the notices of the packages of the document.
+    pub fn new(spdx: &SPDX) -> Self {
+        #[cfg(feature = "license-list")]
+        return Self::with_license_list(spdx, LicenseList::bundled());
+
+        #[cfg(not(feature = "license-list"))]
+        Self::collect(spdx, |_| None)
+    }
+
+    /// Collect the notices of the packages of the document, with the texts of the listed
+    /// licenses from the given license list.
+    #[cfg(feature = "license-list")]
+    pub fn with_license_list(spdx: &SPDX, license_list: &LicenseList) -> Self {
+        Self::collect(spdx, |license| {
+            license_list.license(license).map(|license| LicenseText {
+                license: license.license_id.clone(),
+                name: Some(license.name.clone()),
+                text: license.license_text.clone(),
+                see_also: license.see_also.clone(),
+            })
+        })
+    }
+
+    fn collect(spdx: &SPDX, listed_license: impl Fn(&str) -> Option<LicenseText>) -> Self {
+        info!("Collecting the notices of SPDX.");
+        let mut packages: BTreeMap<String, Vec<PackageNotice>> = BTreeMap::new();
+        let mut trees = BTreeMap::new();
+        for package in &spdx.package_information {
+            let tree = package_license(package).map(LicenseTree::from);
+            let key = tree
+                .as_ref()
+                .map_or_else(|| "NOASSERTION".to_string(), ToString::to_string);
+            if let Some(tree) = tree {
+                trees.insert(key.clone(), tree);
+            }
+            packages
+                .entry(key)
+                .or_default()
+                .push(PackageNotice::new(spdx, package));
+        }
+
+        let groups = packages
+            .into_iter()
+            .map(|(license, packages)| {
+                let mut license_texts: Vec<LicenseText> = Vec::new();
+                let terms = trees
+                    .get(&license)
+                    .map_or_else(Vec::new, LicenseTree::terms);
+                for term in terms {
+                    if SpecialValue::of(&term.license).is_some()
+                        || license_texts
+                            .iter()
+                            .any(|text| text.license == term.license)
+                    {
+                        continue;
+                    }
+                    license_texts.push(license_text(spdx, &term.license, &listed_license));
+                }
+                LicenseGroup {
+                    license,
+                    packages,
+                    license_texts,
+                }
+            })
+            .collect();
+
+        Self { groups }
+    }
+
+    /// Plain text notices.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("THIRD-PARTY SOFTWARE NOTICES\n");
+        for group in &self.groups {
+            let rule = "=".repeat(group.license.chars().count().min(80));
+            let _ = write!(text, "\n{rule}\n{}\n{rule}\n", group.license);
+            for package in &group.packages {
+                let _ = write!(text, "\n{}\n", package.title());
+                for line in package.lines() {
+                    let _ = writeln!(text, "{line}");
+                }
+                for file in &package.files {
+                    let _ = writeln!(text, "\n  {}", file.file_name);
+                    for line in file.lines() {
+                        for line in line.lines() {
+                            let _ = writeln!(text, "    {line}");
+                        }
+                    }
+                }
+            }
+            for license in &group.license_texts {
+                let _ = write!(text, "\n--- {}\n\n{}\n", license.title(), license.body());
+            }
+        }
+        text
+    }
+
+    /// Markdown notices.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Third-party software notices\n");
+        for group in &self.groups {
+            let _ = write!(markdown, "\n## {}\n", group.license);
+            for package in &group.packages {
+                let _ = write!(markdown, "\n### {}\n", package.title());
+                for line in package.lines() {
+                    let _ = write!(markdown, "\n```text\n{}\n```\n", line.trim_end());
+                }
+                for file in &package.files {
+                    let _ = write!(markdown, "\n#### {}\n", file.file_name);
+                    for line in file.lines() {
+                        let _ = write!(markdown, "\n```text\n{}\n```\n", line.trim_end());
+                    }
+                }
+            }
+            for license in &group.license_texts {
+                let _ = write!(
+                    markdown,
+                    "\n### License: {}\n\n```text\n{}\n```\n",
+                    license.title(),
+                    license.body().trim_end()
+                );
+            }
+        }
+        markdown
+    }
+
+    /// HTML notices.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Third-party software notices</title>\n</head>\n<body>\n\
+             <h1>Third-party software notices</h1>\n",
+        );
+        for group in &self.groups {
+            let _ = writeln!(html, "<h2>{}</h2>", escape_html(&group.license));
+            for package in &group.packages {
+                let _ = writeln!(html, "<h3>{}</h3>", escape_html(&package.title()));
+                for line in package.lines() {
+                    let _ = writeln!(html, "<pre>{}</pre>", escape_html(line.trim_end()));
+                }
+                for file in &package.files {
+                    let _ = writeln!(html, "<h4>{}</h4>", escape_html(&file.file_name));
+                    for line in file.lines() {
+                        let _ = writeln!(html, "<pre>{}</pre>", escape_html(line.trim_end()));
+                    }
+                }
+            }
+            for license in &group.license_texts {
+                let _ = writeln!(
+                    html,
+                    "<h3>License: {}</h3>\n<pre>{}</pre>",
+                    escape_html(&license.title()),
+                    escape_html(license.body().trim_end())
+                );
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+impl PackageNotice {
+    fn new(spdx: &SPDX, package: &PackageInformation) -> Self {
+        Self {
+            spdx_id: package.package_spdx_identifier.clone(),
+            name: package.package_name.clone(),
+            version: package.package_version.clone(),
+            copyright_text: copyright(package.copyright_text.as_ref()),
+            attribution_texts: package.package_attribution_text.clone(),
+            files: package_files(spdx, package)
+                .into_iter()
+                .map(FileNotice::new)
+                .filter(|file| {
+                    file.copyright_text.is_some()
+                        || !file.attribution_texts.is_empty()
+                        || file.notice.is_some()
+                })
+                .collect(),
+        }
+    }
+
+    fn title(&self) -> String {
+        self.version.as_ref().map_or_else(
+            || self.name.clone(),
+            |version| format!("{} {version}", self.name),
+        )
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &String> {
+        self.copyright_text.iter().chain(&self.attribution_texts)
+    }
+}
+
+impl FileNotice {
+    fn new(file: &FileInformation) -> Self {
+        Self {
+            spdx_id: file.file_spdx_identifier.clone(),
+            file_name: file.file_name.clone(),
+            copyright_text: copyright(file.copyright_text.as_ref()),
+            attribution_texts: file.file_attribution_text.clone().unwrap_or_default(),
+            notice: file.file_notice.clone(),
+        }
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &String> {
+        self.copyright_text
+            .iter()
+            .chain(&self.attribution_texts)
+            .chain(&self.notice)
+    }
+}
+
+impl LicenseText {
+    fn title(&self) -> String {
+        match &self.name {
+            Some(name) if name != &self.license => format!("{name} ({})", self.license),
+            _ => self.license.clone(),
+        }
+    }
+
+    /// The text of the license, or a reference to it if the text is not available.
+    fn body(&self) -> String {
+        match (&self.text, self.see_also.is_empty()) {
+            (Some(text), _) => text.clone(),
+            (None, false) => format!("See {}", self.see_also.join(", ")),
+            (None, true) => "License text not available.".to_string(),
+        }
+    }
+}
+
+fn package_license(package: &PackageInformation) -> Option<&SpdxExpression> {
+    package
+        .concluded_license
+        .as_ref()
+        .filter(|license| !SpecialValue::is_noassertion(&license.to_string()))
+        .or(package.declared_license.as_ref())
+}
+
+/// Copyright text, `None` if it's missing, `NONE` or `NOASSERTION`.
+fn copyright(copyright_text: Option<&String>) -> Option<String> {
+    copyright_text
+        .filter(|text| SpecialValue::of(text).is_none())
+        .cloned()
+}
+
+fn license_text(
+    spdx: &SPDX,
+    license: &str,
+    listed_license: impl Fn(&str) -> Option<LicenseText>,
+) -> LicenseText {
+    let extracted = spdx
+        .other_licensing_information_detected
+        .iter()
+        .find(|other| other.license_identifier == license);
+    extracted.map_or_else(
+        || {
+            listed_license(license).unwrap_or_else(|| LicenseText {
+                license: license.to_string(),
+                name: None,
+                text: None,
+                see_also: Vec::new(),
+            })
+        },
+        |extracted| LicenseText {
+            license: license.to_string(),
+            name: Some(extracted.license_name.clone()),
+            text: Some(extracted.extracted_text.clone()),
+            see_also: extracted.license_cross_reference.clone(),
+        },
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn packages_are_grouped_by_license() {
+        let notices = Notices::new(&example());
+        let licenses = notices
+            .groups
+            .iter()
+            .map(|group| group.license.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            licenses,
+            vec!["LGPL-2.0-only OR LicenseRef-3", "MPL-1.0", "NOASSERTION"]
+        );
+
+        let glibc = &notices.groups[0];
+        assert_eq!(glibc.packages[0].name, "glibc");
+        assert_eq!(
+            glibc.packages[0].copyright_text.as_deref(),
+            Some("Copyright 2008-2010 John Smith")
+        );
+        assert!(glibc.packages[0]
+            .files
+            .iter()
+            .any(|file| file.file_name == "./lib-source/commons-lang3-3.1-sources.jar"));
+        let licenseref = &glibc.license_texts[1];
+        assert_eq!(licenseref.license, "LicenseRef-3");
+        assert_eq!(licenseref.name.as_deref(), Some("CyberNeko License"));
+        assert!(licenseref.text.is_some());
+        assert!(notices.groups[2].license_texts.is_empty());
+    }
+
+    #[test]
+    fn notices_are_rendered() {
+        let notices = Notices::new(&example());
+
+        let text = notices.to_text();
+        assert!(text.starts_with("THIRD-PARTY SOFTWARE NOTICES\n"));
+        assert!(text.contains("\nglibc 2.11.1\nCopyright 2008-2010 John Smith\n"));
+
+        let markdown = notices.to_markdown();
+        assert!(markdown.contains("\n## LGPL-2.0-only OR LicenseRef-3\n"));
+        assert!(markdown.contains("\n### License: CyberNeko License (LicenseRef-3)\n"));
+
+        let html = notices.to_html();
+        assert!(html.contains("<h3>glibc 2.11.1</h3>"));
+        assert_eq!(
+            escape_html("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[cfg(feature = "license-list")]
+    #[test]
+    fn listed_license_texts_come_from_license_list() {
+        let mut spdx = example();
+        spdx.package_information[0].concluded_license =
+            Some(SpdxExpression::parse("MIT OR GPL-2.0-only").unwrap());
+
+        let notices = Notices::new(&spdx);
+        let group = &notices.groups[0];
+        assert_eq!(group.license, "MIT OR GPL-2.0-only");
+        assert!(group.license_texts[0]
+            .text
+            .as_deref()
+            .unwrap()
+            .starts_with("MIT License"));
+        assert_eq!(
+            group.license_texts[1].name.as_deref(),
+            Some("GNU General Public License v2.0 only")
+        );
+        assert!(notices
+            .to_text()
+            .contains("See https://www.gnu.org/licenses/old-licenses/gpl-2.0-standalone.html"));
+    }
+}
@@ -0,0 +1,347 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeSet;
+
+use log::info;
+use serde::Serialize;
+
+#[cfg(feature = "license-list")]
+use super::LicenseList;
+use crate::{
+    error::SpdxError,
+    models::{LicenseTerm, LicenseTree, SpdxExpression, SPDX},
+};
+
+/// Result of [`LicenseRewrite::apply`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewriteReport {
+    /// SPDX IDs of the elements whose licenses were rewritten.
+    pub rewritten: Vec<String>,
+
+    /// `LicenseRef-`s removed from the other licensing information because they are no longer
+    /// used.
+    pub removed_license_refs: Vec<String>,
+}
+
+/// Replaces licenses in all license expressions of a document.
+///
+/// # Example
+///
+/// ```
+/// use spdx_rs::{licenses::LicenseRewrite, models::SpdxExpression};
+///
+/// let mut rewrite = LicenseRewrite::new();
+/// rewrite.map("GPL-2.0", "GPL-2.0-only")?.map("LicenseRef-3", "MIT")?;
+///
+/// let expression = SpdxExpression::parse("GPL-2.0 OR LicenseRef-3 OR MIT")?;
+/// assert_eq!(rewrite.rewrite(&expression).to_string(), "GPL-2.0-only OR MIT");
+/// # Ok::<(), spdx_rs::error::SpdxError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LicenseRewrite {
+    mapping: Vec<(String, LicenseTree)>,
+}
+
+impl LicenseRewrite {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rewrite of the deprecated licenses of the license list to their replacements, e.g.
+    /// `GPL-2.0+` to `GPL-2.0-or-later`.
+    #[cfg(feature = "license-list")]
+    pub fn deprecated_licenses(license_list: &LicenseList) -> Self {
+        let mut rewrite = Self::new();
+        for license in &license_list.licenses {
+            if let Some(replacement) = license_list.replacement(&license.license_id) {
+                if let Ok(replacement) = SpdxExpression::parse(&replacement) {
+                    rewrite
+                        .mapping
+                        .push((license.license_id.clone(), LicenseTree::from(&replacement)));
+                }
+            }
+        }
+        rewrite
+    }
+
+    /// Replace the license `from` with the expression `to`. Licenses are matched
+    /// case-insensitively. If the license has an exception and `to` is a single license, the
+    /// exception is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Parse`] if `to` is not a valid license expression.
+    pub fn map(&mut self, from: &str, to: &str) -> Result<&mut Self, SpdxError> {
+        let to = LicenseTree::from(&SpdxExpression::parse(to)?);
+        self.mapping.push((from.to_string(), to));
+        Ok(self)
+    }
+
+    /// Rewrite a license expression. Rewritten expressions are simplified, unchanged
+    /// expressions are returned as is.
+    pub fn rewrite(&self, expression: &SpdxExpression) -> SpdxExpression {
+        self.rewrite_tree(LicenseTree::from(expression))
+            .and_then(|tree| SpdxExpression::parse(&tree.to_string()).ok())
+            .unwrap_or_else(|| expression.clone())
+    }
+
+    /// Rewrite the license expressions of the packages, files and snippets of the document,
+    /// including `licenseInfoFromFiles` and `licenseInfoInSnippets`. `LicenseRef-`s that were
+    /// used before the rewrite but not after it are removed from the other licensing
+    /// information.
+    pub fn apply(&self, spdx: &mut SPDX) -> RewriteReport {
+        info!("Rewriting the licenses of SPDX.");
+        let used_before = license_refs(spdx);
+        let mut rewritten = Vec::new();
+
+        for package in &mut spdx.package_information {
+            let mut changed = false;
+            for expression in package
+                .concluded_license
+                .iter_mut()
+                .chain(package.declared_license.iter_mut())
+            {
+                changed |= self.rewrite_in_place(expression);
+            }
+            changed |= self.rewrite_identifiers(&mut package.all_licenses_information_from_files);
+            if changed {
+                rewritten.push(package.package_spdx_identifier.clone());
+            }
+        }
+        for file in &mut spdx.file_information {
+            let mut changed = false;
+            for expression in file
+                .concluded_license
+                .iter_mut()
+                .chain(file.license_information_in_file.iter_mut())
+            {
+                changed |= self.rewrite_in_place(expression);
+            }
+            if changed {
+                rewritten.push(file.file_spdx_identifier.clone());
+            }
+        }
+        for snippet in &mut spdx.snippet_information {
+            let mut changed = snippet
+                .snippet_concluded_license
+                .as_mut()
+                .is_some_and(|expression| self.rewrite_in_place(expression));
+            changed |= self.rewrite_identifiers(&mut snippet.license_information_in_snippet);
+            if changed {
+                rewritten.push(snippet.snippet_spdx_identifier.clone());
+            }
+        }
+
+        let used_after = license_refs(spdx);
+        let removed_license_refs = used_before
+            .difference(&used_after)
+            .cloned()
+            .collect::<Vec<_>>();
+        spdx.other_licensing_information_detected
+            .retain(|other| !removed_license_refs.contains(&other.license_identifier));
+
+        RewriteReport {
+            rewritten,
+            removed_license_refs,
+        }
+    }
+
+    /// The rewritten and simplified tree, `None` if no license was replaced.
+    fn rewrite_tree(&self, tree: LicenseTree) -> Option<LicenseTree> {
+        let mut changed = false;
+        let tree = tree.replace(&mut |term| {
+            let replacement = self.replacement(term);
+            changed |= replacement.is_some();
+            replacement
+        });
+        changed.then(|| tree.simplify())
+    }
+
+    fn replacement(&self, term: &LicenseTerm) -> Option<LicenseTree> {
+        let (_, to) = self
+            .mapping
+            .iter()
+            .find(|(from, _)| from.eq_ignore_ascii_case(&term.license))?;
+        match (to, &term.exception) {
+            (LicenseTree::License(to), Some(exception)) if to.exception.is_none() => Some(
+                LicenseTree::License(LicenseTerm::new(&to.license, Some(exception))),
+            ),
+            _ => Some(to.clone()),
+        }
+    }
+
+    fn rewrite_in_place(&self, expression: &mut SpdxExpression) -> bool {
+        let rewritten = self.rewrite(expression);
+        let changed = rewritten != *expression;
+        *expression = rewritten;
+        changed
+    }
+
+    /// Rewrite a list of license identifiers. Identifiers replaced with an expression are
+    /// replaced with the licenses of the expression.
+    fn rewrite_identifiers(&self, licenses: &mut Vec<String>) -> bool {
+        let mut changed = false;
+        let mut rewritten: Vec<String> = Vec::new();
+        for license in licenses.drain(..) {
+            let replacement = self.replacement(&LicenseTerm::new(&license, None));
+            let replaced = replacement.map_or_else(
+                || vec![license],
+                |tree| {
+                    changed = true;
+                    tree.terms()
+                        .into_iter()
+                        .map(|term| term.license.clone())
+                        .collect()
+                },
+            );
+            for license in replaced {
+                if !rewritten.contains(&license) {
+                    rewritten.push(license);
+                }
+            }
+        }
+        *licenses = rewritten;
+        changed
+    }
+}
+
+/// `LicenseRef-`s used in the license expressions of the document.
+fn license_refs(spdx: &SPDX) -> BTreeSet<String> {
+    let expressions = spdx
+        .package_information
+        .iter()
+        .flat_map(|package| {
+            package
+                .concluded_license
+                .iter()
+                .chain(&package.declared_license)
+        })
+        .chain(spdx.file_information.iter().flat_map(|file| {
+            file.concluded_license
+                .iter()
+                .chain(&file.license_information_in_file)
+        }))
+        .chain(
+            spdx.snippet_information
+                .iter()
+                .flat_map(|snippet| &snippet.snippet_concluded_license),
+        )
+        .flat_map(|expression| {
+            LicenseTree::from(expression)
+                .terms()
+                .into_iter()
+                .map(|term| term.license.clone())
+                .collect::<Vec<_>>()
+        });
+    let identifiers = spdx
+        .package_information
+        .iter()
+        .flat_map(|package| &package.all_licenses_information_from_files)
+        .chain(
+            spdx.snippet_information
+                .iter()
+                .flat_map(|snippet| &snippet.license_information_in_snippet),
+        )
+        .cloned();
+
+    expressions
+        .chain(identifiers)
+        .filter(|license| license.starts_with("LicenseRef-"))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    fn expression(expression: &str) -> SpdxExpression {
+        SpdxExpression::parse(expression).unwrap()
+    }
+
+    #[test]
+    fn expressions_are_rewritten_and_simplified() {
+        let mut rewrite = LicenseRewrite::new();
+        rewrite
+            .map("gpl-2.0", "GPL-2.0-only")
+            .unwrap()
+            .map("LicenseRef-Apache", "Apache-2.0")
+            .unwrap();
+
+        assert_eq!(
+            rewrite
+                .rewrite(&expression(
+                    "GPL-2.0 WITH Classpath-exception-2.0 OR (LicenseRef-Apache AND Apache-2.0)"
+                ))
+                .to_string(),
+            "GPL-2.0-only WITH Classpath-exception-2.0 OR Apache-2.0"
+        );
+        let unchanged = expression("(MIT OR MIT)");
+        assert_eq!(rewrite.rewrite(&unchanged), unchanged);
+        assert!(rewrite.map("MIT", "MIT OR").is_err());
+    }
+
+    #[test]
+    fn document_is_rewritten() {
+        let mut spdx = example();
+        let mut rewrite = LicenseRewrite::new();
+        rewrite
+            .map("LicenseRef-3", "MIT")
+            .unwrap()
+            .map("LicenseRef-2", "BSD-2-Clause AND BSD-3-Clause")
+            .unwrap();
+        let report = rewrite.apply(&mut spdx);
+
+        assert_eq!(
+            report.rewritten,
+            vec!["SPDXRef-Package".to_string(), "SPDXRef-File".to_string()]
+        );
+        assert_eq!(
+            report.removed_license_refs,
+            vec!["LicenseRef-2".to_string(), "LicenseRef-3".to_string()]
+        );
+        let package = &spdx.package_information[0];
+        assert_eq!(
+            package.concluded_license.as_ref().unwrap().to_string(),
+            "LGPL-2.0-only OR MIT"
+        );
+        assert_eq!(
+            package.all_licenses_information_from_files,
+            vec![
+                "GPL-2.0-only",
+                "BSD-2-Clause",
+                "BSD-3-Clause",
+                "LicenseRef-1"
+            ]
+        );
+        assert!(spdx
+            .other_licensing_information_detected
+            .iter()
+            .all(|other| !matches!(
+                other.license_identifier.as_str(),
+                "LicenseRef-2" | "LicenseRef-3"
+            )));
+        assert_eq!(spdx.other_licensing_information_detected.len(), 3);
+    }
+
+    #[cfg(feature = "license-list")]
+    #[test]
+    fn deprecated_licenses_are_replaced() {
+        let rewrite = LicenseRewrite::deprecated_licenses(LicenseList::bundled());
+        assert_eq!(
+            rewrite
+                .rewrite(&expression("GPL-2.0+ AND GPL-2.0-with-classpath-exception"))
+                .to_string(),
+            "GPL-2.0-or-later AND GPL-2.0-only WITH Classpath-exception-2.0"
+        );
+    }
+}
@@ -2,8 +2,11 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// <https://spdx.github.io/spdx-spec/8-annotations/>
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -26,6 +29,10 @@ pub struct Annotation {
     /// <https://spdx.github.io/spdx-spec/8-annotations/#85-annotation-comment>
     #[serde(rename = "comment")]
     pub annotation_comment: String,
+
+    /// Fields of the annotation that are not defined by the spec.
+    #[serde(flatten, skip_deserializing)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 impl Annotation {
@@ -42,12 +49,13 @@ impl Annotation {
             annotation_type,
             spdx_identifier_reference,
             annotation_comment,
+            extensions: BTreeMap::new(),
         }
     }
 }
 
 /// <https://spdx.github.io/spdx-spec/8-annotations/#83-annotation-type>
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AnnotationType {
     Review,
@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use log::info;
+
+use super::{
+    Annotation, DocumentCreationInformation, FileInformation, OtherLicensingInformationDetected,
+    PackageInformation, Relationship, Snippet, SPDX,
+};
+
+impl SPDX {
+    /// Sort the collections of the document to a canonical order, so that documents with the
+    /// same content are serialized identically regardless of the order the elements were
+    /// added or parsed in.
+    ///
+    /// Elements are sorted by their SPDX IDs, relationships and annotations by their fields, and
+    /// lists of values like checksums, creators and license identifiers by their values.
+    /// Duplicate relationships, file references of packages and described elements are removed.
+    /// The order of snippet ranges is kept, as a snippet lists its byte range before its line
+    /// range.
+    ///
+    /// For byte-identical output, also fix the parts that are generated when a document is
+    /// created: the namespace with [`SPDX::set_namespace_uuid`] and the creation time in
+    /// [`CreationInfo::created`](super::CreationInfo::created).
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use spdx_rs::models::{PackageInformation, SPDX};
+    ///
+    /// let mut spdx = SPDX::new("canonical");
+    /// spdx.package_information.push(PackageInformation::new("b", &mut 1));
+    /// spdx.package_information.push(PackageInformation::new("a", &mut 0));
+    /// spdx.canonicalize();
+    ///
+    /// assert_eq!(spdx.package_information[0].package_spdx_identifier, "SPDXRef-1");
+    /// ```
+    pub fn canonicalize(&mut self) {
+        info!("Canonicalizing SPDX.");
+
+        canonicalize_document(&mut self.document_creation_information);
+
+        for package in &mut self.package_information {
+            canonicalize_package(package);
+        }
+        self.package_information
+            .sort_by(|a, b| a.package_spdx_identifier.cmp(&b.package_spdx_identifier));
+
+        for license in &mut self.other_licensing_information_detected {
+            canonicalize_license(license);
+        }
+        self.other_licensing_information_detected
+            .sort_by(|a, b| a.license_identifier.cmp(&b.license_identifier));
+
+        for file in &mut self.file_information {
+            canonicalize_file(file);
+        }
+        self.file_information
+            .sort_by(|a, b| a.file_spdx_identifier.cmp(&b.file_spdx_identifier));
+
+        for snippet in &mut self.snippet_information {
+            canonicalize_snippet(snippet);
+        }
+        self.snippet_information
+            .sort_by(|a, b| a.snippet_spdx_identifier.cmp(&b.snippet_spdx_identifier));
+
+        self.relationships
+            .sort_by(|a, b| relationship_key(a).cmp(&relationship_key(b)));
+        self.relationships.dedup();
+
+        sort_annotations(&mut self.annotations);
+    }
+}
+
+fn canonicalize_document(document: &mut DocumentCreationInformation) {
+    document.external_document_references.sort();
+    document.document_describes.sort();
+    document.document_describes.dedup();
+    document.creation_info.creators.sort();
+}
+
+fn canonicalize_package(package: &mut PackageInformation) {
+    if let Some(verification_code) = &mut package.package_verification_code {
+        verification_code.excludes.sort();
+    }
+    package.package_checksum.sort();
+    package.all_licenses_information_from_files.sort();
+    package.external_reference.sort();
+    package.package_attribution_text.sort();
+    package.files.sort();
+    package.files.dedup();
+    sort_annotations(&mut package.annotations);
+}
+
+fn canonicalize_license(license: &mut OtherLicensingInformationDetected) {
+    license.license_cross_reference.sort();
+}
+
+fn canonicalize_file(file: &mut FileInformation) {
+    file.file_type.sort();
+    file.file_checksum.sort();
+    file.license_information_in_file
+        .sort_by_cached_key(ToString::to_string);
+    file.file_contributor.sort();
+    if let Some(attribution_text) = &mut file.file_attribution_text {
+        attribution_text.sort();
+    }
+}
+
+fn canonicalize_snippet(snippet: &mut Snippet) {
+    snippet.license_information_in_snippet.sort();
+}
+
+fn relationship_key(relationship: &Relationship) -> (&str, &str, &str, Option<&str>) {
+    (
+        &relationship.spdx_element_id,
+        relationship.relationship_type.as_ref(),
+        &relationship.related_spdx_element,
+        relationship.comment.as_deref(),
+    )
+}
+
+fn sort_annotations(annotations: &mut [Annotation]) {
+    annotations.sort_by(|a, b| {
+        (
+            &a.spdx_identifier_reference,
+            a.annotation_date,
+            &a.annotator,
+            a.annotation_type,
+            &a.annotation_comment,
+        )
+            .cmp(&(
+                &b.spdx_identifier_reference,
+                b.annotation_date,
+                &b.annotator,
+                b.annotation_type,
+                &b.annotation_comment,
+            ))
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::parsers::spdx_from_tag_value;
+
+    use super::*;
+
+    #[test]
+    fn order_of_elements_does_not_change_canonical_output() {
+        let input = read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap();
+        let mut spdx: SPDX = serde_json::from_str(&input).unwrap();
+        let mut shuffled = spdx.clone();
+        shuffled.package_information.reverse();
+        shuffled.file_information.rotate_left(2);
+        shuffled.relationships.reverse();
+        shuffled.relationships.push(spdx.relationships[0].clone());
+        shuffled.annotations.reverse();
+        for package in &mut shuffled.package_information {
+            package.package_checksum.reverse();
+            package.external_reference.reverse();
+        }
+        shuffled
+            .document_creation_information
+            .creation_info
+            .creators
+            .reverse();
+
+        spdx.canonicalize();
+        shuffled.canonicalize();
+        assert_eq!(
+            serde_json::to_string(&spdx).unwrap(),
+            serde_json::to_string(&shuffled).unwrap()
+        );
+
+        let mut again = spdx.clone();
+        again.canonicalize();
+        assert_eq!(again, spdx);
+    }
+
+    #[test]
+    fn tag_value_relationships_are_sorted() {
+        let input = read_to_string("tests/data/SPDXTagExample-v2.2.spdx").unwrap();
+        let mut spdx = spdx_from_tag_value(&input).unwrap();
+        spdx.canonicalize();
+
+        let keys = spdx
+            .relationships
+            .iter()
+            .map(relationship_key)
+            .collect::<Vec<_>>();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            spdx.package_information[0].package_spdx_identifier,
+            "SPDXRef-Package"
+        );
+    }
+}
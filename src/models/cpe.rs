@@ -0,0 +1,779 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use crate::{error::SpdxError, version::compare_versions};
+
+/// A [Common Platform Enumeration](https://nvd.nist.gov/products/cpe) name.
+///
+/// The name is stored as a well-formed name (WFN) as defined in
+/// [NISTIR 7695](https://nvlpubs.nist.gov/nistpubs/Legacy/IR/nistir7695.pdf). It can be parsed
+/// from and bound to both the CPE 2.2 URI binding (`cpe:/a:vendor:product:1.0`) and the CPE 2.3
+/// formatted string binding (`cpe:2.3:a:vendor:product:1.0:*:*:*:*:*:*:*`), which are the
+/// locators of SPDX's `cpe22Type` and `cpe23Type` external references.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Cpe {
+    pub part: CpeComponent,
+    pub vendor: CpeComponent,
+    pub product: CpeComponent,
+    pub version: CpeComponent,
+    pub update: CpeComponent,
+    pub edition: CpeComponent,
+    pub language: CpeComponent,
+    pub sw_edition: CpeComponent,
+    pub target_sw: CpeComponent,
+    pub target_hw: CpeComponent,
+    pub other: CpeComponent,
+}
+
+/// Value of a single attribute of a [`Cpe`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum CpeComponent {
+    /// Logical value ANY, bound as `*` or as an empty URI component.
+    #[default]
+    Any,
+
+    /// Logical value NA, bound as `-`.
+    NotApplicable,
+
+    /// Attribute value in WFN form. Special characters are quoted with a backslash, unquoted `*`
+    /// and `?` are wildcards.
+    Value(String),
+}
+
+impl CpeComponent {
+    /// Create a component from an unquoted value, e.g. `"1.0.2"`. Special characters are quoted, so
+    /// the value never contains wildcards.
+    pub fn new(value: &str) -> Self {
+        let mut quoted = String::with_capacity(value.len());
+        for character in value.chars() {
+            if !is_unreserved(character) {
+                quoted.push('\\');
+            }
+            quoted.push(character);
+        }
+        Self::Value(quoted)
+    }
+
+    /// Get the value without quoting, or `None` for the logical values.
+    pub fn unquoted(&self) -> Option<String> {
+        match self {
+            Self::Value(value) => {
+                let mut unquoted = String::with_capacity(value.len());
+                let mut characters = value.chars();
+                while let Some(character) = characters.next() {
+                    if character == '\\' {
+                        if let Some(quoted) = characters.next() {
+                            unquoted.push(quoted);
+                        }
+                    } else {
+                        unquoted.push(character);
+                    }
+                }
+                Some(unquoted)
+            }
+            Self::Any | Self::NotApplicable => None,
+        }
+    }
+
+    fn has_wildcards(&self) -> bool {
+        match self {
+            Self::Value(value) => {
+                let mut characters = value.chars();
+                while let Some(character) = characters.next() {
+                    match character {
+                        '\\' => {
+                            characters.next();
+                        }
+                        '*' | '?' => return true,
+                        _ => {}
+                    }
+                }
+                false
+            }
+            Self::Any | Self::NotApplicable => false,
+        }
+    }
+}
+
+/// Relation between two attribute values, as defined in
+/// [NISTIR 7696](https://nvlpubs.nist.gov/nistpubs/Legacy/IR/nistir7696.pdf).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpeRelation {
+    /// The source value is a superset of the target value.
+    Superset,
+
+    /// The source value is a subset of the target value.
+    Subset,
+
+    /// The values are equal.
+    Equal,
+
+    /// The values have nothing in common.
+    Disjoint,
+
+    /// The target contains wildcards, so the relation is undefined.
+    Undefined,
+}
+
+impl Cpe {
+    fn components(&self) -> [&CpeComponent; 11] {
+        [
+            &self.part,
+            &self.vendor,
+            &self.product,
+            &self.version,
+            &self.update,
+            &self.edition,
+            &self.language,
+            &self.sw_edition,
+            &self.target_sw,
+            &self.target_hw,
+            &self.other,
+        ]
+    }
+
+    fn components_mut(&mut self) -> [&mut CpeComponent; 11] {
+        [
+            &mut self.part,
+            &mut self.vendor,
+            &mut self.product,
+            &mut self.version,
+            &mut self.update,
+            &mut self.edition,
+            &mut self.language,
+            &mut self.sw_edition,
+            &mut self.target_sw,
+            &mut self.target_hw,
+            &mut self.other,
+        ]
+    }
+
+    /// Parse a CPE 2.3 formatted string, e.g. `cpe:2.3:a:vendor:product:1.0:*:*:*:*:*:*:*`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Cpe`] if the input is not a valid formatted string.
+    pub fn from_formatted_string(input: &str) -> Result<Self, SpdxError> {
+        let rest = input
+            .strip_prefix("cpe:2.3:")
+            .ok_or_else(|| SpdxError::Cpe(format!("{input} doesn't start with cpe:2.3:")))?;
+
+        let fields = split_formatted_string(rest);
+        if fields.len() != 11 {
+            return Err(SpdxError::Cpe(format!(
+                "{input} has {} attributes instead of 11",
+                fields.len()
+            )));
+        }
+
+        let mut cpe = Self::default();
+        for (component, field) in IntoIterator::into_iter(cpe.components_mut()).zip(fields) {
+            *component = unbind_formatted_string_value(field)
+                .map_err(|error| SpdxError::Cpe(format!("{input}: {error}")))?;
+        }
+        cpe.validate_part()?;
+
+        Ok(cpe)
+    }
+
+    /// Parse a CPE 2.2 URI, e.g. `cpe:/a:vendor:product:1.0`. The packed edition component of CPE
+    /// 2.3 URIs is supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Cpe`] if the input is not a valid URI.
+    pub fn from_uri(input: &str) -> Result<Self, SpdxError> {
+        let rest = input
+            .strip_prefix("cpe:/")
+            .or_else(|| input.strip_prefix("CPE:/"))
+            .ok_or_else(|| SpdxError::Cpe(format!("{input} doesn't start with cpe:/")))?;
+
+        let fields: Vec<&str> = rest.split(':').collect();
+        if fields.len() > 7 {
+            return Err(SpdxError::Cpe(format!(
+                "{input} has {} components, at most 7 are allowed",
+                fields.len()
+            )));
+        }
+
+        let mut cpe = Self::default();
+        for (index, field) in fields.into_iter().enumerate() {
+            let decode = |value| {
+                decode_uri_value(value).map_err(|err| SpdxError::Cpe(format!("{input}: {err}")))
+            };
+            if index == 5 && field.starts_with('~') {
+                let packed: Vec<&str> = field.split('~').collect();
+                if packed.len() != 6 {
+                    return Err(SpdxError::Cpe(format!(
+                        "{input}: packed edition {field} should have 5 components"
+                    )));
+                }
+                cpe.edition = decode(packed[1])?;
+                cpe.sw_edition = decode(packed[2])?;
+                cpe.target_sw = decode(packed[3])?;
+                cpe.target_hw = decode(packed[4])?;
+                cpe.other = decode(packed[5])?;
+            } else {
+                *cpe.components_mut()[index] = decode(field)?;
+            }
+        }
+        cpe.validate_part()?;
+
+        Ok(cpe)
+    }
+
+    fn validate_part(&self) -> Result<(), SpdxError> {
+        match &self.part {
+            CpeComponent::Any => Ok(()),
+            CpeComponent::Value(value) if matches!(value.as_str(), "a" | "o" | "h") => Ok(()),
+            part => Err(SpdxError::Cpe(format!("invalid part {part:?}"))),
+        }
+    }
+
+    /// Bind the name to a CPE 2.3 formatted string.
+    pub fn to_formatted_string(&self) -> String {
+        let mut bound = String::from("cpe:2.3");
+        for component in self.components() {
+            bound.push(':');
+            bound.push_str(&bind_formatted_string_value(component));
+        }
+        bound
+    }
+
+    /// Bind the name to a URI. Attributes not available in CPE 2.2 are packed into the edition
+    /// component.
+    pub fn to_uri(&self) -> String {
+        let extended = [
+            &self.sw_edition,
+            &self.target_sw,
+            &self.target_hw,
+            &self.other,
+        ];
+        let edition = if extended.iter().all(|c| **c == CpeComponent::Any) {
+            bind_uri_value(&self.edition)
+        } else {
+            let mut packed = String::new();
+            for component in std::iter::once(&self.edition).chain(extended) {
+                packed.push('~');
+                packed.push_str(&bind_uri_value(component));
+            }
+            packed
+        };
+
+        let mut bound = String::from("cpe:/");
+        bound.push_str(
+            &[
+                bind_uri_value(&self.part),
+                bind_uri_value(&self.vendor),
+                bind_uri_value(&self.product),
+                bind_uri_value(&self.version),
+                bind_uri_value(&self.update),
+                edition,
+                bind_uri_value(&self.language),
+            ]
+            .join(":"),
+        );
+        bound.trim_end_matches(':').to_string()
+    }
+
+    /// Compare the attributes of this name (the source) with the target name.
+    pub fn compare(&self, target: &Self) -> [CpeRelation; 11] {
+        let mut relations = [CpeRelation::Equal; 11];
+        for (relation, (source, target)) in relations
+            .iter_mut()
+            .zip(self.components().iter().zip(target.components()))
+        {
+            *relation = compare_components(source, target);
+        }
+        relations
+    }
+
+    /// Check if this name matches the target name, i.e. if the source is a superset of or equal to
+    /// the target. Wildcards are only allowed in the source.
+    pub fn matches(&self, target: &Self) -> bool {
+        self.compare(target)
+            .iter()
+            .all(|relation| matches!(relation, CpeRelation::Superset | CpeRelation::Equal))
+    }
+
+    /// Check if the names are equal.
+    pub fn is_equal(&self, target: &Self) -> bool {
+        self.compare(target)
+            .iter()
+            .all(|relation| *relation == CpeRelation::Equal)
+    }
+
+    /// Check if the names are disjoint.
+    pub fn is_disjoint(&self, target: &Self) -> bool {
+        self.compare(target).contains(&CpeRelation::Disjoint)
+    }
+}
+
+impl FromStr for Cpe {
+    type Err = SpdxError;
+
+    /// Parse either a formatted string or a URI.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("cpe:2.3:") {
+            Self::from_formatted_string(s)
+        } else {
+            Self::from_uri(s)
+        }
+    }
+}
+
+impl Display for Cpe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_formatted_string())
+    }
+}
+
+/// Match criteria for a CPE with an optional version range, as used by vulnerability feeds like the
+/// NVD.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CpeMatch {
+    /// Name to match against, may contain wildcards.
+    pub criteria: Cpe,
+    pub version_start_including: Option<String>,
+    pub version_start_excluding: Option<String>,
+    pub version_end_including: Option<String>,
+    pub version_end_excluding: Option<String>,
+}
+
+impl CpeMatch {
+    /// Create match criteria without a version range.
+    pub fn new(criteria: Cpe) -> Self {
+        Self {
+            criteria,
+            ..Self::default()
+        }
+    }
+
+    fn has_range(&self) -> bool {
+        self.version_start_including.is_some()
+            || self.version_start_excluding.is_some()
+            || self.version_end_including.is_some()
+            || self.version_end_excluding.is_some()
+    }
+
+    /// Check if the target name matches the criteria and its version is in the range.
+    pub fn matches(&self, target: &Cpe) -> bool {
+        if !self.has_range() {
+            return self.criteria.matches(target);
+        }
+
+        // The version is checked against the range instead of the criteria.
+        let criteria = Cpe {
+            version: CpeComponent::Any,
+            ..self.criteria.clone()
+        };
+        if !criteria.matches(target) {
+            return false;
+        }
+
+        let Some(version) = target.version.unquoted() else {
+            return false;
+        };
+
+        let in_range = |bound: &Option<String>, accepted: &[Ordering]| {
+            bound
+                .as_ref()
+                .is_none_or(|bound| accepted.contains(&compare_versions(&version, bound)))
+        };
+
+        in_range(
+            &self.version_start_including,
+            &[Ordering::Greater, Ordering::Equal],
+        ) && in_range(&self.version_start_excluding, &[Ordering::Greater])
+            && in_range(
+                &self.version_end_including,
+                &[Ordering::Less, Ordering::Equal],
+            )
+            && in_range(&self.version_end_excluding, &[Ordering::Less])
+    }
+}
+
+fn is_unreserved(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+/// Split a formatted string on colons that are not quoted.
+fn split_formatted_string(input: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (index, character) in input.char_indices() {
+        if quoted {
+            quoted = false;
+        } else if character == '\\' {
+            quoted = true;
+        } else if character == ':' {
+            fields.push(&input[start..index]);
+            start = index + 1;
+        }
+    }
+    fields.push(&input[start..]);
+    fields
+}
+
+fn unbind_formatted_string_value(value: &str) -> Result<CpeComponent, String> {
+    match value {
+        "*" => return Ok(CpeComponent::Any),
+        "-" => return Ok(CpeComponent::NotApplicable),
+        "" => return Err("empty attribute".to_string()),
+        _ => {}
+    }
+
+    let characters: Vec<char> = value.chars().collect();
+    let mut result = String::with_capacity(value.len());
+    let mut index = 0;
+    while index < characters.len() {
+        let character = characters[index];
+        match character {
+            '\\' => {
+                let quoted = characters
+                    .get(index + 1)
+                    .ok_or_else(|| format!("{value} ends with an escape character"))?;
+                result.push('\\');
+                result.push(*quoted);
+                index += 2;
+                continue;
+            }
+            '*' => {
+                if index != 0 && index != characters.len() - 1 {
+                    return Err(format!("{value} has an embedded asterisk"));
+                }
+                result.push('*');
+            }
+            '?' => {
+                let leading = characters[..=index].iter().all(|c| *c == '?');
+                let trailing = characters[index..].iter().all(|c| *c == '?')
+                    && (index == 0 || characters[index - 1] != '\\');
+                if !leading && !trailing {
+                    return Err(format!("{value} has an embedded question mark"));
+                }
+                result.push('?');
+            }
+            character if is_unreserved(character) => result.push(character),
+            character => {
+                result.push('\\');
+                result.push(character);
+            }
+        }
+        index += 1;
+    }
+
+    Ok(CpeComponent::Value(result.to_lowercase()))
+}
+
+fn bind_formatted_string_value(component: &CpeComponent) -> String {
+    match component {
+        CpeComponent::Any => "*".to_string(),
+        CpeComponent::NotApplicable => "-".to_string(),
+        CpeComponent::Value(value) => {
+            let mut bound = String::with_capacity(value.len());
+            let mut characters = value.chars();
+            while let Some(character) = characters.next() {
+                if character == '\\' {
+                    match characters.next() {
+                        Some(quoted @ ('.' | '-' | '_')) => bound.push(quoted),
+                        Some(quoted) => {
+                            bound.push('\\');
+                            bound.push(quoted);
+                        }
+                        None => {}
+                    }
+                } else {
+                    bound.push(character);
+                }
+            }
+            bound
+        }
+    }
+}
+
+fn decode_uri_value(value: &str) -> Result<CpeComponent, String> {
+    match value {
+        "" => return Ok(CpeComponent::Any),
+        "-" => return Ok(CpeComponent::NotApplicable),
+        _ => {}
+    }
+
+    let value = value.to_lowercase();
+    let bytes = value.as_bytes();
+    let mut result = String::with_capacity(value.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let character = bytes[index] as char;
+        if character == '%' {
+            let encoded = value
+                .get(index..index + 3)
+                .ok_or_else(|| format!("{value} has a truncated percent-encoding"))?;
+            match encoded {
+                "%01" => result.push('?'),
+                "%02" => result.push('*'),
+                _ => {
+                    let decoded = u8::from_str_radix(&encoded[1..], 16)
+                        .map_err(|_| format!("{value} has an invalid percent-encoding"))?;
+                    let decoded = decoded as char;
+                    if !is_unreserved(decoded) {
+                        result.push('\\');
+                    }
+                    result.push(decoded);
+                }
+            }
+            index += 3;
+        } else {
+            if !is_unreserved(character) {
+                result.push('\\');
+            }
+            result.push(character);
+            index += 1;
+        }
+    }
+
+    Ok(CpeComponent::Value(result))
+}
+
+fn bind_uri_value(component: &CpeComponent) -> String {
+    match component {
+        CpeComponent::Any => String::new(),
+        CpeComponent::NotApplicable => "-".to_string(),
+        CpeComponent::Value(value) => {
+            let mut bound = String::with_capacity(value.len());
+            let mut characters = value.chars();
+            while let Some(character) = characters.next() {
+                match character {
+                    '\\' => match characters.next() {
+                        Some(quoted @ ('.' | '-')) => bound.push(quoted),
+                        Some(quoted) => {
+                            let _ = write!(bound, "%{:02x}", u32::from(quoted));
+                        }
+                        None => {}
+                    },
+                    '?' => bound.push_str("%01"),
+                    '*' => bound.push_str("%02"),
+                    character => bound.push(character),
+                }
+            }
+            bound
+        }
+    }
+}
+
+fn compare_components(source: &CpeComponent, target: &CpeComponent) -> CpeRelation {
+    use CpeComponent::{Any, NotApplicable, Value};
+
+    match (source, target) {
+        (Any, Any) | (NotApplicable, NotApplicable) => CpeRelation::Equal,
+        (Any, _) => CpeRelation::Superset,
+        (NotApplicable | Value(_), Any) => CpeRelation::Subset,
+        (NotApplicable, Value(_)) | (Value(_), NotApplicable) => CpeRelation::Disjoint,
+        (Value(_), Value(_)) if target.has_wildcards() => CpeRelation::Undefined,
+        (Value(source_value), Value(target_value)) => {
+            let source_value = source_value.to_lowercase();
+            let target_value = target_value.to_lowercase();
+            if source_value == target_value {
+                CpeRelation::Equal
+            } else if source.has_wildcards()
+                && wildcard_match(
+                    &source_value.chars().collect::<Vec<_>>(),
+                    &target_value.chars().collect::<Vec<_>>(),
+                )
+            {
+                CpeRelation::Superset
+            } else {
+                CpeRelation::Disjoint
+            }
+        }
+    }
+}
+
+/// Match a quoted WFN target value against a source pattern with unquoted `*` and `?` wildcards.
+/// A quoted character in the target counts as a single character.
+fn wildcard_match(pattern: &[char], target: &[char]) -> bool {
+    let target_length = |target: &[char]| if target.first() == Some(&'\\') { 2 } else { 1 };
+
+    match pattern.first() {
+        None => target.is_empty(),
+        Some('*') => {
+            let mut rest = target;
+            loop {
+                if wildcard_match(&pattern[1..], rest) {
+                    return true;
+                }
+                if rest.is_empty() {
+                    return false;
+                }
+                rest = &rest[target_length(rest).min(rest.len())..];
+            }
+        }
+        Some('?') => {
+            !target.is_empty()
+                && wildcard_match(
+                    &pattern[1..],
+                    &target[target_length(target).min(target.len())..],
+                )
+        }
+        Some('\\') => {
+            pattern.len() > 1
+                && target.len() > 1
+                && target[0] == '\\'
+                && pattern[1] == target[1]
+                && wildcard_match(&pattern[2..], &target[2..])
+        }
+        Some(character) => {
+            target.first() == Some(character) && wildcard_match(&pattern[1..], &target[1..])
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::models::SPDX;
+
+    use super::*;
+
+    #[test]
+    fn formatted_string_is_parsed() {
+        let cpe = Cpe::from_formatted_string(
+            "cpe:2.3:a:pivotal_software:spring_framework:4.1.0:*:*:*:*:*:*:*",
+        )
+        .unwrap();
+        assert_eq!(cpe.part, CpeComponent::Value("a".to_string()));
+        assert_eq!(cpe.vendor, CpeComponent::new("pivotal_software"));
+        assert_eq!(cpe.product, CpeComponent::new("spring_framework"));
+        assert_eq!(cpe.version, CpeComponent::new("4.1.0"));
+        assert_eq!(cpe.version.unquoted(), Some("4.1.0".to_string()));
+        assert_eq!(cpe.update, CpeComponent::Any);
+    }
+
+    #[test]
+    fn formatted_string_round_trips() {
+        for input in [
+            "cpe:2.3:a:microsoft:internet_explorer:8.0.6001:beta:*:*:*:*:*:*",
+            "cpe:2.3:a:hp:insight_diagnostics:7.4.0.1570:-:*:*:online:win2003:x64:*",
+            "cpe:2.3:a:foo\\:bar:big\\$money_2010:*:*:*:*:special:ipod_touch:80gb:*",
+        ] {
+            assert_eq!(Cpe::from_str(input).unwrap().to_formatted_string(), input);
+        }
+    }
+
+    #[test]
+    fn uri_is_parsed_and_bound() {
+        let cpe = Cpe::from_uri("cpe:/a:microsoft:internet_explorer:8.0.6001:beta").unwrap();
+        assert_eq!(cpe.version, CpeComponent::new("8.0.6001"));
+        assert_eq!(cpe.update, CpeComponent::new("beta"));
+        assert_eq!(
+            cpe.to_formatted_string(),
+            "cpe:2.3:a:microsoft:internet_explorer:8.0.6001:beta:*:*:*:*:*:*"
+        );
+        assert_eq!(
+            cpe.to_uri(),
+            "cpe:/a:microsoft:internet_explorer:8.0.6001:beta"
+        );
+
+        let packed =
+            Cpe::from_uri("cpe:/a:hp:insight_diagnostics:7.4.0.1570:-:~~online~win2003~x64~")
+                .unwrap();
+        assert_eq!(packed.target_sw, CpeComponent::new("win2003"));
+        assert_eq!(
+            packed.to_uri(),
+            "cpe:/a:hp:insight_diagnostics:7.4.0.1570:-:~~online~win2003~x64~"
+        );
+    }
+
+    #[test]
+    fn uri_percent_encoding_is_decoded() {
+        let cpe = Cpe::from_uri("cpe:/a:foo%5cbar:big%24money_2010%02").unwrap();
+        assert_eq!(cpe.vendor, CpeComponent::new("foo\\bar"));
+        assert_eq!(
+            cpe.product,
+            CpeComponent::Value("big\\$money_2010*".to_string())
+        );
+        assert_eq!(cpe.to_uri(), "cpe:/a:foo%5cbar:big%24money_2010%02");
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        assert!(Cpe::from_str("cpe:2.3:a:vendor:product").is_err());
+        assert!(Cpe::from_str("cpe:2.3:x:vendor:product:1:*:*:*:*:*:*:*").is_err());
+        assert!(Cpe::from_str("cpe:2.3:a:ven*dor:product:1:*:*:*:*:*:*:*").is_err());
+        assert!(Cpe::from_str("pkg:cargo/serde@1.0.0").is_err());
+    }
+
+    #[test]
+    fn names_are_matched() {
+        let target =
+            Cpe::from_str("cpe:2.3:a:microsoft:internet_explorer:8.0.6001:beta:*:*:*:*:*:*")
+                .unwrap();
+
+        let any_version = Cpe::from_str("cpe:/a:microsoft:internet_explorer").unwrap();
+        assert!(any_version.matches(&target));
+        assert!(!target.matches(&any_version));
+
+        let wildcard =
+            Cpe::from_str("cpe:2.3:a:microsoft:internet_explorer:8.*:*:*:*:*:*:*:*").unwrap();
+        assert!(wildcard.matches(&target));
+
+        let single = Cpe::from_str("cpe:2.3:a:microsoft:internet_?xplorer:*:*:*:*:*:*:*:*");
+        assert!(single.is_err());
+
+        let other_vendor = Cpe::from_str("cpe:2.3:a:mozilla:*:*:*:*:*:*:*:*:*").unwrap();
+        assert!(!other_vendor.matches(&target));
+        assert!(other_vendor.is_disjoint(&target));
+
+        let not_applicable =
+            Cpe::from_str("cpe:2.3:a:microsoft:internet_explorer:8.0.6001:-:*:*:*:*:*:*").unwrap();
+        assert!(!not_applicable.matches(&target));
+    }
+
+    #[test]
+    fn version_ranges_are_matched() {
+        let criteria = CpeMatch {
+            criteria: Cpe::from_str("cpe:2.3:a:pivotal_software:spring_framework:*:*:*:*:*:*:*:*")
+                .unwrap(),
+            version_start_including: Some("4.0.0".to_string()),
+            version_end_excluding: Some("4.1.10".to_string()),
+            ..CpeMatch::default()
+        };
+
+        let affected =
+            Cpe::from_str("cpe:2.3:a:pivotal_software:spring_framework:4.1.0:*:*:*:*:*:*:*")
+                .unwrap();
+        let fixed =
+            Cpe::from_str("cpe:2.3:a:pivotal_software:spring_framework:4.1.10:*:*:*:*:*:*:*")
+                .unwrap();
+        let unrelated =
+            Cpe::from_str("cpe:2.3:a:pivotal_software:spring_boot:4.1.0:*:*:*:*:*:*:*").unwrap();
+
+        assert!(criteria.matches(&affected));
+        assert!(!criteria.matches(&fixed));
+        assert!(!criteria.matches(&unrelated));
+    }
+
+    #[test]
+    fn cpes_are_read_from_external_references() {
+        let spdx: SPDX = serde_json::from_str(
+            &read_to_string("tests/data/SPDXJSONExample-v2.2.spdx.json").unwrap(),
+        )
+        .unwrap();
+        let cpes = spdx.package_information[0].cpes().unwrap();
+        assert_eq!(
+            cpes,
+            vec![
+                Cpe::from_str("cpe:2.3:a:pivotal_software:spring_framework:4.1.0:*:*:*:*:*:*:*")
+                    .unwrap()
+            ]
+        );
+    }
+}
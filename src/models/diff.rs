@@ -0,0 +1,306 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{collections::HashSet, fmt};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::{Relationship, SPDX};
+
+/// Differences between two documents, created with [`SPDX::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDiff {
+    /// Changed fields of the document creation information.
+    pub document: Vec<FieldChange>,
+
+    pub packages: ElementChanges,
+    pub files: ElementChanges,
+    pub snippets: ElementChanges,
+
+    /// Other licensing information, matched by license identifier.
+    pub other_licenses: ElementChanges,
+
+    pub added_relationships: Vec<Relationship>,
+    pub removed_relationships: Vec<Relationship>,
+}
+
+/// Added, removed and changed elements of one kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementChanges {
+    /// SPDX IDs of the elements only in the new document.
+    pub added: Vec<String>,
+
+    /// SPDX IDs of the elements only in the old document.
+    pub removed: Vec<String>,
+
+    pub changed: Vec<ElementChange>,
+}
+
+/// Changed fields of an element in both documents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementChange {
+    pub spdx_id: String,
+    pub fields: Vec<FieldChange>,
+}
+
+/// A field with different values in the documents. Fields are named as in JSON documents, and a
+/// missing field has the value `null`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+impl DocumentDiff {
+    /// Returns `true` if the documents are the same.
+    pub fn is_empty(&self) -> bool {
+        self.document.is_empty()
+            && self.packages.is_empty()
+            && self.files.is_empty()
+            && self.snippets.is_empty()
+            && self.other_licenses.is_empty()
+            && self.added_relationships.is_empty()
+            && self.removed_relationships.is_empty()
+    }
+}
+
+impl ElementChanges {
+    /// Returns `true` if no element was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Compare the elements by their IDs.
+    fn new<T: Serialize>(old: &[T], new: &[T], id: impl Fn(&T) -> &str) -> Self {
+        let mut changes = Self::default();
+        for old_element in old {
+            let old_id = id(old_element);
+            match new.iter().find(|new_element| id(new_element) == old_id) {
+                Some(new_element) => {
+                    let fields = field_changes(old_element, new_element);
+                    if !fields.is_empty() {
+                        changes.changed.push(ElementChange {
+                            spdx_id: old_id.to_string(),
+                            fields,
+                        });
+                    }
+                }
+                None => changes.removed.push(old_id.to_string()),
+            }
+        }
+        for new_element in new {
+            let new_id = id(new_element);
+            if !old.iter().any(|old_element| id(old_element) == new_id) {
+                changes.added.push(new_id.to_string());
+            }
+        }
+        changes
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, kind: &str) -> fmt::Result {
+        for id in &self.added {
+            writeln!(f, "+ {kind} {id}")?;
+        }
+        for id in &self.removed {
+            writeln!(f, "- {kind} {id}")?;
+        }
+        for change in &self.changed {
+            writeln!(f, "~ {kind} {}", change.spdx_id)?;
+            for field in &change.fields {
+                writeln!(f, "    {field}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SPDX {
+    /// Compare the document to a newer version of it.
+    ///
+    /// Packages, files and snippets are matched by SPDX ID and compared field by field. The
+    /// order of the elements and relationships doesn't matter, but the order of the values of a
+    /// field does.
+    pub fn diff(&self, new: &Self) -> DocumentDiff {
+        let old_relationships = self.relationships.iter().collect::<HashSet<_>>();
+        let new_relationships = new.relationships.iter().collect::<HashSet<_>>();
+
+        DocumentDiff {
+            document: field_changes(
+                &self.document_creation_information,
+                &new.document_creation_information,
+            ),
+            packages: ElementChanges::new(
+                &self.package_information,
+                &new.package_information,
+                |package| &package.package_spdx_identifier,
+            ),
+            files: ElementChanges::new(&self.file_information, &new.file_information, |file| {
+                &file.file_spdx_identifier
+            }),
+            snippets: ElementChanges::new(
+                &self.snippet_information,
+                &new.snippet_information,
+                |snippet| &snippet.snippet_spdx_identifier,
+            ),
+            other_licenses: ElementChanges::new(
+                &self.other_licensing_information_detected,
+                &new.other_licensing_information_detected,
+                |license| &license.license_identifier,
+            ),
+            added_relationships: new
+                .relationships
+                .iter()
+                .filter(|relationship| !old_relationships.contains(relationship))
+                .cloned()
+                .collect(),
+            removed_relationships: self
+                .relationships
+                .iter()
+                .filter(|relationship| !new_relationships.contains(relationship))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.old, self.new)
+    }
+}
+
+/// Lists the differences one per line: `+` for added, `-` for removed and `~` for changed
+/// elements.
+impl fmt::Display for DocumentDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for field in &self.document {
+            writeln!(f, "~ document {field}")?;
+        }
+        self.packages.write(f, "package")?;
+        self.files.write(f, "file")?;
+        self.snippets.write(f, "snippet")?;
+        self.other_licenses.write(f, "license")?;
+        for relationship in &self.added_relationships {
+            writeln!(f, "+ relationship {}", relationship_line(relationship))?;
+        }
+        for relationship in &self.removed_relationships {
+            writeln!(f, "- relationship {}", relationship_line(relationship))?;
+        }
+        Ok(())
+    }
+}
+
+fn relationship_line(relationship: &Relationship) -> String {
+    format!(
+        "{} {} {}",
+        relationship.spdx_element_id,
+        relationship.relationship_type,
+        relationship.related_spdx_element
+    )
+}
+
+/// Compare the JSON representations of two values field by field.
+fn field_changes<T: Serialize>(old: &T, new: &T) -> Vec<FieldChange> {
+    let (old, new) = (json_object(old), json_object(new));
+    old.keys()
+        .chain(new.keys().filter(|field| !old.contains_key(*field)))
+        .filter_map(|field| {
+            let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+            let new_value = new.get(field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then(|| FieldChange {
+                field: field.clone(),
+                old: old_value,
+                new: new_value,
+            })
+        })
+        .collect()
+}
+
+fn json_object<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use serde_json::json;
+
+    use crate::models::RelationshipType;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn same_document_has_no_differences() {
+        let spdx = example();
+        let diff = spdx.diff(&spdx.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn differences_are_found() {
+        let old = example();
+        let mut new = old.clone();
+        new.package_information[0].package_version = Some("2.12".to_string());
+        new.package_information
+            .retain(|package| package.package_name != "Saxon");
+        new.file_information[0].file_spdx_identifier = "SPDXRef-NewFile".to_string();
+        new.relationships.push(Relationship::new(
+            "SPDXRef-Package",
+            "SPDXRef-NewFile",
+            RelationshipType::Contains,
+            None,
+        ));
+        new.document_creation_information.document_name = "new".to_string();
+
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.document,
+            vec![FieldChange {
+                field: "name".to_string(),
+                old: json!("SPDX-Tools-v2.0"),
+                new: json!("new"),
+            }]
+        );
+        assert_eq!(diff.packages.removed, vec!["SPDXRef-Saxon"]);
+        assert_eq!(
+            diff.packages.changed,
+            vec![ElementChange {
+                spdx_id: "SPDXRef-Package".to_string(),
+                fields: vec![FieldChange {
+                    field: "versionInfo".to_string(),
+                    old: json!("2.11.1"),
+                    new: json!("2.12"),
+                }]
+            }]
+        );
+        assert_eq!(diff.files.added, vec!["SPDXRef-NewFile"]);
+        assert_eq!(diff.files.removed.len(), 1);
+        assert_eq!(diff.added_relationships.len(), 1);
+        assert!(diff.removed_relationships.is_empty());
+
+        let text = diff.to_string();
+        assert!(text.contains("- package SPDXRef-Saxon\n"));
+        assert!(
+            text.contains("~ package SPDXRef-Package\n    versionInfo: \"2.11.1\" -> \"2.12\"\n")
+        );
+        assert!(text.contains("+ relationship SPDXRef-Package CONTAINS SPDXRef-NewFile\n"));
+    }
+}
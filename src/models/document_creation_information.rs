@@ -2,8 +2,11 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use super::Checksum;
 
@@ -49,6 +52,10 @@ pub struct DocumentCreationInformation {
     /// <https://github.com/spdx/spdx-spec/issues/395>
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub document_describes: Vec<String>,
+
+    /// Fields of the document that are not defined by the spec.
+    #[serde(flatten, skip_deserializing)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 impl Default for DocumentCreationInformation {
@@ -65,6 +72,7 @@ impl Default for DocumentCreationInformation {
             document_comment: None,
             creation_info: CreationInfo::default(),
             document_describes: Vec::new(),
+            extensions: BTreeMap::new(),
         }
     }
 }
@@ -104,7 +112,7 @@ impl Default for CreationInfo {
 }
 
 /// <https://spdx.github.io/spdx-spec/2-document-creation-information/#26-external-document-references>
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ExternalDocumentReference {
     /// Unique ID string of the reference.
     #[serde(rename = "externalDocumentId")]
@@ -2,7 +2,10 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use spdx_expression::SpdxExpression;
 
 use super::{Algorithm, Checksum};
@@ -83,7 +86,10 @@ pub struct FileInformation {
     /// <https://spdx.github.io/spdx-spec/4-file-information/#415-file-attribution-text>
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub file_attribution_text: Option<Vec<String>>,
-    // TODO: Snippet Information.
+
+    /// Fields of the file that are not defined by the spec.
+    #[serde(flatten, skip_deserializing)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 impl Default for FileInformation {
@@ -101,6 +107,7 @@ impl Default for FileInformation {
             file_notice: None,
             file_contributor: Vec::new(),
             file_attribution_text: None,
+            extensions: BTreeMap::new(),
         }
     }
 }
@@ -140,7 +147,7 @@ impl FileInformation {
 }
 
 /// <https://spdx.github.io/spdx-spec/4-file-information/#43-file-type>
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum FileType {
     Source,
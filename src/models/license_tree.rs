@@ -0,0 +1,332 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use spdx_expression::SpdxExpression;
+
+/// The structure of an [`SpdxExpression`], for evaluating the expression.
+///
+/// Parentheses are removed and nested operators of the same kind are flattened, so
+/// `MIT AND (Apache-2.0 AND BSD-3-Clause)` has a single `And` with three licenses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseTree {
+    /// A license, optionally with an exception, e.g. `GPL-2.0-only WITH Classpath-exception-2.0`.
+    License(LicenseTerm),
+
+    /// All of the licenses apply.
+    And(Vec<LicenseTree>),
+
+    /// Any one of the licenses can be chosen.
+    Or(Vec<LicenseTree>),
+}
+
+/// A single license of a [`LicenseTree`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LicenseTerm {
+    /// License identifier, `LicenseRef-` or `DocumentRef-` reference, `NONE` or `NOASSERTION`.
+    pub license: String,
+
+    /// Identifier of the exception of a `WITH` expression.
+    pub exception: Option<String>,
+}
+
+impl LicenseTerm {
+    pub fn new(license: &str, exception: Option<&str>) -> Self {
+        Self {
+            license: license.to_string(),
+            exception: exception.map(ToString::to_string),
+        }
+    }
+}
+
+impl fmt::Display for LicenseTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.exception {
+            Some(exception) => write!(f, "{} WITH {exception}", self.license),
+            None => write!(f, "{}", self.license),
+        }
+    }
+}
+
+impl fmt::Display for LicenseTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (operands, operator) = match self {
+            Self::License(term) => return write!(f, "{term}"),
+            Self::And(operands) => (operands, " AND "),
+            Self::Or(operands) => (operands, " OR "),
+        };
+        for (index, operand) in operands.iter().enumerate() {
+            if index > 0 {
+                f.write_str(operator)?;
+            }
+            match operand {
+                Self::License(_) => write!(f, "{operand}")?,
+                _ => write!(f, "({operand})")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&SpdxExpression> for LicenseTree {
+    fn from(expression: &SpdxExpression) -> Self {
+        let expression = expression.to_string();
+        // The expression has already been validated by the parser of `SpdxExpression`, so
+        // parsing its string representation only fails if the formats disagree.
+        Parser::new(&expression)
+            .parse()
+            .unwrap_or_else(|| Self::License(LicenseTerm::new(&expression, None)))
+    }
+}
+
+impl LicenseTree {
+    /// All licenses in the tree.
+    pub fn terms(&self) -> Vec<&LicenseTerm> {
+        match self {
+            Self::License(term) => vec![term],
+            Self::And(operands) | Self::Or(operands) => {
+                operands.iter().flat_map(Self::terms).collect()
+            }
+        }
+    }
+
+    /// All licenses in the tree, for modifying them.
+    pub fn terms_mut(&mut self) -> Vec<&mut LicenseTerm> {
+        match self {
+            Self::License(term) => vec![term],
+            Self::And(operands) | Self::Or(operands) => {
+                operands.iter_mut().flat_map(Self::terms_mut).collect()
+            }
+        }
+    }
+
+    /// Replace licenses of the tree. `replacement` returns the tree to replace a license with,
+    /// or `None` to keep the license. Nested operators of the same kind are flattened.
+    #[must_use]
+    pub fn replace(self, replacement: &mut impl FnMut(&LicenseTerm) -> Option<Self>) -> Self {
+        match self {
+            Self::License(term) => replacement(&term).unwrap_or(Self::License(term)),
+            Self::And(operands) => Self::combine(
+                operands
+                    .into_iter()
+                    .map(|operand| operand.replace(replacement))
+                    .collect(),
+                true,
+            ),
+            Self::Or(operands) => Self::combine(
+                operands
+                    .into_iter()
+                    .map(|operand| operand.replace(replacement))
+                    .collect(),
+                false,
+            ),
+        }
+    }
+
+    /// Remove duplicate operands, e.g. `MIT OR MIT AND (MIT)` becomes `MIT`. Licenses are
+    /// compared case-insensitively.
+    #[must_use]
+    pub fn simplify(self) -> Self {
+        let (operands, and) = match self {
+            Self::License(_) => return self,
+            Self::And(operands) => (operands, true),
+            Self::Or(operands) => (operands, false),
+        };
+        let operands = operands.into_iter().map(Self::simplify).collect();
+        let (operands, and) = match Self::combine(operands, and) {
+            Self::And(operands) => (operands, true),
+            Self::Or(operands) => (operands, false),
+            license @ Self::License(_) => return license,
+        };
+
+        let mut unique: Vec<Self> = Vec::new();
+        for operand in operands {
+            let key = operand.to_string().to_ascii_lowercase();
+            if !unique
+                .iter()
+                .any(|existing| existing.to_string().to_ascii_lowercase() == key)
+            {
+                unique.push(operand);
+            }
+        }
+        Self::combine(unique, and)
+    }
+
+    fn combine(operands: Vec<Self>, and: bool) -> Self {
+        let mut flattened = Vec::new();
+        for operand in operands {
+            match operand {
+                Self::And(nested) if and => flattened.extend(nested),
+                Self::Or(nested) if !and => flattened.extend(nested),
+                operand => flattened.push(operand),
+            }
+        }
+        if flattened.len() == 1 {
+            flattened.remove(0)
+        } else if and {
+            Self::And(flattened)
+        } else {
+            Self::Or(flattened)
+        }
+    }
+}
+
+/// Recursive descent parser for the string representation of [`SpdxExpression`]. `WITH` binds
+/// tighter than `AND`, which binds tighter than `OR`.
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(expression: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        for word in expression.split_whitespace() {
+            let mut rest = word;
+            while let Some(index) = rest.find(['(', ')']) {
+                if index > 0 {
+                    tokens.push(&rest[..index]);
+                }
+                tokens.push(&rest[index..=index]);
+                rest = &rest[index + 1..];
+            }
+            if !rest.is_empty() {
+                tokens.push(rest);
+            }
+        }
+        Self {
+            tokens,
+            position: 0,
+        }
+    }
+
+    fn parse(mut self) -> Option<LicenseTree> {
+        let tree = self.or()?;
+        (self.position == self.tokens.len()).then_some(tree)
+    }
+
+    fn next_if(&mut self, operator: &str) -> bool {
+        let matches = self
+            .tokens
+            .get(self.position)
+            .is_some_and(|token| token.eq_ignore_ascii_case(operator));
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn or(&mut self) -> Option<LicenseTree> {
+        let mut operands = vec![self.and()?];
+        while self.next_if("OR") {
+            operands.push(self.and()?);
+        }
+        Some(LicenseTree::combine(operands, false))
+    }
+
+    fn and(&mut self) -> Option<LicenseTree> {
+        let mut operands = vec![self.with()?];
+        while self.next_if("AND") {
+            operands.push(self.with()?);
+        }
+        Some(LicenseTree::combine(operands, true))
+    }
+
+    fn with(&mut self) -> Option<LicenseTree> {
+        if self.next_if("(") {
+            let tree = self.or()?;
+            return self.next_if(")").then_some(tree);
+        }
+
+        let license = *self.tokens.get(self.position)?;
+        if matches!(license, "(" | ")") {
+            return None;
+        }
+        self.position += 1;
+        let exception = if self.next_if("WITH") {
+            let exception = *self.tokens.get(self.position)?;
+            self.position += 1;
+            Some(exception)
+        } else {
+            None
+        };
+        Some(LicenseTree::License(LicenseTerm::new(license, exception)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tree(expression: &str) -> LicenseTree {
+        LicenseTree::from(&SpdxExpression::parse(expression).unwrap())
+    }
+
+    #[test]
+    fn simple_license() {
+        assert_eq!(
+            tree("MIT"),
+            LicenseTree::License(LicenseTerm::new("MIT", None))
+        );
+        assert_eq!(
+            tree("DocumentRef-other:LicenseRef-1"),
+            LicenseTree::License(LicenseTerm::new("DocumentRef-other:LicenseRef-1", None))
+        );
+    }
+
+    #[test]
+    fn precedence_of_operators() {
+        assert_eq!(
+            tree("MIT OR Apache-2.0 AND GPL-2.0-only WITH Classpath-exception-2.0"),
+            LicenseTree::Or(vec![
+                LicenseTree::License(LicenseTerm::new("MIT", None)),
+                LicenseTree::And(vec![
+                    LicenseTree::License(LicenseTerm::new("Apache-2.0", None)),
+                    LicenseTree::License(LicenseTerm::new(
+                        "GPL-2.0-only",
+                        Some("Classpath-exception-2.0")
+                    )),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parentheses_are_flattened() {
+        let tree = tree("(MIT AND (Apache-2.0 AND BSD-3-Clause)) OR (ISC)");
+        assert_eq!(
+            tree,
+            LicenseTree::Or(vec![
+                LicenseTree::And(vec![
+                    LicenseTree::License(LicenseTerm::new("MIT", None)),
+                    LicenseTree::License(LicenseTerm::new("Apache-2.0", None)),
+                    LicenseTree::License(LicenseTerm::new("BSD-3-Clause", None)),
+                ]),
+                LicenseTree::License(LicenseTerm::new("ISC", None)),
+            ])
+        );
+        assert_eq!(
+            tree.to_string(),
+            "(MIT AND Apache-2.0 AND BSD-3-Clause) OR ISC"
+        );
+        assert_eq!(tree.terms().len(), 4);
+    }
+
+    #[test]
+    fn licenses_are_replaced_and_simplified() {
+        let replaced = tree("LicenseRef-1 OR (MIT AND Apache-2.0)").replace(&mut |term| {
+            (term.license == "LicenseRef-1").then(|| tree("MIT AND Apache-2.0"))
+        });
+        assert_eq!(
+            replaced.to_string(),
+            "(MIT AND Apache-2.0) OR (MIT AND Apache-2.0)"
+        );
+        assert_eq!(replaced.simplify().to_string(), "MIT AND Apache-2.0");
+        assert_eq!(
+            tree("mit OR (MIT OR ISC)").simplify().to_string(),
+            "mit OR ISC"
+        );
+    }
+}
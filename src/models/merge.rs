@@ -0,0 +1,348 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::{HashMap, HashSet};
+
+use log::info;
+use serde::Serialize;
+
+use super::{DocumentCreationInformation, Pointer, SPDX};
+use crate::licenses::LicenseRewrite;
+
+/// Result of [`SPDX::merge`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    /// Elements and `LicenseRef-`s of the merged document that were renamed because the ID was
+    /// already used for a different element.
+    pub renamed: Vec<RenamedId>,
+
+    /// Elements and `LicenseRef-`s that were identical in both documents and were kept once.
+    pub duplicates: Vec<String>,
+}
+
+/// An ID of the merged document and the ID it was renamed to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenamedId {
+    pub from: String,
+    pub to: String,
+}
+
+impl SPDX {
+    /// Merge the packages, files, snippets, other licensing information, relationships and
+    /// annotations of another document into this one.
+    ///
+    /// Elements with the same SPDX ID are kept once if they are identical. Otherwise the
+    /// element of the other document is renamed by appending a number to its ID, and the
+    /// references to it are updated. Conflicting `LicenseRef-`s are renamed the same way. The
+    /// document of the other document is replaced with this document, so the elements it
+    /// describes are described by this document.
+    pub fn merge(&mut self, other: &Self) -> MergeReport {
+        info!("Merging SPDX.");
+        let mut other = other.clone();
+        let mut report = MergeReport::default();
+
+        let mut used_ids = self
+            .package_information
+            .iter()
+            .map(|package| package.package_spdx_identifier.clone())
+            .chain(
+                self.file_information
+                    .iter()
+                    .map(|file| file.file_spdx_identifier.clone()),
+            )
+            .chain(
+                self.snippet_information
+                    .iter()
+                    .map(|snippet| snippet.snippet_spdx_identifier.clone()),
+            )
+            .collect::<HashSet<_>>();
+
+        let mut renamed = Vec::new();
+        let mut duplicates = HashSet::new();
+        let mut plan = |existing: bool, id: &String| {
+            if existing {
+                duplicates.insert(id.clone());
+            } else if used_ids.contains(id) {
+                renamed.push(RenamedId {
+                    from: id.clone(),
+                    to: unique_id(id, &mut used_ids),
+                });
+            }
+        };
+        for package in &other.package_information {
+            plan(
+                self.package_information.contains(package),
+                &package.package_spdx_identifier,
+            );
+        }
+        for file in &other.file_information {
+            plan(
+                self.file_information.contains(file),
+                &file.file_spdx_identifier,
+            );
+        }
+        for snippet in &other.snippet_information {
+            plan(
+                self.snippet_information.contains(snippet),
+                &snippet.snippet_spdx_identifier,
+            );
+        }
+        let mut id_map = renamed
+            .iter()
+            .map(|renamed| (renamed.from.clone(), renamed.to.clone()))
+            .collect::<HashMap<_, _>>();
+        id_map.insert(
+            other.document_creation_information.spdx_identifier.clone(),
+            self.document_creation_information.spdx_identifier.clone(),
+        );
+        rename_elements(&mut other, &id_map);
+        report.renamed = renamed;
+
+        self.merge_licenses(&mut other, &mut report);
+
+        self.package_information.extend(
+            other
+                .package_information
+                .into_iter()
+                .filter(|package| !duplicates.contains(&package.package_spdx_identifier)),
+        );
+        self.file_information.extend(
+            other
+                .file_information
+                .into_iter()
+                .filter(|file| !duplicates.contains(&file.file_spdx_identifier)),
+        );
+        self.snippet_information.extend(
+            other
+                .snippet_information
+                .into_iter()
+                .filter(|snippet| !duplicates.contains(&snippet.snippet_spdx_identifier)),
+        );
+        report.duplicates.extend(duplicates);
+        report.duplicates.sort();
+
+        extend_unique(&mut self.relationships, other.relationships);
+        extend_unique(&mut self.annotations, other.annotations);
+        merge_document(
+            &mut self.document_creation_information,
+            other.document_creation_information,
+        );
+
+        report
+    }
+
+    /// Move the other licensing information of the other document to this document, renaming
+    /// conflicting `LicenseRef-`s in the other document.
+    fn merge_licenses(&mut self, other: &mut Self, report: &mut MergeReport) {
+        let mut used_ids = self
+            .other_licensing_information_detected
+            .iter()
+            .map(|license| license.license_identifier.clone())
+            .collect::<HashSet<_>>();
+
+        let mut rewrite = LicenseRewrite::new();
+        let mut licenses = Vec::new();
+        for license in &other.other_licensing_information_detected {
+            let id = &license.license_identifier;
+            if self.other_licensing_information_detected.contains(license) {
+                report.duplicates.push(id.clone());
+            } else if used_ids.contains(id) {
+                let to = unique_id(id, &mut used_ids);
+                if rewrite.map(id, &to).is_ok() {
+                    let mut license = license.clone();
+                    license.license_identifier.clone_from(&to);
+                    licenses.push(license);
+                    report.renamed.push(RenamedId {
+                        from: id.clone(),
+                        to,
+                    });
+                }
+            } else {
+                licenses.push(license.clone());
+            }
+        }
+
+        rewrite.apply(other);
+        self.other_licensing_information_detected.extend(licenses);
+    }
+}
+
+/// Merge the described elements, creators and external document references of the documents.
+fn merge_document(document: &mut DocumentCreationInformation, other: DocumentCreationInformation) {
+    extend_unique(&mut document.document_describes, other.document_describes);
+    extend_unique(
+        &mut document.creation_info.creators,
+        other.creation_info.creators,
+    );
+    for reference in other.external_document_references {
+        if !document
+            .external_document_references
+            .iter()
+            .any(|existing| existing.id_string == reference.id_string)
+        {
+            document.external_document_references.push(reference);
+        }
+    }
+}
+
+/// Append the values that are not in the target yet.
+fn extend_unique<T: PartialEq>(target: &mut Vec<T>, values: Vec<T>) {
+    for value in values {
+        if !target.contains(&value) {
+            target.push(value);
+        }
+    }
+}
+
+/// The ID with the smallest number appended that is not used yet.
+fn unique_id(id: &str, used_ids: &mut HashSet<String>) -> String {
+    let mut number = 2;
+    while used_ids.contains(&format!("{id}-{number}")) {
+        number += 1;
+    }
+    let unique = format!("{id}-{number}");
+    used_ids.insert(unique.clone());
+    unique
+}
+
+/// Rename SPDX IDs and all references to them.
+fn rename_elements(spdx: &mut SPDX, renames: &HashMap<String, String>) {
+    let rename = |id: &mut String| {
+        if let Some(to) = renames.get(id.as_str()) {
+            id.clone_from(to);
+        }
+    };
+
+    let document = &mut spdx.document_creation_information;
+    rename(&mut document.spdx_identifier);
+    document.document_describes.iter_mut().for_each(rename);
+    for package in &mut spdx.package_information {
+        rename(&mut package.package_spdx_identifier);
+        package.files.iter_mut().for_each(rename);
+        for annotation in &mut package.annotations {
+            annotation
+                .spdx_identifier_reference
+                .iter_mut()
+                .for_each(rename);
+        }
+    }
+    for file in &mut spdx.file_information {
+        rename(&mut file.file_spdx_identifier);
+    }
+    for snippet in &mut spdx.snippet_information {
+        rename(&mut snippet.snippet_spdx_identifier);
+        rename(&mut snippet.snippet_from_file_spdx_identifier);
+        for range in &mut snippet.ranges {
+            for pointer in [&mut range.start_pointer, &mut range.end_pointer] {
+                match pointer {
+                    Pointer::Byte { reference, .. } | Pointer::Line { reference, .. } => {
+                        reference.iter_mut().for_each(rename);
+                    }
+                }
+            }
+        }
+    }
+    for relationship in &mut spdx.relationships {
+        rename(&mut relationship.spdx_element_id);
+        rename(&mut relationship.related_spdx_element);
+    }
+    for annotation in &mut spdx.annotations {
+        annotation
+            .spdx_identifier_reference
+            .iter_mut()
+            .for_each(rename);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::models::{OtherLicensingInformationDetected, RelationshipType, SpdxExpression};
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn identical_document_is_merged_once() {
+        let mut spdx = example();
+        let report = spdx.merge(&example());
+
+        assert!(report.renamed.is_empty());
+        assert_eq!(spdx, example());
+        assert!(report.duplicates.contains(&"SPDXRef-Package".to_string()));
+        assert!(report.duplicates.contains(&"LicenseRef-1".to_string()));
+        assert!(spdx.validate().is_empty());
+    }
+
+    #[test]
+    fn conflicting_ids_are_renamed() {
+        let mut spdx = example();
+        let mut other = example();
+        other.document_creation_information.spdx_identifier = "SPDXRef-OTHER".to_string();
+        for relationship in &mut other.relationships {
+            if relationship.spdx_element_id == "SPDXRef-DOCUMENT" {
+                relationship.spdx_element_id = "SPDXRef-OTHER".to_string();
+            }
+        }
+        other.package_information[0].package_version = Some("2.12".to_string());
+        other.other_licensing_information_detected[4] = OtherLicensingInformationDetected {
+            extracted_text: "Other text".to_string(),
+            ..other.other_licensing_information_detected[4].clone()
+        };
+        let license_ref = other.other_licensing_information_detected[4]
+            .license_identifier
+            .clone();
+        let report = spdx.merge(&other);
+
+        assert_eq!(
+            report.renamed,
+            vec![
+                RenamedId {
+                    from: "SPDXRef-Package".to_string(),
+                    to: "SPDXRef-Package-2".to_string()
+                },
+                RenamedId {
+                    from: license_ref.clone(),
+                    to: format!("{license_ref}-2")
+                }
+            ]
+        );
+        assert_eq!(
+            spdx.package_information.len(),
+            example().package_information.len() + 1
+        );
+        let package = spdx
+            .package_information
+            .iter()
+            .find(|package| package.package_spdx_identifier == "SPDXRef-Package-2")
+            .unwrap();
+        assert_eq!(package.package_version.as_deref(), Some("2.12"));
+        assert!(spdx
+            .relationships
+            .contains(&crate::models::Relationship::new(
+                "SPDXRef-DOCUMENT",
+                "SPDXRef-Package-2",
+                RelationshipType::Contains,
+                None
+            )));
+        assert!(spdx
+            .other_licensing_information_detected
+            .iter()
+            .any(
+                |license| license.license_identifier == format!("{license_ref}-2")
+                    && license.extracted_text == "Other text"
+            ));
+        assert_eq!(
+            package.concluded_license,
+            Some(SpdxExpression::parse("LGPL-2.0-only OR LicenseRef-3-2").unwrap())
+        );
+        assert!(spdx.validate().is_empty());
+    }
+}
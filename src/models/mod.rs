@@ -3,22 +3,40 @@
 // SPDX-License-Identifier: MIT
 
 mod annotation;
+mod canonical;
 mod checksum;
+mod cpe;
+mod diff;
 mod document_creation_information;
 mod file_information;
+mod license_tree;
+mod merge;
 mod other_licensing_information_detected;
 mod package_information;
+mod purl;
 mod relationship;
 mod snippet;
 mod spdx_document;
+mod special_value;
+mod summary;
+mod validation;
+mod verification;
 
 pub use annotation::*;
 pub use checksum::*;
+pub use cpe::*;
+pub use diff::*;
 pub use document_creation_information::*;
 pub use file_information::*;
+pub use license_tree::*;
+pub use merge::*;
 pub use other_licensing_information_detected::*;
 pub use package_information::*;
+pub use purl::*;
 pub use relationship::*;
 pub use snippet::*;
 pub use spdx_document::*;
 pub use spdx_expression::*;
+pub use special_value::*;
+pub use summary::*;
+pub use validation::*;
@@ -2,7 +2,10 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// <https://spdx.github.io/spdx-spec/6-other-licensing-information-detected/>
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
@@ -27,6 +30,10 @@ pub struct OtherLicensingInformationDetected {
     /// <https://spdx.github.io/spdx-spec/6-other-licensing-information-detected/#65-license-comment>
     #[serde(rename = "comment", skip_serializing_if = "Option::is_none", default)]
     pub license_comment: Option<String>,
+
+    /// Fields of the license that are not defined by the spec.
+    #[serde(flatten, skip_deserializing)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 fn default_noassertion() -> String {
@@ -2,12 +2,17 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::{collections::BTreeMap, str::FromStr};
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use spdx_expression::SpdxExpression;
 
+use crate::error::SpdxError;
+
 use super::Annotation;
 
-use super::{Checksum, FileInformation};
+use super::{Checksum, Cpe, FileInformation, Purl};
 
 /// ## Package Information
 ///
@@ -179,6 +184,10 @@ pub struct PackageInformation {
         default
     )]
     pub primary_package_purpose: Option<PrimaryPackagePurpose>,
+
+    /// Fields of the package that are not defined by the spec.
+    #[serde(flatten, skip_deserializing)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 impl Default for PackageInformation {
@@ -212,6 +221,7 @@ impl Default for PackageInformation {
             release_date: None,
             valid_until_date: None,
             primary_package_purpose: None,
+            extensions: BTreeMap::new(),
         }
     }
 }
@@ -241,6 +251,30 @@ impl PackageInformation {
             })
             .collect()
     }
+
+    /// Get the CPEs from the package's `cpe22Type` and `cpe23Type` external references.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Cpe`] if any of the locators is not a valid CPE.
+    pub fn cpes(&self) -> Result<Vec<Cpe>, SpdxError> {
+        self.external_reference
+            .iter()
+            .filter_map(ExternalPackageReference::cpe)
+            .collect()
+    }
+
+    /// Get the purls from the package's `purl` external references.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Purl`] if any of the locators is not a valid purl.
+    pub fn purls(&self) -> Result<Vec<Purl>, SpdxError> {
+        self.external_reference
+            .iter()
+            .filter_map(ExternalPackageReference::purl)
+            .collect()
+    }
 }
 
 /// <https://spdx.github.io/spdx-spec/3-package-information/#39-package-verification-code>
@@ -266,7 +300,7 @@ impl PackageVerificationCode {
 }
 
 /// <https://spdx.github.io/spdx-spec/3-package-information/#321-external-reference>
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ExternalPackageReference {
     pub reference_category: ExternalPackageReferenceCategory,
@@ -292,10 +326,39 @@ impl ExternalPackageReference {
             reference_comment,
         }
     }
+
+    /// Parse the locator of a `SECURITY` reference of type `cpe22Type` or `cpe23Type`. Returns
+    /// `None` for other references.
+    pub fn cpe(&self) -> Option<Result<Cpe, SpdxError>> {
+        // Older documents use the full URI of the reference type.
+        let reference_type = self
+            .reference_type
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.reference_type);
+
+        match (&self.reference_category, reference_type) {
+            (ExternalPackageReferenceCategory::Security, "cpe22Type" | "cpe23Type") => {
+                Some(Cpe::from_str(&self.reference_locator))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse the locator of a `PACKAGE-MANAGER` reference of type `purl`. Returns `None` for other
+    /// references.
+    pub fn purl(&self) -> Option<Result<Purl, SpdxError>> {
+        match (&self.reference_category, self.reference_type.as_str()) {
+            (ExternalPackageReferenceCategory::PackageManager, "purl") => {
+                Some(Purl::from_str(&self.reference_locator))
+            }
+            _ => None,
+        }
+    }
 }
 
 /// <https://spdx.github.io/spdx-spec/3-package-information/#321-external-reference>
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum ExternalPackageReferenceCategory {
     Security,
@@ -0,0 +1,305 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use crate::error::SpdxError;
+
+/// A [Package URL](https://github.com/package-url/purl-spec), the locator of SPDX's `purl`
+/// external references.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Purl {
+    /// Package type, e.g. `cargo` or `npm`. Always lowercase.
+    pub purl_type: String,
+
+    /// Optional namespace, e.g. the Maven group id or the npm scope.
+    pub namespace: Option<String>,
+
+    /// Name of the package.
+    pub name: String,
+
+    /// Version of the package.
+    pub version: Option<String>,
+
+    /// Extra qualifying data, e.g. `arch` or `repository_url`.
+    pub qualifiers: BTreeMap<String, String>,
+
+    /// Path to a subdirectory within the package.
+    pub subpath: Option<String>,
+}
+
+impl Purl {
+    /// Create a new purl without namespace, qualifiers or subpath.
+    pub fn new(purl_type: &str, name: &str, version: Option<&str>) -> Self {
+        Self {
+            purl_type: purl_type.to_lowercase(),
+            namespace: None,
+            name: name.to_string(),
+            version: version.map(ToString::to_string),
+            qualifiers: BTreeMap::new(),
+            subpath: None,
+        }
+    }
+
+    /// Set the namespace of the purl.
+    #[must_use]
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Check if the purls refer to the same package, ignoring version, qualifiers and subpath.
+    pub fn same_package(&self, other: &Self) -> bool {
+        self.purl_type == other.purl_type
+            && self.namespace == other.namespace
+            && self.name == other.name
+    }
+}
+
+impl FromStr for Purl {
+    type Err = SpdxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason: &str| SpdxError::Purl(format!("{s}: {reason}"));
+
+        let rest = s
+            .strip_prefix("pkg:")
+            .ok_or_else(|| error("doesn't start with pkg:"))?
+            .trim_start_matches('/');
+
+        let (rest, subpath) = match rest.split_once('#') {
+            Some((rest, subpath)) => {
+                let segments = subpath
+                    .split('/')
+                    .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+                    .map(percent_decode)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|reason| error(&reason))?;
+                (rest, (!segments.is_empty()).then(|| segments.join("/")))
+            }
+            None => (rest, None),
+        };
+
+        let (rest, qualifiers) = match rest.split_once('?') {
+            Some((rest, qualifiers)) => {
+                let mut parsed = BTreeMap::new();
+                for qualifier in qualifiers.split('&').filter(|q| !q.is_empty()) {
+                    let (key, value) = qualifier
+                        .split_once('=')
+                        .ok_or_else(|| error("qualifier without a value"))?;
+                    if !value.is_empty() {
+                        parsed.insert(
+                            key.to_lowercase(),
+                            percent_decode(value).map_err(|reason| error(&reason))?,
+                        );
+                    }
+                }
+                (rest, parsed)
+            }
+            None => (rest, BTreeMap::new()),
+        };
+
+        let (purl_type, rest) = rest.split_once('/').ok_or_else(|| error("missing name"))?;
+        if purl_type.is_empty() {
+            return Err(error("missing type"));
+        }
+        let purl_type = purl_type.to_lowercase();
+
+        let rest = rest.trim_end_matches('/');
+        let (rest, version) = match rest.rsplit_once('@') {
+            // An `@` at the start of the namespace is an npm scope, not a version.
+            Some((rest, version)) if !rest.is_empty() => (
+                rest,
+                Some(percent_decode(version).map_err(|reason| error(&reason))?),
+            ),
+            _ => (rest, None),
+        };
+
+        let (namespace, name) = match rest.rsplit_once('/') {
+            Some((namespace, name)) => {
+                let segments = namespace
+                    .split('/')
+                    .filter(|segment| !segment.is_empty())
+                    .map(percent_decode)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|reason| error(&reason))?;
+                (Some(segments.join("/")), name)
+            }
+            None => (None, rest),
+        };
+        let mut name = percent_decode(name).map_err(|reason| error(&reason))?;
+        if name.is_empty() {
+            return Err(error("missing name"));
+        }
+
+        let mut namespace = namespace.filter(|namespace| !namespace.is_empty());
+        match purl_type.as_str() {
+            "bitbucket" | "github" => {
+                namespace = namespace.map(|namespace| namespace.to_lowercase());
+                name = name.to_lowercase();
+            }
+            "pypi" => name = name.to_lowercase().replace('_', "-"),
+            _ => {}
+        }
+
+        Ok(Self {
+            purl_type,
+            namespace,
+            name,
+            version,
+            qualifiers,
+            subpath,
+        })
+    }
+}
+
+impl Display for Purl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pkg:{}/", self.purl_type)?;
+        if let Some(namespace) = &self.namespace {
+            for segment in namespace.split('/') {
+                write!(f, "{}/", percent_encode(segment))?;
+            }
+        }
+        write!(f, "{}", percent_encode(&self.name))?;
+        if let Some(version) = &self.version {
+            write!(f, "@{}", percent_encode(version))?;
+        }
+        if !self.qualifiers.is_empty() {
+            let qualifiers = self
+                .qualifiers
+                .iter()
+                .map(|(key, value)| format!("{key}={}", percent_encode(value)))
+                .collect::<Vec<_>>();
+            write!(f, "?{}", qualifiers.join("&"))?;
+        }
+        if let Some(subpath) = &self.subpath {
+            let segments = subpath.split('/').map(percent_encode).collect::<Vec<_>>();
+            write!(f, "#{}", segments.join("/"))?;
+        }
+        Ok(())
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'-' | b'_' | b'~' | b':') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let byte = value
+                .get(index + 1..index + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("invalid percent-encoding in {value}"))?;
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| format!("{value} is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::models::SPDX;
+
+    use super::*;
+
+    #[test]
+    fn purl_is_parsed() {
+        let purl = Purl::from_str("pkg:maven/org.apache.jena/apache-jena@3.12.0").unwrap();
+        assert_eq!(purl.purl_type, "maven");
+        assert_eq!(purl.namespace, Some("org.apache.jena".to_string()));
+        assert_eq!(purl.name, "apache-jena");
+        assert_eq!(purl.version, Some("3.12.0".to_string()));
+    }
+
+    #[test]
+    fn purl_with_qualifiers_and_subpath_is_parsed() {
+        let purl = Purl::from_str(
+            "pkg:rpm/fedora/curl@7.50.3-1.fc25?distro=fedora-25&arch=i386#docs/readme",
+        )
+        .unwrap();
+        assert_eq!(purl.qualifiers.get("arch"), Some(&"i386".to_string()));
+        assert_eq!(
+            purl.qualifiers.get("distro"),
+            Some(&"fedora-25".to_string())
+        );
+        assert_eq!(purl.subpath, Some("docs/readme".to_string()));
+        assert_eq!(
+            purl.to_string(),
+            "pkg:rpm/fedora/curl@7.50.3-1.fc25?arch=i386&distro=fedora-25#docs/readme"
+        );
+    }
+
+    #[test]
+    fn npm_scope_is_parsed() {
+        for input in [
+            "pkg:npm/%40angular/animation@12.3.1",
+            "pkg:npm/@angular/animation@12.3.1",
+        ] {
+            let purl = Purl::from_str(input).unwrap();
+            assert_eq!(purl.namespace, Some("@angular".to_string()));
+            assert_eq!(purl.name, "animation");
+            assert_eq!(purl.to_string(), "pkg:npm/%40angular/animation@12.3.1");
+        }
+    }
+
+    #[test]
+    fn type_specific_names_are_normalized() {
+        assert_eq!(
+            Purl::from_str("pkg:PyPI/Django_Allauth@1.0").unwrap().name,
+            "django-allauth"
+        );
+        assert_eq!(
+            Purl::from_str("pkg:github/Package-URL/purl-spec").unwrap(),
+            Purl::new("github", "purl-spec", None).with_namespace("package-url")
+        );
+    }
+
+    #[test]
+    fn invalid_purls_are_rejected() {
+        assert!(Purl::from_str("maven/org.apache.jena/apache-jena@3.12.0").is_err());
+        assert!(Purl::from_str("pkg:maven").is_err());
+        assert!(Purl::from_str("pkg:cargo/serde@1.0%zz").is_err());
+    }
+
+    #[test]
+    fn purls_are_read_from_external_references() {
+        let spdx: SPDX = serde_json::from_str(
+            &read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap(),
+        )
+        .unwrap();
+        let purls = spdx
+            .package_information
+            .iter()
+            .map(|package| package.purls().unwrap())
+            .find(|purls| !purls.is_empty())
+            .unwrap();
+        assert_eq!(
+            purls,
+            vec![Purl::from_str("pkg:maven/org.apache.jena/apache-jena@3.12.0").unwrap()]
+        );
+    }
+}
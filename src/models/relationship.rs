@@ -2,11 +2,18 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::{
+    collections::BTreeMap,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use strum_macros::AsRefStr;
 
 /// <https://spdx.github.io/spdx-spec/7-relationships-between-SPDX-elements/#71-relationship>
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Relationship {
     /// SPDX ID of the element.
@@ -22,6 +29,10 @@ pub struct Relationship {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub comment: Option<String>,
+
+    /// Fields of the relationship that are not defined by the spec.
+    #[serde(flatten, skip_deserializing)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 impl Relationship {
@@ -37,10 +48,23 @@ impl Relationship {
             related_spdx_element: related_spdx_element.to_string(),
             relationship_type,
             comment,
+            extensions: BTreeMap::new(),
         }
     }
 }
 
+// Hashes a subset of the fields compared by `PartialEq`, so equal relationships always have equal
+// hashes as `Hash` requires. Relationships that differ only in their extensions collide but stay
+// unequal, so sets like the one used to remove duplicate tag-value relationships keep both.
+impl Hash for Relationship {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.spdx_element_id.hash(state);
+        self.related_spdx_element.hash(state);
+        self.relationship_type.hash(state);
+        self.comment.hash(state);
+    }
+}
+
 /// <https://spdx.github.io/spdx-spec/7-relationships-between-SPDX-elements/#71-relationship>
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, AsRefStr, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -92,14 +116,60 @@ pub enum RelationshipType {
     Other,
 }
 
+/// Formats the relationship type as in the SPDX specification, e.g. `DEPENDS_ON`.
+impl fmt::Display for RelationshipType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.as_ref().chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 {
+                f.write_str("_")?;
+            }
+            write!(f, "{}", c.to_ascii_uppercase())?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use std::fs::read_to_string;
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        fs::read_to_string,
+        iter::FromIterator,
+    };
 
     use crate::models::SPDX;
 
     use super::*;
 
+    fn hash(relationship: &Relationship) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        relationship.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_relationships_have_equal_hashes() {
+        let relationship = Relationship::new(
+            "SPDXRef-DOCUMENT",
+            "SPDXRef-Package",
+            RelationshipType::Describes,
+            Some("comment".to_string()),
+        );
+        assert_eq!(hash(&relationship), hash(&relationship.clone()));
+
+        let mut extended = relationship.clone();
+        extended
+            .extensions
+            .insert("confidence".to_string(), Value::from(0.9));
+        assert_ne!(extended, relationship);
+        assert_eq!(hash(&extended), hash(&relationship));
+        assert_eq!(
+            HashSet::<Relationship>::from_iter([relationship.clone(), extended, relationship])
+                .len(),
+            2
+        );
+    }
+
     #[test]
     fn spdx_element_id() {
         let spdx: SPDX = serde_json::from_str(
@@ -137,4 +207,13 @@ mod test {
             RelationshipType::CopyOf
         );
     }
+    #[test]
+    fn relationship_type_display() {
+        assert_eq!(RelationshipType::DependsOn.to_string(), "DEPENDS_ON");
+        assert_eq!(RelationshipType::Describes.to_string(), "DESCRIBES");
+        assert_eq!(
+            RelationshipType::ExpandedFromArchive.to_string(),
+            "EXPANDED_FROM_ARCHIVE"
+        );
+    }
 }
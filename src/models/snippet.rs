@@ -2,7 +2,10 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use spdx_expression::SpdxExpression;
 
 /// <https://spdx.github.io/spdx-spec/5-snippet-information/>
@@ -66,6 +69,10 @@ pub struct Snippet {
         default
     )]
     pub snippet_attribution_text: Option<String>,
+
+    /// Fields of the snippet that are not defined by the spec.
+    #[serde(flatten, skip_deserializing)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 /// <https://spdx.github.io/spdx-spec/5-snippet-information/#53-snippet-byte-range>
@@ -112,6 +119,15 @@ impl Pointer {
             line_number,
         }
     }
+
+    /// Set the SPDX ID of the file the pointer refers to.
+    pub fn set_reference(&mut self, file_spdx_id: &str) {
+        match self {
+            Self::Byte { reference, .. } | Self::Line { reference, .. } => {
+                *reference = Some(file_spdx_id.to_string());
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -8,6 +8,8 @@ use log::info;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::parallel;
+
 use super::{
     Algorithm, Annotation, DocumentCreationInformation, FileInformation,
     OtherLicensingInformationDetected, PackageInformation, Relationship, Snippet,
@@ -85,11 +87,7 @@ impl SPDX {
         Self {
             document_creation_information: DocumentCreationInformation {
                 document_name: name.to_string(),
-                spdx_document_namespace: format!(
-                    "http://spdx.org/spdxdocs/{}-{}",
-                    name,
-                    Uuid::new_v4()
-                ),
+                spdx_document_namespace: document_namespace(name, Uuid::new_v4()),
                 ..DocumentCreationInformation::default()
             },
             package_information: Vec::new(),
@@ -102,19 +100,24 @@ impl SPDX {
         }
     }
 
+    /// Set the UUID of the document namespace, which is random for documents created with
+    /// [`SPDX::new`]. Use a fixed UUID, or one derived from the input, to create the same
+    /// namespace for the same input.
+    pub fn set_namespace_uuid(&mut self, uuid: Uuid) {
+        let document = &mut self.document_creation_information;
+        document.spdx_document_namespace = document_namespace(&document.document_name, uuid);
+    }
+
     /// Get unique hashes for all files the SPDX.
     pub fn get_unique_hashes(&self, algorithm: Algorithm) -> HashSet<String> {
         info!("Getting unique hashes for files in SPDX.");
 
-        let mut unique_hashes: HashSet<String> = HashSet::new();
-
-        for file_information in &self.file_information {
-            if let Some(checksum) = file_information.checksum(algorithm) {
-                unique_hashes.insert(checksum.to_string());
-            }
-        }
-
-        unique_hashes
+        parallel::map(&self.file_information, |file_information| {
+            file_information.checksum(algorithm).map(str::to_string)
+        })
+        .into_iter()
+        .flatten()
+        .collect()
     }
 
     /// Find related files of the package with the provided id.
@@ -152,19 +155,20 @@ impl SPDX {
     pub fn get_license_ids(&self) -> HashSet<String> {
         info!("Getting all license identifiers from SPDX.");
 
-        let mut license_ids = HashSet::new();
-
-        for file in &self.file_information {
-            if let Some(concluded_license) = &file.concluded_license {
-                for license in concluded_license.identifiers() {
-                    if license != "NOASSERTION" && license != "NONE" {
-                        license_ids.insert(license.clone());
-                    }
-                }
-            }
-        }
-
-        license_ids
+        parallel::flat_map(&self.file_information, |file| {
+            file.concluded_license
+                .as_ref()
+                .map(|concluded_license| {
+                    concluded_license
+                        .identifiers()
+                        .into_iter()
+                        .filter(|license| license != "NOASSERTION" && license != "NONE")
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+        .into_iter()
+        .collect()
     }
 
     /// Get all relationships where the given SPDX ID is the SPDX element id.
@@ -184,9 +188,28 @@ impl SPDX {
     }
 }
 
+/// Namespace of a document created by this library.
+fn document_namespace(name: &str, uuid: Uuid) -> String {
+    format!("http://spdx.org/spdxdocs/{name}-{uuid}")
+}
+
+/// Replace characters that are not allowed in SPDX IDs with `-`.
+pub fn sanitize_spdx_id(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
-    use std::{fs::read_to_string, iter::FromIterator};
+    use std::{collections::BTreeMap, fs::read_to_string, iter::FromIterator};
 
     use spdx_expression::SpdxExpression;
 
@@ -266,12 +289,14 @@ mod test {
             related_spdx_element: "SPDXRef-Saxon".into(),
             relationship_type: RelationshipType::DynamicLink,
             comment: None,
+            extensions: BTreeMap::new(),
         };
         let relationship_2 = Relationship {
             spdx_element_id: "SPDXRef-Package".into(),
             related_spdx_element: "SPDXRef-JenaLib".into(),
             relationship_type: RelationshipType::Contains,
             comment: None,
+            extensions: BTreeMap::new(),
         };
         let expected_relationships = vec![&relationship_1, &relationship_2];
 
@@ -291,18 +316,21 @@ mod test {
             related_spdx_element: "SPDXRef-Package".into(),
             relationship_type: RelationshipType::Contains,
             comment: None,
+            extensions: BTreeMap::new(),
         };
         let relationship_2 = Relationship {
             spdx_element_id: "SPDXRef-DOCUMENT".into(),
             related_spdx_element: "SPDXRef-Package".into(),
             relationship_type: RelationshipType::Describes,
             comment: None,
+            extensions: BTreeMap::new(),
         };
         let relationship_3 = Relationship {
             spdx_element_id: "SPDXRef-JenaLib".into(),
             related_spdx_element: "SPDXRef-Package".into(),
             relationship_type: RelationshipType::Contains,
             comment: None,
+            extensions: BTreeMap::new(),
         };
         let expected_relationships = vec![&relationship_1, &relationship_2, &relationship_3];
 
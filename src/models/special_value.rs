@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+/// The special values SPDX allows in place of the value of many fields, e.g. licenses,
+/// copyright texts and suppliers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialValue {
+    /// `NOASSERTION`: the value hasn't been determined or isn't given.
+    NoAssertion,
+
+    /// `NONE`: the field has no value, e.g. there is no copyright.
+    None,
+}
+
+impl SpecialValue {
+    /// The special value of a field value, ignoring surrounding whitespace. Empty values are
+    /// treated as `NOASSERTION`, as they don't assert anything either.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use spdx_rs::models::SpecialValue;
+    ///
+    /// assert_eq!(SpecialValue::of(" NOASSERTION"), Some(SpecialValue::NoAssertion));
+    /// assert_eq!(SpecialValue::of(""), Some(SpecialValue::NoAssertion));
+    /// assert_eq!(SpecialValue::of("NONE"), Some(SpecialValue::None));
+    /// assert_eq!(SpecialValue::of("MIT"), None);
+    /// ```
+    pub fn of(value: &str) -> Option<Self> {
+        match value.trim() {
+            "" | "NOASSERTION" => Some(Self::NoAssertion),
+            "NONE" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// Whether the value is `NOASSERTION` or empty.
+    pub fn is_noassertion(value: &str) -> bool {
+        Self::of(value) == Some(Self::NoAssertion)
+    }
+}
@@ -0,0 +1,284 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::Serialize;
+use spdx_expression::SpdxExpression;
+
+use super::{SpecialValue, SPDX};
+
+/// Statistics of a document, created with [`SPDX::summary`]. Printable with [`fmt::Display`]
+/// and serializable to JSON.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    /// Number of packages.
+    pub packages: usize,
+
+    /// Number of files.
+    pub files: usize,
+
+    /// Number of snippets.
+    pub snippets: usize,
+
+    /// Number of relationships.
+    pub relationships: usize,
+
+    /// Number of packages and files per concluded license. Missing licenses are counted as
+    /// `NOASSERTION`.
+    pub concluded_licenses: BTreeMap<String, usize>,
+
+    /// Number of packages per declared license. Missing licenses are counted as `NOASSERTION`.
+    pub declared_licenses: BTreeMap<String, usize>,
+
+    /// Coverage of optional information.
+    pub coverage: Coverage,
+
+    /// Number of relationships per relationship type, e.g. `DEPENDS_ON`.
+    pub relationship_types: BTreeMap<String, usize>,
+}
+
+/// Percentages of the elements of a document that have a piece of information.
+///
+/// A percentage is 0 if the document has no elements of the kind, as none of them has the
+/// information. This differs from
+/// [`QualityReport::score`](crate::quality::QualityReport::score), which is 100 if there's
+/// nothing to check.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Coverage {
+    /// Files with at least one checksum.
+    pub files_with_checksums: f64,
+
+    /// Packages with at least one valid purl.
+    pub packages_with_purls: f64,
+
+    /// Packages with a supplier other than `NOASSERTION` or empty.
+    pub packages_with_suppliers: f64,
+
+    /// Packages and files whose concluded license is missing or `NOASSERTION`.
+    pub noassertion_concluded_licenses: f64,
+
+    /// Packages whose declared license is missing or `NOASSERTION`.
+    pub noassertion_declared_licenses: f64,
+
+    /// Packages and files whose copyright text is missing, empty or `NOASSERTION`.
+    pub noassertion_copyright_texts: f64,
+}
+
+impl SPDX {
+    /// Count the elements, licenses and relationships of the document.
+    pub fn summary(&self) -> Summary {
+        let packages = &self.package_information;
+        let files = &self.file_information;
+        let licensed = packages.len() + files.len();
+
+        let mut concluded_licenses = BTreeMap::new();
+        for license in packages
+            .iter()
+            .map(|package| package.concluded_license.as_ref())
+            .chain(files.iter().map(|file| file.concluded_license.as_ref()))
+        {
+            *concluded_licenses.entry(license_key(license)).or_insert(0) += 1;
+        }
+        let mut declared_licenses = BTreeMap::new();
+        for package in packages {
+            *declared_licenses
+                .entry(license_key(package.declared_license.as_ref()))
+                .or_insert(0) += 1;
+        }
+        let mut relationship_types = BTreeMap::new();
+        for relationship in &self.relationships {
+            *relationship_types
+                .entry(relationship.relationship_type.to_string())
+                .or_insert(0) += 1;
+        }
+
+        let coverage = Coverage {
+            files_with_checksums: percentage(
+                files
+                    .iter()
+                    .filter(|file| !file.file_checksum.is_empty())
+                    .count(),
+                files.len(),
+            ),
+            packages_with_purls: percentage(
+                packages
+                    .iter()
+                    .filter(|package| package.purls().is_ok_and(|purls| !purls.is_empty()))
+                    .count(),
+                packages.len(),
+            ),
+            packages_with_suppliers: percentage(
+                packages
+                    .iter()
+                    .filter(|package| !is_noassertion(package.package_supplier.as_deref()))
+                    .count(),
+                packages.len(),
+            ),
+            noassertion_concluded_licenses: percentage(
+                concluded_licenses.get("NOASSERTION").copied().unwrap_or(0),
+                licensed,
+            ),
+            noassertion_declared_licenses: percentage(
+                declared_licenses.get("NOASSERTION").copied().unwrap_or(0),
+                packages.len(),
+            ),
+            noassertion_copyright_texts: percentage(
+                packages
+                    .iter()
+                    .map(|package| &package.copyright_text)
+                    .chain(files.iter().map(|file| &file.copyright_text))
+                    .filter(|text| is_noassertion(text.as_deref()))
+                    .count(),
+                licensed,
+            ),
+        };
+
+        Summary {
+            packages: packages.len(),
+            files: files.len(),
+            snippets: self.snippet_information.len(),
+            relationships: self.relationships.len(),
+            concluded_licenses,
+            declared_licenses,
+            coverage,
+            relationship_types,
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Packages: {}", self.packages)?;
+        writeln!(f, "Files: {}", self.files)?;
+        writeln!(f, "Snippets: {}", self.snippets)?;
+        writeln!(f, "Relationships: {}", self.relationships)?;
+
+        writeln!(f, "\nConcluded licenses:")?;
+        write_counts(f, &self.concluded_licenses)?;
+        writeln!(f, "\nDeclared licenses:")?;
+        write_counts(f, &self.declared_licenses)?;
+
+        let coverage = &self.coverage;
+        writeln!(f, "\nCoverage:")?;
+        for (name, value) in [
+            ("Files with checksums", coverage.files_with_checksums),
+            ("Packages with purls", coverage.packages_with_purls),
+            ("Packages with suppliers", coverage.packages_with_suppliers),
+            (
+                "NOASSERTION concluded licenses",
+                coverage.noassertion_concluded_licenses,
+            ),
+            (
+                "NOASSERTION declared licenses",
+                coverage.noassertion_declared_licenses,
+            ),
+            (
+                "NOASSERTION copyright texts",
+                coverage.noassertion_copyright_texts,
+            ),
+        ] {
+            writeln!(f, "  {name}: {value:.1}%")?;
+        }
+
+        writeln!(f, "\nRelationship types:")?;
+        write_counts(f, &self.relationship_types)
+    }
+}
+
+fn write_counts(f: &mut fmt::Formatter<'_>, counts: &BTreeMap<String, usize>) -> fmt::Result {
+    for (key, count) in counts {
+        writeln!(f, "  {key}: {count}")?;
+    }
+    Ok(())
+}
+
+fn license_key(license: Option<&SpdxExpression>) -> String {
+    license.map_or_else(|| "NOASSERTION".to_string(), ToString::to_string)
+}
+
+fn is_noassertion(value: Option<&str>) -> bool {
+    value.is_none_or(SpecialValue::is_noassertion)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn counts() {
+        let spdx = example();
+        let summary = spdx.summary();
+
+        assert_eq!(summary.packages, spdx.package_information.len());
+        assert_eq!(summary.files, spdx.file_information.len());
+        assert_eq!(summary.snippets, spdx.snippet_information.len());
+        assert_eq!(summary.relationships, spdx.relationships.len());
+        assert_eq!(
+            summary.concluded_licenses.values().sum::<usize>(),
+            summary.packages + summary.files
+        );
+        assert_eq!(
+            summary.declared_licenses.values().sum::<usize>(),
+            summary.packages
+        );
+        assert_eq!(
+            summary.relationship_types.values().sum::<usize>(),
+            summary.relationships
+        );
+        assert_eq!(summary.concluded_licenses.get("MPL-1.0"), Some(&1));
+        assert!(summary.relationship_types.contains_key("CONTAINS"));
+    }
+
+    #[test]
+    fn coverage() {
+        let summary = example().summary();
+        let coverage = &summary.coverage;
+        for value in [
+            coverage.files_with_checksums,
+            coverage.packages_with_purls,
+            coverage.packages_with_suppliers,
+            coverage.noassertion_concluded_licenses,
+            coverage.noassertion_declared_licenses,
+            coverage.noassertion_copyright_texts,
+        ] {
+            assert!((0.0..=100.0).contains(&value));
+        }
+        assert!((coverage.files_with_checksums - 100.0).abs() < f64::EPSILON);
+
+        assert_eq!(SPDX::new("empty").summary().coverage, Coverage::default());
+    }
+
+    #[test]
+    fn display_and_json() {
+        let summary = example().summary();
+        let text = summary.to_string();
+        assert!(text.contains(&format!("Packages: {}\n", summary.packages)));
+        assert!(text.contains("  MPL-1.0: 1\n"));
+        assert!(text.contains("  Files with checksums: 100.0%\n"));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["packages"], summary.packages);
+        assert_eq!(json["concludedLicenses"]["MPL-1.0"], 1);
+        assert!(json["coverage"]["filesWithChecksums"].is_number());
+    }
+}
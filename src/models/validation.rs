@@ -0,0 +1,294 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use serde::Serialize;
+
+use super::{RelationshipType, SPDX};
+use crate::parallel;
+
+/// A problem found by [`SPDX::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationError {
+    /// SPDX ID of the element with the problem.
+    pub spdx_id: String,
+
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(spdx_id: &str, message: impl Into<String>) -> Self {
+        Self {
+            spdx_id: spdx_id.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.spdx_id, self.message)
+    }
+}
+
+/// Check whether the value is a valid SPDX ID, `SPDXRef-` followed by letters, numbers, `.` and
+/// `-`.
+pub fn is_valid_spdx_id(value: &str) -> bool {
+    value.strip_prefix("SPDXRef-").is_some_and(|id| {
+        !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElementKind {
+    Document,
+    Package,
+    File,
+    Snippet,
+}
+
+impl SPDX {
+    /// Check the structure of the document: the SPDX IDs must be valid and unique, and every
+    /// reference to an element must point to an element of the document or to an external
+    /// document.
+    ///
+    /// The license expressions are not checked, they are validated when they are parsed.
+    ///
+    /// With the `rayon` feature the elements are checked in parallel. The errors are in the same
+    /// order either way.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let document = &self.document_creation_information;
+        let document_id = document.spdx_identifier.as_str();
+        let mut errors = Vec::new();
+
+        self.validate_document_information(&mut errors);
+
+        let elements = self.elements(&mut errors);
+        let external_ids = document
+            .external_document_references
+            .iter()
+            .map(|reference| reference.id_string.as_str())
+            .collect::<HashSet<_>>();
+        let exists = |id: &str| {
+            elements.contains_key(id)
+                || matches!(id, "NONE" | "NOASSERTION")
+                || id
+                    .split_once(':')
+                    .is_some_and(|(document, _)| external_ids.contains(document))
+        };
+
+        for described in &document.document_describes {
+            if !exists(described) {
+                errors.push(ValidationError::new(
+                    document_id,
+                    format!("described element {described} doesn't exist"),
+                ));
+            }
+        }
+        let describes_anything = !document.document_describes.is_empty()
+            || self
+                .relationships_for_spdx_id(document_id)
+                .iter()
+                .any(|relationship| relationship.relationship_type == RelationshipType::Describes);
+        if !describes_anything && !self.package_information.is_empty() {
+            errors.push(ValidationError::new(
+                document_id,
+                "document doesn't describe any element",
+            ));
+        }
+
+        errors.extend(parallel::flat_map(&self.package_information, |package| {
+            let id = package.package_spdx_identifier.as_str();
+            let mut errors = Vec::new();
+            if package.package_download_location.is_empty() {
+                errors.push(ValidationError::new(id, "download location is empty"));
+            }
+            if package.files_analyzed == Some(false) && package.package_verification_code.is_some()
+            {
+                errors.push(ValidationError::new(
+                    id,
+                    "package with files not analyzed has a verification code",
+                ));
+            }
+            for file in &package.files {
+                if elements.get(file.as_str()) != Some(&ElementKind::File) {
+                    errors.push(ValidationError::new(
+                        id,
+                        format!("file {file} of the package doesn't exist"),
+                    ));
+                }
+            }
+            errors
+        }));
+
+        errors.extend(parallel::flat_map(&self.snippet_information, |snippet| {
+            let file = &snippet.snippet_from_file_spdx_identifier;
+            if elements.get(file.as_str()) == Some(&ElementKind::File) {
+                Vec::new()
+            } else {
+                vec![ValidationError::new(
+                    &snippet.snippet_spdx_identifier,
+                    format!("file {file} of the snippet doesn't exist"),
+                )]
+            }
+        }));
+
+        errors.extend(parallel::flat_map(&self.relationships, |relationship| {
+            [
+                &relationship.spdx_element_id,
+                &relationship.related_spdx_element,
+            ]
+            .iter()
+            .filter(|id| !exists(id))
+            .map(|id| {
+                ValidationError::new(
+                    &relationship.spdx_element_id,
+                    format!(
+                        "element {id} of relationship {} doesn't exist",
+                        relationship.relationship_type.as_ref()
+                    ),
+                )
+            })
+            .collect()
+        }));
+
+        errors
+    }
+
+    /// Check the document creation information.
+    fn validate_document_information(&self, errors: &mut Vec<ValidationError>) {
+        let document = &self.document_creation_information;
+        let document_id = document.spdx_identifier.as_str();
+
+        if document_id != "SPDXRef-DOCUMENT" {
+            errors.push(ValidationError::new(
+                document_id,
+                "document SPDX ID must be SPDXRef-DOCUMENT",
+            ));
+        }
+        if document.document_name.is_empty() {
+            errors.push(ValidationError::new(document_id, "document name is empty"));
+        }
+        if document.spdx_document_namespace.is_empty()
+            || document.spdx_document_namespace.contains('#')
+        {
+            errors.push(ValidationError::new(
+                document_id,
+                "document namespace must be a URI without a fragment",
+            ));
+        }
+        if document.creation_info.creators.is_empty() {
+            errors.push(ValidationError::new(
+                document_id,
+                "document has no creators",
+            ));
+        }
+    }
+
+    /// The SPDX IDs of the document, packages, files and snippets. Invalid and duplicate IDs are
+    /// added to `errors`.
+    fn elements(&self, errors: &mut Vec<ValidationError>) -> HashMap<&str, ElementKind> {
+        let ids = std::iter::once((
+            self.document_creation_information.spdx_identifier.as_str(),
+            ElementKind::Document,
+        ))
+        .chain(self.package_information.iter().map(|package| {
+            (
+                package.package_spdx_identifier.as_str(),
+                ElementKind::Package,
+            )
+        }))
+        .chain(
+            self.file_information
+                .iter()
+                .map(|file| (file.file_spdx_identifier.as_str(), ElementKind::File)),
+        )
+        .chain(self.snippet_information.iter().map(|snippet| {
+            (
+                snippet.snippet_spdx_identifier.as_str(),
+                ElementKind::Snippet,
+            )
+        }));
+
+        let mut elements = HashMap::new();
+        for (id, kind) in ids {
+            if kind != ElementKind::Document && !is_valid_spdx_id(id) {
+                errors.push(ValidationError::new(id, "invalid SPDX ID"));
+            }
+            if elements.insert(id, kind).is_some() {
+                errors.push(ValidationError::new(id, "duplicate SPDX ID"));
+            }
+        }
+        elements
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::models::{FileInformation, Relationship};
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn example_document_is_valid() {
+        assert_eq!(example().validate(), Vec::new());
+    }
+
+    #[test]
+    fn spdx_ids_are_validated() {
+        assert!(is_valid_spdx_id("SPDXRef-Package-1.0"));
+        assert!(!is_valid_spdx_id("SPDXRef-"));
+        assert!(!is_valid_spdx_id("SPDXRef-a_b"));
+        assert!(!is_valid_spdx_id("Package"));
+    }
+
+    #[test]
+    fn duplicate_ids_are_found() {
+        let mut spdx = example();
+        let mut file = FileInformation::new("./duplicate", &mut 0);
+        file.file_spdx_identifier = "SPDXRef-Saxon".to_string();
+        spdx.file_information.push(file);
+
+        assert_eq!(
+            spdx.validate(),
+            vec![ValidationError::new("SPDXRef-Saxon", "duplicate SPDX ID")]
+        );
+    }
+
+    #[test]
+    fn missing_elements_are_found() {
+        let mut spdx = example();
+        spdx.relationships.push(Relationship::new(
+            "SPDXRef-Saxon",
+            "SPDXRef-Missing",
+            RelationshipType::DependsOn,
+            None,
+        ));
+        spdx.package_information[0]
+            .files
+            .push("SPDXRef-Saxon".to_string());
+
+        let errors = spdx.validate();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].message.contains("SPDXRef-Saxon of the package"));
+        assert_eq!(errors[1].spdx_id, "SPDXRef-Saxon");
+        assert!(errors[1].message.contains("SPDXRef-Missing"));
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use log::info;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+
+use super::{Algorithm, Checksum, FileInformation, ValidationError, SPDX};
+use crate::parallel;
+
+impl Checksum {
+    /// Calculate the checksum of the file at the path. Returns `None` if the algorithm is not
+    /// supported. SHA1, SHA224, SHA256, SHA384, SHA512 and MD5 are supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the file can't be read.
+    pub fn calculate(algorithm: Algorithm, path: &Path) -> io::Result<Option<Self>> {
+        let mut file = File::open(path)?;
+        let value = match algorithm {
+            Algorithm::SHA1 => digest::<Sha1>(&mut file)?,
+            Algorithm::SHA224 => digest::<Sha224>(&mut file)?,
+            Algorithm::SHA256 => digest::<Sha256>(&mut file)?,
+            Algorithm::SHA384 => digest::<Sha384>(&mut file)?,
+            Algorithm::SHA512 => digest::<Sha512>(&mut file)?,
+            Algorithm::MD5 => digest::<Md5>(&mut file)?,
+            _ => return Ok(None),
+        };
+        Ok(Some(Self::new(algorithm, &value)))
+    }
+}
+
+fn digest<D: Digest + io::Write>(reader: &mut impl Read) -> io::Result<String> {
+    let mut hasher = D::new();
+    io::copy(reader, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .fold(String::new(), |mut value, byte| {
+            let _ = write!(value, "{byte:02x}");
+            value
+        }))
+}
+
+impl SPDX {
+    /// Check the checksums of the files of the document against the files on disk. The file
+    /// names are resolved relative to `root`. Checksums calculated with algorithms not supported
+    /// by [`Checksum::calculate`] are skipped.
+    ///
+    /// With the `rayon` feature the files are read in parallel. The errors are in the order of
+    /// the files either way.
+    pub fn verify_checksums(&self, root: &Path) -> Vec<ValidationError> {
+        info!("Verifying checksums of files in SPDX.");
+
+        parallel::flat_map(&self.file_information, |file| verify_file(file, root))
+    }
+}
+
+fn verify_file(file: &FileInformation, root: &Path) -> Vec<ValidationError> {
+    let path = root.join(&file.file_name);
+    let mut errors = Vec::new();
+    for expected in &file.file_checksum {
+        match Checksum::calculate(expected.algorithm, &path) {
+            Ok(Some(actual)) if !actual.value.eq_ignore_ascii_case(&expected.value) => {
+                errors.push(ValidationError {
+                    spdx_id: file.file_spdx_identifier.clone(),
+                    message: format!(
+                        "{:?} checksum of {} doesn't match the file on disk",
+                        expected.algorithm, file.file_name
+                    ),
+                });
+            }
+            Ok(_) => {}
+            Err(error) => {
+                errors.push(ValidationError {
+                    spdx_id: file.file_spdx_identifier.clone(),
+                    message: format!("{} can't be read: {error}", file.file_name),
+                });
+                break;
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checksums_are_calculated() {
+        let path = Path::new("tests/data/SPDXTagExample-v2.2.spdx");
+        let sha1 = Checksum::calculate(Algorithm::SHA1, path).unwrap().unwrap();
+        let sha256 = Checksum::calculate(Algorithm::SHA256, path)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sha1.value.len(), 40);
+        assert_eq!(sha256.value.len(), 64);
+        assert_eq!(Checksum::calculate(Algorithm::MD2, path).unwrap(), None);
+        assert!(Checksum::calculate(Algorithm::SHA1, Path::new("missing")).is_err());
+    }
+
+    #[test]
+    fn checksums_are_verified() {
+        let path = Path::new("tests/data/SPDXTagExample-v2.2.spdx");
+        let mut spdx = SPDX::new("verify");
+        let mut file = FileInformation::new("./SPDXTagExample-v2.2.spdx", &mut 0);
+        file.file_checksum = vec![
+            Checksum::calculate(Algorithm::SHA1, path).unwrap().unwrap(),
+            Checksum::new(Algorithm::MD2, "skipped"),
+        ];
+        spdx.file_information.push(file.clone());
+        assert!(spdx.verify_checksums(Path::new("tests/data")).is_empty());
+
+        file.file_checksum[0].value = "0".repeat(40);
+        file.file_spdx_identifier = "SPDXRef-2".to_string();
+        spdx.file_information.push(file.clone());
+        file.file_name = "./missing".to_string();
+        file.file_spdx_identifier = "SPDXRef-3".to_string();
+        spdx.file_information.push(file);
+
+        let errors = spdx.verify_checksums(Path::new("tests/data"));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].spdx_id, "SPDXRef-2");
+        assert!(errors[0].message.contains("SHA1"));
+        assert_eq!(errors[1].spdx_id, "SPDXRef-3");
+        assert!(errors[1].message.contains("can't be read"));
+    }
+}
@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Helpers for processing elements across threads with the `rayon` feature and sequentially
+//! without it. The results are always in the order of the input.
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Map each item.
+#[cfg(feature = "rayon")]
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    items.par_iter().map(f).collect()
+}
+
+/// Map each item.
+#[cfg(not(feature = "rayon"))]
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    items.iter().map(f).collect()
+}
+
+/// Map each item by value.
+#[cfg(feature = "rayon")]
+pub fn map_owned<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    items.into_par_iter().map(f).collect()
+}
+
+/// Map each item by value.
+#[cfg(not(feature = "rayon"))]
+pub fn map_owned<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    items.into_iter().map(f).collect()
+}
+
+/// Map each item to any number of results and concatenate them.
+pub fn flat_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> Vec<R> + Sync + Send,
+{
+    map(items, f).into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn results_are_in_input_order() {
+        let items = (0..10_000).collect::<Vec<_>>();
+        assert_eq!(map(&items, |item| item * 2)[9_999], 19_998);
+        assert_eq!(map_owned(items.clone(), |item| item + 1)[0], 1);
+        assert_eq!(flat_map(&items, |item| vec![*item; 2])[..4], [0, 0, 1, 1]);
+    }
+}
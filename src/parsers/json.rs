@@ -0,0 +1,426 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{fmt, io::Read, marker::PhantomData};
+
+use log::info;
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess},
+    Deserializer,
+};
+use serde_json::{Map, Value};
+
+use super::SpdxVisitor;
+use crate::{
+    error::SpdxError,
+    models::{Annotation, OtherLicensingInformationDetected, SPDX},
+    parallel,
+};
+
+/// Parse a JSON SPDX document.
+///
+/// The result is the same as with `serde_json::from_str`, but the packages, files, snippets and
+/// relationships are deserialized separately. With the `rayon` feature they are deserialized in
+/// parallel, which makes parsing documents with a large number of elements faster, as parsing
+/// the license expressions of the elements is relatively slow.
+///
+/// # Errors
+///
+/// Returns [`SpdxError::Json`] if the input is not a valid SPDX document.
+pub fn spdx_from_json(input: &str) -> Result<SPDX, SpdxError> {
+    info!("Parsing SPDX from JSON.");
+    let mut fields: Map<String, Value> = serde_json::from_str(input)?;
+    let packages = take_elements(&mut fields, "packages");
+    let files = take_elements(&mut fields, "files");
+    let snippets = take_elements(&mut fields, "snippets");
+    let relationships = take_elements(&mut fields, "relationships");
+
+    let mut spdx: SPDX = serde_json::from_value(Value::Object(fields))?;
+    spdx.package_information = deserialize_elements(packages)?;
+    spdx.file_information = deserialize_elements(files)?;
+    spdx.snippet_information = deserialize_elements(snippets)?;
+    spdx.relationships = deserialize_elements(relationships)?;
+    Ok(spdx)
+}
+
+/// Remove the array from the fields. Values that are not arrays are left for the deserializer to
+/// reject.
+fn take_elements(fields: &mut Map<String, Value>, key: &str) -> Vec<Value> {
+    match fields.remove(key) {
+        Some(Value::Array(elements)) => elements,
+        Some(value) => {
+            fields.insert(key.to_string(), value);
+            Vec::new()
+        }
+        None => Vec::new(),
+    }
+}
+
+fn deserialize_elements<T: DeserializeOwned + Send>(
+    elements: Vec<Value>,
+) -> Result<Vec<T>, SpdxError> {
+    let elements = parallel::map_owned(elements, serde_json::from_value)
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+    Ok(elements)
+}
+
+/// Parse a JSON SPDX document from a reader without keeping the whole document in memory.
+///
+/// The parts of the document are passed to the visitor as soon as they are read. Packages,
+/// files, snippets and relationships are never collected, so documents with millions of
+/// elements can be processed in constant memory. The fields of the document creation
+/// information must therefore appear before the `packages`, `files`, `snippets` and
+/// `relationships` arrays, as they do in documents written by this crate. Other licensing
+/// information and annotations that appear before the end of the document creation information
+/// are kept until it has been visited.
+///
+/// The reader is not buffered, so wrap files in a [`std::io::BufReader`].
+///
+/// # Usage
+///
+/// ```
+/// # use spdx_rs::error::SpdxError;
+/// use std::{fs::File, io::BufReader};
+///
+/// use spdx_rs::{models::FileInformation, parsers::{visit_spdx_json, SpdxVisitor}};
+/// # fn main() -> Result<(), SpdxError> {
+///
+/// #[derive(Default)]
+/// struct FileCounter(usize);
+///
+/// impl SpdxVisitor for FileCounter {
+///     fn file(&mut self, _: FileInformation) -> Result<(), SpdxError> {
+///         self.0 += 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let reader = BufReader::new(File::open("tests/data/SPDXJSONExample-v2.3.spdx.json")?);
+/// let mut counter = FileCounter::default();
+/// visit_spdx_json(reader, &mut counter)?;
+///
+/// assert_eq!(counter.0, 5);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SpdxError::Json`] if the input is not a valid SPDX document, or if a field of the
+/// document creation information appears after the elements. Errors returned by the visitor
+/// are passed through.
+pub fn visit_spdx_json<R: Read, V: SpdxVisitor>(
+    reader: R,
+    visitor: &mut V,
+) -> Result<(), SpdxError> {
+    info!("Streaming SPDX from JSON.");
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let mut stream = Stream {
+        visitor,
+        error: None,
+        document_creation_information: Some(Map::new()),
+        other_licensing_information_detected: Vec::new(),
+        annotations: Vec::new(),
+    };
+
+    let result = deserializer.deserialize_map(DocumentVisitor {
+        stream: &mut stream,
+    });
+    if let Some(error) = stream.error {
+        return Err(error);
+    }
+    result?;
+    deserializer.end()?;
+    Ok(())
+}
+
+/// State of a streamed document.
+struct Stream<'a, V> {
+    visitor: &'a mut V,
+
+    /// Error returned by the visitor.
+    error: Option<SpdxError>,
+
+    /// Fields of the document creation information, `None` after it has been visited.
+    document_creation_information: Option<Map<String, Value>>,
+
+    /// Parts read before the document creation information was visited.
+    other_licensing_information_detected: Vec<OtherLicensingInformationDetected>,
+    annotations: Vec<Annotation>,
+}
+
+impl<V: SpdxVisitor> Stream<'_, V> {
+    /// Pass a part of the document to the visitor. Returns `false` if the visitor failed.
+    fn visit(&mut self, visit: impl FnOnce(&mut V) -> Result<(), SpdxError>) -> bool {
+        match visit(self.visitor) {
+            Ok(()) => true,
+            Err(error) => {
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+
+    /// Visit the document creation information and the parts read before it, if not done yet.
+    fn finish_document_creation_information<E: de::Error>(&mut self) -> Result<(), E> {
+        let Some(fields) = self.document_creation_information.take() else {
+            return Ok(());
+        };
+        // Deserializing the fields as a document rejects unknown fields like the whole document
+        // would.
+        let spdx: SPDX = serde_json::from_value(Value::Object(fields)).map_err(E::custom)?;
+        let mut ok = self.visit(|visitor| {
+            visitor.document_creation_information(spdx.document_creation_information)
+        });
+        for license in std::mem::take(&mut self.other_licensing_information_detected) {
+            ok = ok && self.visit(|visitor| visitor.other_licensing_information_detected(license));
+        }
+        for annotation in std::mem::take(&mut self.annotations) {
+            ok = ok && self.visit(|visitor| visitor.annotation(annotation));
+        }
+        if ok {
+            Ok(())
+        } else {
+            Err(visitor_failed())
+        }
+    }
+}
+
+fn visitor_failed<E: de::Error>() -> E {
+    E::custom("the visitor returned an error")
+}
+
+/// Reads the top-level object of the document.
+struct DocumentVisitor<'s, 'a, V> {
+    stream: &'s mut Stream<'a, V>,
+}
+
+impl<'de, V: SpdxVisitor> de::Visitor<'de> for DocumentVisitor<'_, '_, V> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an SPDX document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let stream = self.stream;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "packages" => {
+                    stream.finish_document_creation_information()?;
+                    map.next_value_seed(ElementsSeed::new(|package| {
+                        stream.visit(|visitor| visitor.package(package))
+                    }))?;
+                }
+                "files" => {
+                    stream.finish_document_creation_information()?;
+                    map.next_value_seed(ElementsSeed::new(|file| {
+                        stream.visit(|visitor| visitor.file(file))
+                    }))?;
+                }
+                "snippets" => {
+                    stream.finish_document_creation_information()?;
+                    map.next_value_seed(ElementsSeed::new(|snippet| {
+                        stream.visit(|visitor| visitor.snippet(snippet))
+                    }))?;
+                }
+                "relationships" => {
+                    stream.finish_document_creation_information()?;
+                    map.next_value_seed(ElementsSeed::new(|relationship| {
+                        stream.visit(|visitor| visitor.relationship(relationship))
+                    }))?;
+                }
+                "hasExtractedLicensingInfos" if stream.document_creation_information.is_some() => {
+                    let mut licenses = map.next_value()?;
+                    stream
+                        .other_licensing_information_detected
+                        .append(&mut licenses);
+                }
+                "hasExtractedLicensingInfos" => {
+                    map.next_value_seed(ElementsSeed::new(|license| {
+                        stream
+                            .visit(|visitor| visitor.other_licensing_information_detected(license))
+                    }))?;
+                }
+                "annotations" if stream.document_creation_information.is_some() => {
+                    let mut annotations = map.next_value()?;
+                    stream.annotations.append(&mut annotations);
+                }
+                "annotations" => {
+                    map.next_value_seed(ElementsSeed::new(|annotation| {
+                        stream.visit(|visitor| visitor.annotation(annotation))
+                    }))?;
+                }
+                _ => match stream.document_creation_information.as_mut() {
+                    Some(fields) => {
+                        fields.insert(key, map.next_value()?);
+                    }
+                    None => {
+                        return Err(de::Error::custom(format!(
+                            "`{key}` must appear before packages, files, snippets and \
+                             relationships in a streamed document"
+                        )))
+                    }
+                },
+            }
+        }
+        stream.finish_document_creation_information()
+    }
+}
+
+/// Reads an array and passes each element to a function instead of collecting them.
+struct ElementsSeed<T, F> {
+    visit: F,
+    element: PhantomData<T>,
+}
+
+impl<T, F: FnMut(T) -> bool> ElementsSeed<T, F> {
+    const fn new(visit: F) -> Self {
+        Self {
+            visit,
+            element: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned, F: FnMut(T) -> bool> DeserializeSeed<'de> for ElementsSeed<T, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: DeserializeOwned, F: FnMut(T) -> bool> de::Visitor<'de> for ElementsSeed<T, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(element) = seq.next_element()? {
+            if !(self.visit)(element) {
+                return Err(visitor_failed());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{read_to_string, File};
+
+    use super::*;
+    use crate::models::{DocumentCreationInformation, FileInformation, PackageInformation};
+
+    const EXAMPLE: &str = "tests/data/SPDXJSONExample-v2.3.spdx.json";
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string(EXAMPLE).unwrap()).unwrap()
+    }
+
+    /// Records the order the parts are visited in.
+    #[derive(Default)]
+    struct Events(Vec<&'static str>);
+
+    impl SpdxVisitor for Events {
+        fn document_creation_information(
+            &mut self,
+            _: DocumentCreationInformation,
+        ) -> Result<(), SpdxError> {
+            self.0.push("document");
+            Ok(())
+        }
+
+        fn package(&mut self, _: PackageInformation) -> Result<(), SpdxError> {
+            self.0.push("package");
+            Ok(())
+        }
+
+        fn file(&mut self, _: FileInformation) -> Result<(), SpdxError> {
+            if self.0.contains(&"file") {
+                return Err(SpdxError::Import("second file".to_string()));
+            }
+            self.0.push("file");
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn streamed_document_equals_deserialized_document() {
+        let mut spdx = SPDX::new("");
+        visit_spdx_json(File::open(EXAMPLE).unwrap(), &mut spdx).unwrap();
+        assert_eq!(spdx, example());
+
+        let mut written = SPDX::new("");
+        visit_spdx_json(
+            serde_json::to_string(&example()).unwrap().as_bytes(),
+            &mut written,
+        )
+        .unwrap();
+        assert_eq!(written, example());
+    }
+
+    #[test]
+    fn document_creation_information_is_visited_first() {
+        let mut events = Events::default();
+        let mut spdx = example();
+        spdx.file_information.clear();
+        visit_spdx_json(
+            serde_json::to_string(&spdx).unwrap().as_bytes(),
+            &mut events,
+        )
+        .unwrap();
+        assert_eq!(events.0[0], "document");
+        assert_eq!(
+            events.0.iter().filter(|event| **event == "package").count(),
+            4
+        );
+    }
+
+    #[test]
+    fn visitor_errors_are_returned() {
+        let mut events = Events::default();
+        let result = visit_spdx_json(File::open(EXAMPLE).unwrap(), &mut events);
+        assert!(matches!(result, Err(SpdxError::Import(_))));
+    }
+
+    #[test]
+    fn parsed_document_equals_deserialized_document() {
+        assert_eq!(
+            spdx_from_json(&read_to_string(EXAMPLE).unwrap()).unwrap(),
+            example()
+        );
+
+        let invalid_package = read_to_string(EXAMPLE).unwrap().replacen(
+            "\"SPDXID\" : \"SPDXRef-Package\"",
+            "\"SPDXID\" : 1",
+            1,
+        );
+        let not_array = r#"{"packages": {}}"#;
+        for input in [&invalid_package, not_array, "[]"] {
+            assert!(spdx_from_json(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn invalid_documents_are_rejected() {
+        let late_field = r#"{"packages": [], "spdxVersion": "SPDX-2.3"}"#;
+        let unknown_field = read_to_string(EXAMPLE).unwrap().replacen(
+            "\"SPDXID\"",
+            "\"unknown\": 1, \"SPDXID\"",
+            1,
+        );
+        for input in [late_field, &unknown_field, "[]", "{} {}"] {
+            assert!(
+                visit_spdx_json(input.as_bytes(), &mut SPDX::new("")).is_err(),
+                "{}",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,293 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use log::{info, warn};
+use serde::{
+    de::{self, value, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer, Serialize,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::SpdxError,
+    models::{
+        Annotation, DocumentCreationInformation, FileInformation,
+        OtherLicensingInformationDetected, PackageInformation, Relationship, Snippet, SPDX,
+    },
+};
+
+/// A field not defined by the spec, found by [`spdx_from_json_lenient`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownField {
+    /// Location of the element with the field, `document` or e.g. `packages[2]`.
+    pub path: String,
+
+    /// Name of the field.
+    pub field: String,
+}
+
+impl fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown field `{}` in {}", self.field, self.path)
+    }
+}
+
+/// The arrays of elements in the document.
+const ELEMENT_ARRAYS: [&str; 6] = [
+    "packages",
+    "hasExtractedLicensingInfos",
+    "files",
+    "snippets",
+    "relationships",
+    "annotations",
+];
+
+/// Parse a JSON SPDX document, keeping the fields not defined by the spec.
+///
+/// Deserializing [`SPDX`] with Serde rejects documents with unknown fields in the document or in
+/// packages, which is the right thing to do for conformance checks, but makes documents with
+/// producer extensions or fields from newer spec versions unreadable. This function instead moves
+/// the unknown fields of the document and of its packages, files, snippets, relationships,
+/// annotations and other licensing information to their `extensions`, from where they are
+/// written back when the document is serialized. The unknown fields are returned and logged as
+/// warnings. Unknown fields of nested objects, like checksums, are ignored. The tag-value parsers
+/// keep unknown tags in the same `extensions`.
+///
+/// # Usage
+///
+/// ```
+/// # use spdx_rs::error::SpdxError;
+/// use spdx_rs::parsers::spdx_from_json_lenient;
+/// # fn main() -> Result<(), SpdxError> {
+///
+/// let input = std::fs::read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json")?
+///     .replacen("\"SPDXID\"", "\"producerField\": true, \"SPDXID\"", 1);
+/// assert!(serde_json::from_str::<spdx_rs::models::SPDX>(&input).is_err());
+///
+/// let (spdx, unknown_fields) = spdx_from_json_lenient(&input)?;
+/// assert_eq!(unknown_fields[0].to_string(), "unknown field `producerField` in document");
+/// assert!(serde_json::to_string(&spdx)?.contains("\"producerField\":true"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SpdxError::Json`] if the input is not a valid SPDX document even without the unknown
+/// fields.
+pub fn spdx_from_json_lenient(input: &str) -> Result<(SPDX, Vec<UnknownField>), SpdxError> {
+    info!("Parsing SPDX from JSON leniently.");
+    let mut document: Map<String, Value> = serde_json::from_str(input)?;
+    let mut unknown_fields = Vec::new();
+
+    let mut known = field_names::<DocumentCreationInformation>();
+    known.extend(ELEMENT_ARRAYS);
+    let extensions = take_unknown_fields(&mut document, &known, "document", &mut unknown_fields);
+
+    let mut elements =
+        |key, known| element_extensions(&mut document, key, &known, &mut unknown_fields);
+    let packages = elements("packages", field_names::<PackageInformation>());
+    let licenses = elements(
+        "hasExtractedLicensingInfos",
+        field_names::<OtherLicensingInformationDetected>(),
+    );
+    let files = elements("files", field_names::<FileInformation>());
+    let snippets = elements("snippets", field_names::<Snippet>());
+    let relationships = elements("relationships", field_names::<Relationship>());
+    let annotations = elements("annotations", field_names::<Annotation>());
+
+    let mut spdx: SPDX = serde_json::from_value(Value::Object(document))?;
+    spdx.document_creation_information.extensions = extensions;
+    for (package, extensions) in spdx.package_information.iter_mut().zip(packages) {
+        package.extensions = extensions;
+    }
+    for (license, extensions) in spdx
+        .other_licensing_information_detected
+        .iter_mut()
+        .zip(licenses)
+    {
+        license.extensions = extensions;
+    }
+    for (file, extensions) in spdx.file_information.iter_mut().zip(files) {
+        file.extensions = extensions;
+    }
+    for (snippet, extensions) in spdx.snippet_information.iter_mut().zip(snippets) {
+        snippet.extensions = extensions;
+    }
+    for (relationship, extensions) in spdx.relationships.iter_mut().zip(relationships) {
+        relationship.extensions = extensions;
+    }
+    for (annotation, extensions) in spdx.annotations.iter_mut().zip(annotations) {
+        annotation.extensions = extensions;
+    }
+
+    Ok((spdx, unknown_fields))
+}
+
+/// Take the unknown fields of each element in the array of the document.
+fn element_extensions(
+    document: &mut Map<String, Value>,
+    key: &str,
+    known: &HashSet<&str>,
+    unknown_fields: &mut Vec<UnknownField>,
+) -> Vec<BTreeMap<String, Value>> {
+    let Some(Value::Array(elements)) = document.get_mut(key) else {
+        return Vec::new();
+    };
+    elements
+        .iter_mut()
+        .enumerate()
+        .map(|(index, element)| match element {
+            Value::Object(fields) => {
+                let path = format!("{key}[{index}]");
+                take_unknown_fields(fields, known, &path, unknown_fields)
+            }
+            _ => BTreeMap::new(),
+        })
+        .collect()
+}
+
+fn take_unknown_fields(
+    fields: &mut Map<String, Value>,
+    known: &HashSet<&str>,
+    path: &str,
+    unknown_fields: &mut Vec<UnknownField>,
+) -> BTreeMap<String, Value> {
+    let unknown = fields
+        .keys()
+        .filter(|field| !known.contains(field.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut extensions = BTreeMap::new();
+    for field in unknown {
+        let unknown_field = UnknownField {
+            path: path.to_string(),
+            field,
+        };
+        warn!("Keeping {unknown_field}.");
+        if let Some(value) = fields.remove(&unknown_field.field) {
+            extensions.insert(unknown_field.field.clone(), value);
+        }
+        unknown_fields.push(unknown_field);
+    }
+    extensions
+}
+
+/// Names of the fields of a struct, read from its [`Deserialize`] implementation.
+fn field_names<'de, T: Deserialize<'de>>() -> HashSet<&'static str> {
+    let mut fields = HashSet::new();
+    // Reading the names always ends in an error, as there's no value to deserialize.
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Deserializer that records the field names passed to `deserialize_struct`.
+struct FieldNames<'a>(&'a mut HashSet<&'static str>);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("only struct field names can be read"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.extend(fields);
+        Err(de::Error::custom("field names read"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    const EXAMPLE: &str = "tests/data/SPDXJSONExample-v2.3.spdx.json";
+
+    #[test]
+    fn strictly_parsed_fields_are_not_extensions() {
+        let input = read_to_string(EXAMPLE).unwrap();
+        let (mut spdx, unknown_fields) = spdx_from_json_lenient(&input).unwrap();
+
+        // Annotations of files are not supported by the model and are dropped by strict parsing.
+        assert_eq!(
+            unknown_fields,
+            [UnknownField {
+                path: "files[4]".to_string(),
+                field: "annotations".to_string(),
+            }]
+        );
+        spdx.file_information[4].extensions.clear();
+        assert_eq!(spdx, serde_json::from_str::<SPDX>(&input).unwrap());
+    }
+
+    #[test]
+    fn unknown_fields_are_kept() {
+        let mut document: Value = serde_json::from_str(&read_to_string(EXAMPLE).unwrap()).unwrap();
+        document["documentVersion"] = Value::from("3.0");
+        document["packages"][1]["x-producer"] = serde_json::json!({ "build": 7 });
+        document["files"][0]["fileKind"] = Value::from("source");
+        document["relationships"][2]["confidence"] = Value::from(0.9);
+        assert!(serde_json::from_value::<SPDX>(document.clone()).is_err());
+
+        let (spdx, unknown_fields) = spdx_from_json_lenient(&document.to_string()).unwrap();
+        assert_eq!(
+            unknown_fields
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "unknown field `documentVersion` in document",
+                "unknown field `x-producer` in packages[1]",
+                "unknown field `fileKind` in files[0]",
+                "unknown field `annotations` in files[4]",
+                "unknown field `confidence` in relationships[2]",
+            ]
+        );
+        assert_eq!(
+            spdx.package_information[1].extensions["x-producer"]["build"],
+            7
+        );
+
+        let written: Value = serde_json::to_value(&spdx).unwrap();
+        for pointer in [
+            "/documentVersion",
+            "/packages/1/x-producer",
+            "/files/0/fileKind",
+            "/files/4/annotations",
+            "/relationships/2/confidence",
+        ] {
+            assert_eq!(written.pointer(pointer), document.pointer(pointer));
+        }
+    }
+
+    #[test]
+    fn invalid_known_fields_are_rejected() {
+        let input = read_to_string(EXAMPLE).unwrap().replacen(
+            "\"SPDXID\" : \"SPDXRef-Package\"",
+            "\"SPDXID\" : 1",
+            1,
+        );
+        assert!(spdx_from_json_lenient(&input).is_err());
+    }
+}
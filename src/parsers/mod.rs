@@ -25,22 +25,34 @@
 //!
 //! [Serde]: https://serde.rs
 
-use std::collections::HashSet;
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashSet},
+    io::BufRead,
+};
 
 use chrono::{DateTime, Utc};
+use log::warn;
+use serde_json::Value;
 use spdx_expression::SpdxExpression;
 
 use crate::{
     error::SpdxError,
     models::{
-        Annotation, AnnotationType, DocumentCreationInformation, ExternalPackageReference,
-        FileInformation, OtherLicensingInformationDetected, PackageInformation, Pointer, Range,
-        Relationship, Snippet, SPDX,
+        Annotation, AnnotationType, CreationInfo, DocumentCreationInformation,
+        ExternalPackageReference, FileInformation, OtherLicensingInformationDetected,
+        PackageInformation, Pointer, Range, Relationship, Snippet, SPDX,
     },
-    parsers::tag_value::{atoms, Atom},
+    parsers::tag_value::{atoms, read_tag_value, Atom},
 };
 
+mod json;
+mod lenient;
 mod tag_value;
+mod visitor;
+
+pub use json::{spdx_from_json, visit_spdx_json};
+pub use lenient::{spdx_from_json_lenient, UnknownField};
+pub use visitor::SpdxVisitor;
 
 /// Parse a tag-value SPDX document to [`SPDX`].
 ///
@@ -74,101 +86,349 @@ pub fn spdx_from_tag_value(input: &str) -> Result<SPDX, SpdxError> {
     Ok(spdx)
 }
 
-#[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
+/// Parse a tag-value SPDX document from a reader without keeping the whole document in memory.
+///
+/// The reader is read line by line, and each package, file, snippet, other licensing
+/// information, relationship and annotation is passed to the visitor as soon as it is complete.
+/// The document creation information is visited first. Relationships are visited as they
+/// appear, so a relationship that is listed more than once is visited more than once.
+///
+/// # Usage
+///
+/// ```
+/// # use spdx_rs::error::SpdxError;
+/// use std::{fs::File, io::BufReader};
+///
+/// use spdx_rs::{models::PackageInformation, parsers::{visit_spdx_tag_value, SpdxVisitor}};
+/// # fn main() -> Result<(), SpdxError> {
+///
+/// #[derive(Default)]
+/// struct PackageNames(Vec<String>);
+///
+/// impl SpdxVisitor for PackageNames {
+///     fn package(&mut self, package: PackageInformation) -> Result<(), SpdxError> {
+///         self.0.push(package.package_name);
+///         Ok(())
+///     }
+/// }
+///
+/// let reader = BufReader::new(File::open("tests/data/SPDXTagExample-v2.2.spdx")?);
+/// let mut names = PackageNames::default();
+/// visit_spdx_tag_value(reader, &mut names)?;
+///
+/// assert_eq!(names.0[0], "glibc");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// - If reading from the reader fails.
+/// - If parsing of the tag-value fails.
+/// - If parsing of some of the values fail.
+/// - If the visitor returns an error.
+pub fn visit_spdx_tag_value<R: BufRead, V: SpdxVisitor>(
+    mut reader: R,
+    visitor: &mut V,
+) -> Result<(), SpdxError> {
+    let mut state = ParserState::default();
+    let mut buffer = String::new();
+    while read_tag_value(&mut reader, &mut buffer)? {
+        let (rest, atoms) =
+            atoms(&buffer).map_err(|err| SpdxError::TagValueParse(err.to_string()))?;
+        if !rest.trim().is_empty() {
+            return Err(SpdxError::TagValueParse(format!(
+                "invalid tag-value: {}",
+                rest.trim()
+            )));
+        }
+        for atom in &atoms {
+            state.process(atom, visitor)?;
+        }
+    }
+    state.finish(visitor)
+}
+
 fn spdx_from_atoms(atoms: &[Atom]) -> Result<SPDX, SpdxError> {
-    let mut document_creation_information_in_progress =
-        Some(DocumentCreationInformation::default());
-    let mut document_creation_information_final: Option<DocumentCreationInformation> = None;
+    let mut spdx = SPDX::new("");
+    let mut state = ParserState::default();
+    for atom in atoms {
+        state.process(atom, &mut spdx)?;
+    }
+    state.finish(&mut spdx)?;
+
+    // The relationships of files to their packages may also be listed explicitly.
+    let mut unique_relationships = HashSet::new();
+    spdx.relationships
+        .retain(|relationship| unique_relationships.insert(relationship.clone()));
 
-    let mut package_information: Vec<PackageInformation> = Vec::new();
-    let mut package_in_progress: Option<PackageInformation> = None;
-    let mut external_package_ref_in_progress: Option<ExternalPackageReference> = None;
+    Ok(spdx)
+}
 
-    let mut other_licensing_information_detected: Vec<OtherLicensingInformationDetected> =
-        Vec::new();
-    let mut license_info_in_progress: Option<OtherLicensingInformationDetected> = None;
+/// Parts of a tag-value document that are being parsed. Completed parts are passed to the
+/// visitor after each atom, so only the elements in progress are kept in memory.
+struct ParserState {
+    document_creation_information_in_progress: Option<DocumentCreationInformation>,
 
-    let mut file_information: Vec<FileInformation> = Vec::new();
-    let mut file_in_progress: Option<FileInformation> = None;
+    package_information: Vec<PackageInformation>,
+    package_in_progress: Option<PackageInformation>,
+    external_package_ref_in_progress: Option<ExternalPackageReference>,
 
-    let mut snippet_information: Vec<Snippet> = Vec::new();
-    let mut snippet_in_progress: Option<Snippet> = None;
+    other_licensing_information_detected: Vec<OtherLicensingInformationDetected>,
+    license_info_in_progress: Option<OtherLicensingInformationDetected>,
 
-    let mut relationships: HashSet<Relationship> = HashSet::new();
-    let mut relationship_in_progress: Option<Relationship> = None;
+    file_information: Vec<FileInformation>,
+    file_in_progress: Option<FileInformation>,
 
-    let mut annotations: Vec<Annotation> = Vec::new();
-    let mut annotation_in_progress = AnnotationInProgress::default();
+    snippet_information: Vec<Snippet>,
+    snippet_in_progress: Option<Snippet>,
+
+    relationships: Vec<Relationship>,
+    relationship_in_progress: Option<Relationship>,
+
+    annotations: Vec<Annotation>,
+    annotation_in_progress: AnnotationInProgress,
+    review_in_progress: Option<ReviewInProgress>,
+
+    /// Element the latest tags belong to. Unknown tags are added to its extensions.
+    current_element: Element,
+    document_id: String,
+}
+
+/// Kinds of elements in a tag-value document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Element {
+    Document,
+    Package,
+    File,
+    Snippet,
+    License,
+    Relationship,
+    Annotation,
+    Review,
+}
+
+impl Element {
+    /// The element started by the atom, if any.
+    const fn started_by(atom: &Atom) -> Option<Self> {
+        match atom {
+            Atom::PackageName(_) => Some(Self::Package),
+            Atom::FileName(_) => Some(Self::File),
+            Atom::SnippetSPDXID(_) => Some(Self::Snippet),
+            Atom::LicenseID(_) => Some(Self::License),
+            Atom::Relationship(_) => Some(Self::Relationship),
+            Atom::Annotator(_) => Some(Self::Annotation),
+            Atom::Reviewer(_) => Some(Self::Review),
+            _ => None,
+        }
+    }
+}
+
+impl Default for ParserState {
+    fn default() -> Self {
+        Self {
+            // The default creators are placeholders, only the creators of the document are kept.
+            document_creation_information_in_progress: Some(DocumentCreationInformation {
+                creation_info: CreationInfo {
+                    creators: Vec::new(),
+                    ..CreationInfo::default()
+                },
+                ..DocumentCreationInformation::default()
+            }),
+            package_information: Vec::new(),
+            package_in_progress: None,
+            external_package_ref_in_progress: None,
+            other_licensing_information_detected: Vec::new(),
+            license_info_in_progress: None,
+            file_information: Vec::new(),
+            file_in_progress: None,
+            snippet_information: Vec::new(),
+            snippet_in_progress: None,
+            relationships: Vec::new(),
+            relationship_in_progress: None,
+            annotations: Vec::new(),
+            annotation_in_progress: AnnotationInProgress::default(),
+            review_in_progress: None,
+            current_element: Element::Document,
+            document_id: String::new(),
+        }
+    }
+}
+
+impl ParserState {
+    fn process(&mut self, atom: &Atom, visitor: &mut impl SpdxVisitor) -> Result<(), SpdxError> {
+        if let Atom::Unknown(tag, value) = atom {
+            self.process_unknown(tag, value);
+            return Ok(());
+        }
+        if let Some(element) = Element::started_by(atom) {
+            self.current_element = element;
+        }
 
-    for atom in atoms {
         let document_creation_information = process_atom_for_document_creation_information(
             atom,
-            &mut document_creation_information_in_progress,
+            &mut self.document_creation_information_in_progress,
         )?;
         if let Some(document_creation_information) = document_creation_information {
-            document_creation_information_final = Some(document_creation_information);
-            document_creation_information_in_progress = None;
+            self.document_creation_information_in_progress = None;
+            self.document_id
+                .clone_from(&document_creation_information.spdx_identifier);
+            visitor.document_creation_information(document_creation_information)?;
         }
         process_atom_for_packages(
             atom,
-            &mut package_information,
-            &mut package_in_progress,
-            &mut external_package_ref_in_progress,
-        );
+            &mut self.package_information,
+            &mut self.package_in_progress,
+            &mut self.external_package_ref_in_progress,
+        )?;
         process_atom_for_files(
             atom,
-            &mut file_in_progress,
-            &mut file_information,
-            &package_in_progress,
-            &mut relationships,
+            &mut self.file_in_progress,
+            &mut self.file_information,
+            self.package_in_progress.as_ref(),
+            &mut self.relationships,
+        )?;
+        process_atom_for_snippets(
+            atom,
+            &mut self.snippet_information,
+            &mut self.snippet_in_progress,
+        )?;
+        process_atom_for_relationships(
+            atom,
+            &mut self.relationships,
+            &mut self.relationship_in_progress,
         );
-        process_atom_for_snippets(atom, &mut snippet_information, &mut snippet_in_progress);
-        process_atom_for_relationships(atom, &mut relationships, &mut relationship_in_progress);
-        process_atom_for_annotations(atom, &mut annotations, &mut annotation_in_progress)?;
+        process_atom_for_annotations(
+            atom,
+            &mut self.annotations,
+            &mut self.annotation_in_progress,
+        )?;
+        process_atom_for_reviews(
+            atom,
+            &mut self.annotations,
+            &mut self.review_in_progress,
+            &self.document_id,
+        )?;
         process_atom_for_license_info(
             atom,
-            &mut other_licensing_information_detected,
-            &mut license_info_in_progress,
+            &mut self.other_licensing_information_detected,
+            &mut self.license_info_in_progress,
         )?;
-    }
-    if let Some(file) = file_in_progress {
-        file_information.push(file);
-    }
-    if let Some(snippet) = &mut snippet_in_progress {
-        snippet_information.push(snippet.clone());
+        self.flush(visitor)
     }
 
-    if let Some(package) = package_in_progress {
-        package_information.push(package);
-    }
+    /// Complete the parts in progress at the end of the document.
+    fn finish(mut self, visitor: &mut impl SpdxVisitor) -> Result<(), SpdxError> {
+        if let Some(document_creation_information) =
+            self.document_creation_information_in_progress.take()
+        {
+            visitor.document_creation_information(document_creation_information)?;
+        }
 
-    if let Some(relationship) = relationship_in_progress {
-        relationships.insert(relationship);
-    }
+        if let Some(file) = self.file_in_progress.take() {
+            self.file_information.push(file);
+        }
+        if let Some(snippet) = self.snippet_in_progress.take() {
+            self.snippet_information.push(snippet);
+        }
+
+        if let Some(mut package) = self.package_in_progress.take() {
+            if let Some(pkg_ref) = self.external_package_ref_in_progress.take() {
+                package.external_reference.push(pkg_ref);
+            }
+            self.package_information.push(package);
+        }
+
+        if let Some(relationship) = self.relationship_in_progress.take() {
+            self.relationships.push(relationship);
+        }
 
-    if let Some(license_info) = license_info_in_progress {
-        other_licensing_information_detected.push(license_info);
+        if let Some(license_info) = self.license_info_in_progress.take() {
+            self.other_licensing_information_detected.push(license_info);
+        }
+
+        process_annotation(&mut self.annotation_in_progress, &mut self.annotations);
+        if let Some(review) = self.review_in_progress.take() {
+            process_review(review, &mut self.annotations, &self.document_id);
+        }
+
+        self.flush(visitor)
     }
 
-    if document_creation_information_in_progress.is_some() {
-        document_creation_information_final = document_creation_information_in_progress;
+    /// Add a tag not defined by the spec to the extensions of the current element. Repeated tags
+    /// are collected to an array.
+    fn process_unknown(&mut self, tag: &str, value: &str) {
+        let extensions = match self.current_element {
+            Element::Document => self
+                .document_creation_information_in_progress
+                .as_mut()
+                .map(|document| &mut document.extensions),
+            Element::Package => self
+                .package_in_progress
+                .as_mut()
+                .map(|package| &mut package.extensions),
+            Element::File => self
+                .file_in_progress
+                .as_mut()
+                .map(|file| &mut file.extensions),
+            Element::Snippet => self
+                .snippet_in_progress
+                .as_mut()
+                .map(|snippet| &mut snippet.extensions),
+            Element::License => self
+                .license_info_in_progress
+                .as_mut()
+                .map(|license| &mut license.extensions),
+            Element::Relationship => self
+                .relationship_in_progress
+                .as_mut()
+                .map(|relationship| &mut relationship.extensions),
+            Element::Annotation => Some(&mut self.annotation_in_progress.extensions),
+            Element::Review => self
+                .review_in_progress
+                .as_mut()
+                .map(|review| &mut review.extensions),
+        };
+        let Some(extensions) = extensions else {
+            warn!("Ignoring unknown tag {tag} outside of an element.");
+            return;
+        };
+
+        warn!("Keeping unknown tag {tag}.");
+        let value = Value::from(value);
+        match extensions.entry(tag.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                Value::Array(values) => values.push(value),
+                previous => *previous = Value::Array(vec![previous.take(), value]),
+            },
+        }
     }
 
-    process_annotation(&mut annotation_in_progress, &mut annotations);
-
-    Ok(SPDX {
-        document_creation_information: document_creation_information_final
-            // TODO: Proper error handling
-            .expect("If this doesn't exist, the document is not valid."),
-        package_information,
-        other_licensing_information_detected,
-        file_information,
-        snippet_information,
-        relationships: relationships.into_iter().collect(),
-        annotations,
-        // TODO: This should probably be removed.
-        spdx_ref_counter: 0,
-    })
+    /// Pass the completed parts to the visitor.
+    fn flush(&mut self, visitor: &mut impl SpdxVisitor) -> Result<(), SpdxError> {
+        for package in self.package_information.drain(..) {
+            visitor.package(package)?;
+        }
+        for file in self.file_information.drain(..) {
+            visitor.file(file)?;
+        }
+        for snippet in self.snippet_information.drain(..) {
+            visitor.snippet(snippet)?;
+        }
+        for license in self.other_licensing_information_detected.drain(..) {
+            visitor.other_licensing_information_detected(license)?;
+        }
+        for relationship in self.relationships.drain(..) {
+            visitor.relationship(relationship)?;
+        }
+        for annotation in self.annotations.drain(..) {
+            visitor.annotation(annotation)?;
+        }
+        Ok(())
+    }
 }
 
 fn process_atom_for_document_creation_information(
@@ -280,7 +540,7 @@ fn process_atom_for_packages(
     packages: &mut Vec<PackageInformation>,
     mut package_in_progress: &mut Option<PackageInformation>,
     mut external_package_ref_in_progress: &mut Option<ExternalPackageReference>,
-) {
+) -> Result<(), SpdxError> {
     match atom {
         Atom::PackageName(value) => {
             if let Some(package) = &mut package_in_progress {
@@ -350,7 +610,7 @@ fn process_atom_for_packages(
         }
         Atom::PackageLicenseConcluded(value) => {
             if let Some(package) = &mut package_in_progress {
-                package.concluded_license = Some(SpdxExpression::parse(value).unwrap());
+                package.concluded_license = Some(SpdxExpression::parse(value)?);
             }
         }
         Atom::PackageLicenseInfoFromFiles(value) => {
@@ -362,7 +622,7 @@ fn process_atom_for_packages(
         }
         Atom::PackageLicenseDeclared(value) => {
             if let Some(package) = &mut package_in_progress {
-                package.declared_license = Some(SpdxExpression::parse(value).unwrap());
+                package.declared_license = Some(SpdxExpression::parse(value)?);
             }
         }
         Atom::PackageLicenseComments(value) => {
@@ -405,15 +665,17 @@ fn process_atom_for_packages(
         }
         _ => {}
     }
+
+    Ok(())
 }
 
 fn process_atom_for_files(
     atom: &Atom,
     mut file_in_progress: &mut Option<FileInformation>,
     files: &mut Vec<FileInformation>,
-    package_in_progress: &Option<PackageInformation>,
-    relationships: &mut HashSet<Relationship>,
-) {
+    package_in_progress: Option<&PackageInformation>,
+    relationships: &mut Vec<Relationship>,
+) -> Result<(), SpdxError> {
     match atom {
         Atom::PackageName(_) => {
             if let Some(file) = &mut file_in_progress {
@@ -435,7 +697,7 @@ fn process_atom_for_files(
             if let Some(file) = &mut file_in_progress {
                 file.file_spdx_identifier = value.to_string();
                 if let Some(package) = package_in_progress {
-                    relationships.insert(Relationship::new(
+                    relationships.push(Relationship::new(
                         &package.package_spdx_identifier,
                         value,
                         crate::models::RelationshipType::Contains,
@@ -461,13 +723,13 @@ fn process_atom_for_files(
         }
         Atom::LicenseConcluded(value) => {
             if let Some(file) = &mut file_in_progress {
-                file.concluded_license = Some(SpdxExpression::parse(value).unwrap());
+                file.concluded_license = Some(SpdxExpression::parse(value)?);
             }
         }
         Atom::LicenseInfoInFile(value) => {
             if let Some(file) = &mut file_in_progress {
                 file.license_information_in_file
-                    .push(SpdxExpression::parse(value).unwrap());
+                    .push(SpdxExpression::parse(value)?);
             }
         }
         Atom::LicenseComments(value) => {
@@ -492,13 +754,15 @@ fn process_atom_for_files(
         }
         _ => {}
     }
+
+    Ok(())
 }
 
 fn process_atom_for_snippets(
     atom: &Atom,
     snippets: &mut Vec<Snippet>,
     mut snippet_in_progress: &mut Option<Snippet>,
-) {
+) -> Result<(), SpdxError> {
     match atom {
         Atom::SnippetSPDXID(value) => {
             if let Some(snippet) = &snippet_in_progress {
@@ -533,7 +797,7 @@ fn process_atom_for_snippets(
         }
         Atom::SnippetLicenseConcluded(value) => {
             if let Some(snippet) = &mut snippet_in_progress {
-                snippet.snippet_concluded_license = Some(SpdxExpression::parse(value).unwrap());
+                snippet.snippet_concluded_license = Some(SpdxExpression::parse(value)?);
             }
         }
         Atom::LicenseInfoInSnippet(value) => {
@@ -570,18 +834,20 @@ fn process_atom_for_snippets(
         }
         _ => {}
     }
+
+    Ok(())
 }
 
 #[allow(clippy::unnecessary_wraps)]
 fn process_atom_for_relationships(
     atom: &Atom,
-    relationships: &mut HashSet<Relationship>,
+    relationships: &mut Vec<Relationship>,
     mut relationship_in_progress: &mut Option<Relationship>,
 ) {
     match atom {
         Atom::Relationship(value) => {
             if let Some(relationship) = relationship_in_progress {
-                relationships.insert(relationship.clone());
+                relationships.push(relationship.clone());
             }
             *relationship_in_progress = Some(value.clone());
         }
@@ -601,6 +867,7 @@ struct AnnotationInProgress {
     comment_in_progress: Option<String>,
     type_in_progress: Option<AnnotationType>,
     spdxref_in_progress: Option<String>,
+    extensions: BTreeMap<String, Value>,
 }
 
 fn process_annotation(
@@ -614,22 +881,18 @@ fn process_annotation(
         comment_in_progress: Some(comment),
         type_in_progress: Some(annotation_type),
         spdxref_in_progress: Some(spdxref),
+        extensions,
     } = &mut annotation_in_progress
     {
-        let annotation = Annotation::new(
+        let mut annotation = Annotation::new(
             annotator.clone(),
             *date,
             *annotation_type,
             Some(spdxref.clone()),
             comment.clone(),
         );
-        *annotation_in_progress = AnnotationInProgress {
-            annotator_in_progress: None,
-            comment_in_progress: None,
-            date_in_progress: None,
-            spdxref_in_progress: None,
-            type_in_progress: None,
-        };
+        annotation.extensions = std::mem::take(extensions);
+        *annotation_in_progress = AnnotationInProgress::default();
         annotations.push(annotation);
     }
 }
@@ -664,6 +927,68 @@ fn process_atom_for_annotations(
     Ok(())
 }
 
+/// Review information from SPDX 1.2, deprecated in SPDX 2.0 in favor of annotations.
+#[derive(Debug)]
+struct ReviewInProgress {
+    reviewer: String,
+    date: Option<DateTime<Utc>>,
+    comment: Option<String>,
+    extensions: BTreeMap<String, Value>,
+}
+
+/// Convert the review to an annotation of type `REVIEW` on the document.
+fn process_review(review: ReviewInProgress, annotations: &mut Vec<Annotation>, document_id: &str) {
+    let Some(date) = review.date else {
+        warn!("Ignoring review by {} without ReviewDate.", review.reviewer);
+        return;
+    };
+    let mut annotation = Annotation::new(
+        review.reviewer,
+        date,
+        AnnotationType::Review,
+        Some(document_id.to_string()),
+        review.comment.unwrap_or_default(),
+    );
+    annotation.extensions = review.extensions;
+    annotations.push(annotation);
+}
+
+fn process_atom_for_reviews(
+    atom: &Atom,
+    annotations: &mut Vec<Annotation>,
+    review_in_progress: &mut Option<ReviewInProgress>,
+    document_id: &str,
+) -> Result<(), SpdxError> {
+    match atom {
+        Atom::ReviewDate(value) => {
+            if let Some(review) = review_in_progress {
+                review.date = Some(DateTime::parse_from_rfc3339(value)?.with_timezone(&Utc));
+            }
+        }
+        Atom::ReviewComment(value) => {
+            if let Some(review) = review_in_progress {
+                review.comment = Some(value.clone());
+            }
+        }
+        Atom::TVComment(_) => {}
+        _ => {
+            if let Some(review) = review_in_progress.take() {
+                process_review(review, annotations, document_id);
+            }
+            if let Atom::Reviewer(value) = atom {
+                *review_in_progress = Some(ReviewInProgress {
+                    reviewer: value.clone(),
+                    date: None,
+                    comment: None,
+                    extensions: BTreeMap::new(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::unnecessary_wraps)]
 fn process_atom_for_license_info(
     atom: &Atom,
@@ -710,7 +1035,11 @@ fn process_atom_for_license_info(
 #[cfg(test)]
 #[allow(clippy::too_many_lines)]
 mod test_super {
-    use std::{fs::read_to_string, iter::FromIterator};
+    use std::{
+        fs::{read_to_string, File},
+        io::BufReader,
+        iter::FromIterator,
+    };
 
     use chrono::TimeZone;
 
@@ -796,6 +1125,58 @@ compatible system run time libraries."
         );
     }
 
+    #[test]
+    fn only_creators_of_the_document_are_kept() {
+        let file = read_to_string("tests/data/SPDXTagExample-v2.2.spdx").unwrap();
+        let spdx = spdx_from_tag_value(&file).unwrap();
+        assert_eq!(
+            spdx.document_creation_information.creation_info.creators,
+            [
+                "Tool: LicenseFind-1.0",
+                "Organization: ExampleCodeInspect ()",
+                "Person: Jane Doe ()",
+            ]
+        );
+    }
+
+    #[test]
+    fn external_ref_of_last_package_is_kept() {
+        let input = "SPDXVersion: SPDX-2.2
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: last-package
+DocumentNamespace: http://example.com/last-package
+Creator: Tool: test
+Created: 2010-01-29T18:30:22Z
+
+PackageName: first
+SPDXID: SPDXRef-first
+PackageDownloadLocation: NOASSERTION
+ExternalRef: PACKAGE-MANAGER purl pkg:npm/first@1.0.0
+
+PackageName: last
+SPDXID: SPDXRef-last
+PackageDownloadLocation: NOASSERTION
+ExternalRef: PACKAGE-MANAGER purl pkg:npm/last@1.0.0
+";
+        let spdx = spdx_from_tag_value(input).unwrap();
+        let locators = spdx
+            .package_information
+            .iter()
+            .map(|package| {
+                package
+                    .external_reference
+                    .iter()
+                    .map(|reference| reference.reference_locator.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            [vec!["pkg:npm/first@1.0.0"], vec!["pkg:npm/last@1.0.0"]]
+        );
+    }
+
     #[test]
     fn package_info_is_retrieved() {
         let file = read_to_string("tests/data/SPDXTagExample-v2.2.spdx").unwrap();
@@ -1100,4 +1481,157 @@ THE SOFTWARE IS PROVIDED �AS IS', WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMP
         assert_eq!(spdx.annotations.len(), 5);
         assert_eq!(spdx.other_licensing_information_detected.len(), 5);
     }
+
+    #[test]
+    fn streamed_tag_value_equals_parsed_tag_value() {
+        let path = "tests/data/SPDXTagExample-v2.2.spdx";
+        let parsed = spdx_from_tag_value(&read_to_string(path).unwrap()).unwrap();
+
+        let reader = BufReader::new(File::open(path).unwrap());
+        let mut streamed = SPDX::new("");
+        visit_spdx_tag_value(reader, &mut streamed).unwrap();
+
+        assert_eq!(
+            streamed.document_creation_information,
+            parsed.document_creation_information
+        );
+        assert_eq!(streamed.package_information, parsed.package_information);
+        assert_eq!(streamed.file_information, parsed.file_information);
+        assert_eq!(streamed.snippet_information, parsed.snippet_information);
+        assert_eq!(
+            streamed.other_licensing_information_detected,
+            parsed.other_licensing_information_detected
+        );
+        assert_eq!(streamed.annotations, parsed.annotations);
+        assert_eq!(
+            HashSet::<Relationship>::from_iter(streamed.relationships),
+            HashSet::from_iter(parsed.relationships)
+        );
+    }
+
+    #[test]
+    fn unknown_tags_are_kept_as_extensions() {
+        let input = read_to_string("tests/data/SPDXTagExample-v2.2.spdx")
+            .unwrap()
+            .replacen("Created:", "ToolBuild: 42\nCreated:", 1)
+            .replacen(
+                "PackageName: glibc\n",
+                "PackageName: glibc\nPackageScope: runtime\n",
+                1,
+            )
+            .replacen(
+                "FileName: ./package/foo.c\n",
+                "FileName: ./package/foo.c\nFileKind: source\nFileKind: header\n",
+                1,
+            );
+        let spdx = spdx_from_tag_value(&input).unwrap();
+
+        assert_eq!(
+            spdx.document_creation_information.extensions["ToolBuild"],
+            "42"
+        );
+        let glibc = spdx
+            .package_information
+            .iter()
+            .find(|package| package.package_name == "glibc")
+            .unwrap();
+        assert_eq!(glibc.extensions["PackageScope"], "runtime");
+        let foo = spdx
+            .file_information
+            .iter()
+            .find(|file| file.file_name == "./package/foo.c")
+            .unwrap();
+        assert_eq!(
+            foo.extensions["FileKind"],
+            serde_json::json!(["source", "header"])
+        );
+        let others = spdx
+            .package_information
+            .iter()
+            .map(|package| &package.extensions)
+            .chain(spdx.file_information.iter().map(|file| &file.extensions));
+        assert_eq!(
+            others.filter(|extensions| !extensions.is_empty()).count(),
+            2
+        );
+
+        let written = crate::writers::spdx_to_tag_value(&spdx);
+        let reparsed = spdx_from_tag_value(&written).unwrap();
+        assert_eq!(
+            reparsed.document_creation_information.extensions,
+            spdx.document_creation_information.extensions
+        );
+        assert_eq!(reparsed.package_information, spdx.package_information);
+    }
+
+    #[test]
+    fn review_information_is_converted_to_annotations() {
+        let input = "SPDXVersion: SPDX-1.2
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: reviewed
+DocumentNamespace: http://example.com/reviewed
+Creator: Tool: test
+Created: 2012-01-29T18:30:22Z
+
+Reviewer: Person: Joe Reviewer
+ReviewDate: 2012-02-10T00:00:00Z
+ReviewComment: <text>Looks good.</text>
+
+Reviewer: Person: Suzanne Reviewer
+ReviewDate: 2012-03-13T00:00:00Z
+
+PackageName: reviewed
+SPDXID: SPDXRef-Package
+PackageDownloadLocation: NOASSERTION
+";
+        let spdx = spdx_from_tag_value(input).unwrap();
+
+        assert_eq!(
+            spdx.annotations,
+            [
+                Annotation::new(
+                    "Person: Joe Reviewer".to_string(),
+                    Utc.with_ymd_and_hms(2012, 2, 10, 0, 0, 0).unwrap(),
+                    AnnotationType::Review,
+                    Some("SPDXRef-DOCUMENT".to_string()),
+                    "Looks good.".to_string(),
+                ),
+                Annotation::new(
+                    "Person: Suzanne Reviewer".to_string(),
+                    Utc.with_ymd_and_hms(2012, 3, 13, 0, 0, 0).unwrap(),
+                    AnnotationType::Review,
+                    Some("SPDXRef-DOCUMENT".to_string()),
+                    String::new(),
+                ),
+            ]
+        );
+        assert_eq!(spdx.package_information.len(), 1);
+    }
+
+    #[test]
+    fn invalid_license_expression_is_an_error() {
+        let input = read_to_string("tests/data/SPDXTagExample-v2.2.spdx")
+            .unwrap()
+            .replacen(
+                "PackageLicenseConcluded: (LGPL-2.0-only OR LicenseRef-3)",
+                "PackageLicenseConcluded: (LGPL-2.0-only OR",
+                1,
+            );
+
+        assert!(matches!(
+            spdx_from_tag_value(&input),
+            Err(SpdxError::Parse { .. })
+        ));
+        assert!(matches!(
+            visit_spdx_tag_value(input.as_bytes(), &mut SPDX::new("")),
+            Err(SpdxError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_streamed_tag_value_is_rejected() {
+        let input = "SPDXVersion: SPDX-2.3\nnot a tag\n";
+        assert!(visit_spdx_tag_value(input.as_bytes(), &mut SPDX::new("")).is_err());
+    }
 }
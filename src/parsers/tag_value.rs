@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: MIT
 
-use std::{num::ParseIntError, str::FromStr};
+use std::{io::BufRead, num::ParseIntError, str::FromStr};
 
 use nom::{
     branch::alt,
@@ -15,10 +15,13 @@ use nom::{
     AsChar, IResult,
 };
 
-use crate::models::{
-    Algorithm, AnnotationType, Checksum, ExternalDocumentReference, ExternalPackageReference,
-    ExternalPackageReferenceCategory, FileType, PackageVerificationCode, Relationship,
-    RelationshipType,
+use crate::{
+    error::SpdxError,
+    models::{
+        Algorithm, AnnotationType, Checksum, ExternalDocumentReference, ExternalPackageReference,
+        ExternalPackageReferenceCategory, FileType, PackageVerificationCode, Relationship,
+        RelationshipType,
+    },
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -109,10 +112,53 @@ pub(super) enum Atom {
     SPDXREF(String),
     AnnotationComment(String),
 
+    // Review Information, deprecated in SPDX 2.0 in favor of annotations.
+    Reviewer(String),
+    ReviewDate(String),
+    ReviewComment(String),
+
+    /// Tag not defined by the spec, with its value. Kept as an extension of the current element.
+    Unknown(String, String),
+
     /// Comment in the document. Not part of the final SPDX.
     TVComment(String),
 }
 
+/// Read the next line of a tag-value document, or all lines of a tag-value pair with a
+/// `<text>` value that spans multiple lines. Returns `false` at the end of the input.
+///
+/// # Errors
+///
+/// - If reading from the reader fails.
+/// - If a `<text>` value is not closed before the end of the input.
+pub(super) fn read_tag_value<R: BufRead>(
+    reader: &mut R,
+    buffer: &mut String,
+) -> Result<bool, SpdxError> {
+    buffer.clear();
+    if reader.read_line(buffer)? == 0 {
+        return Ok(false);
+    }
+    if buffer.trim_start().starts_with('#') {
+        return Ok(true);
+    }
+    let text_start = buffer
+        .split_once(':')
+        .filter(|(_, value)| value.trim_start().starts_with("<text>"))
+        .map(|(tag, _)| tag.len());
+    if let Some(text_start) = text_start {
+        while !buffer[text_start..].contains("</text>") {
+            if reader.read_line(buffer)? == 0 {
+                return Err(SpdxError::TagValueParse(format!(
+                    "<text> of {} is not closed with </text>",
+                    buffer[..text_start].trim()
+                )));
+            }
+        }
+    }
+    Ok(true)
+}
+
 pub(super) fn atoms(i: &str) -> IResult<&str, Vec<Atom>, VerboseError<&str>> {
     many0(alt((ws(tv_comment), ws(tag_value_to_atom))))(i)
 }
@@ -222,10 +268,12 @@ fn tag_value_to_atom(i: &str) -> IResult<&str, Atom, VerboseError<&str>> {
         "AnnotationType" => Ok((i, Atom::AnnotationType(annotation_type(key_value.1)?.1))),
         "SPDXREF" => Ok((i, Atom::SPDXREF(key_value.1.to_string()))),
         "AnnotationComment" => Ok((i, Atom::AnnotationComment(key_value.1.to_string()))),
-        v => {
-            dbg!(v);
-            unimplemented!()
-        }
+
+        // Review Information
+        "Reviewer" => Ok((i, Atom::Reviewer(key_value.1.to_string()))),
+        "ReviewDate" => Ok((i, Atom::ReviewDate(key_value.1.to_string()))),
+        "ReviewComment" => Ok((i, Atom::ReviewComment(key_value.1.to_string()))),
+        v => Ok((i, Atom::Unknown(v.to_string(), key_value.1.to_string()))),
     }
 }
 
@@ -393,7 +441,7 @@ fn package_verification_code(
             } else {
                 Vec::new()
             };
-            PackageVerificationCode::new(value.to_string(), excludes)
+            PackageVerificationCode::new(value.trim().to_string(), excludes)
         },
     )(i)
 }
@@ -478,7 +526,56 @@ mod tests {
         },
     };
 
-    use super::{atoms, tag_value, tag_value_to_atom, Atom};
+    use super::{atoms, read_tag_value, tag_value, tag_value_to_atom, Atom};
+
+    fn read_all(input: &str) -> Vec<String> {
+        let mut reader = input.as_bytes();
+        let mut buffer = String::new();
+        let mut values = Vec::new();
+        while read_tag_value(&mut reader, &mut buffer).unwrap() {
+            values.push(buffer.clone());
+        }
+        values
+    }
+
+    #[test]
+    fn text_on_one_line_is_read() {
+        assert_eq!(
+            read_all("PackageComment: <text>One line.</text>\nPackageName: next\n"),
+            [
+                "PackageComment: <text>One line.</text>\n",
+                "PackageName: next\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn text_is_read_until_closing_tag() {
+        let values =
+            read_all("PackageComment: <text>First\nlast</text> trailing\nPackageName: next\n");
+        assert_eq!(
+            values,
+            [
+                "PackageComment: <text>First\nlast</text> trailing\n",
+                "PackageName: next\n"
+            ]
+        );
+
+        // Content after the closing tag is left over, so it's rejected by the parser.
+        let (rest, parsed) = atoms(&values[0]).unwrap();
+        assert_eq!(parsed, [Atom::PackageComment("First\nlast".to_string())]);
+        assert_eq!(rest.trim(), "trailing");
+    }
+
+    #[test]
+    fn unterminated_text_is_an_error() {
+        let mut reader = "PackageComment: <text>First\nlast\n".as_bytes();
+        let error = read_tag_value(&mut reader, &mut String::new()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Error parsing tag-value: <text> of PackageComment is not closed with </text>"
+        );
+    }
 
     #[test]
     fn version_can_be_parsed() {
@@ -537,6 +634,16 @@ mod tests {
         assert_eq!(value.excludes, expected);
     }
 
+    #[test]
+    fn package_verification_code_is_trimmed() {
+        let (_, value) = package_verification_code(
+            "d6a770ba38583ed4bb4525bd96e50461655d2758 (excludes: ./package.spdx)",
+        )
+        .unwrap();
+        assert_eq!(value.value, "d6a770ba38583ed4bb4525bd96e50461655d2758");
+        assert_eq!(value.excludes, vec!["./package.spdx"]);
+    }
+
     #[test]
     fn external_package_ref_can_be_parsed() {
         let (_, value) = external_package_reference(
@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::SpdxError,
+    models::{
+        Annotation, DocumentCreationInformation, FileInformation,
+        OtherLicensingInformationDetected, PackageInformation, Relationship, Snippet, SPDX,
+    },
+};
+
+/// Receives the parts of a document from a streaming parser, one at a time.
+///
+/// The document creation information is always visited first. The other methods are called in
+/// the order the elements appear in the input. All methods do nothing by default, so only the
+/// needed parts have to be implemented. An error returned from a method stops the parsing and is
+/// returned from the parser.
+#[allow(unused_variables, clippy::missing_errors_doc)]
+pub trait SpdxVisitor {
+    fn document_creation_information(
+        &mut self,
+        document_creation_information: DocumentCreationInformation,
+    ) -> Result<(), SpdxError> {
+        Ok(())
+    }
+
+    fn package(&mut self, package: PackageInformation) -> Result<(), SpdxError> {
+        Ok(())
+    }
+
+    fn file(&mut self, file: FileInformation) -> Result<(), SpdxError> {
+        Ok(())
+    }
+
+    fn snippet(&mut self, snippet: Snippet) -> Result<(), SpdxError> {
+        Ok(())
+    }
+
+    fn other_licensing_information_detected(
+        &mut self,
+        license: OtherLicensingInformationDetected,
+    ) -> Result<(), SpdxError> {
+        Ok(())
+    }
+
+    fn relationship(&mut self, relationship: Relationship) -> Result<(), SpdxError> {
+        Ok(())
+    }
+
+    fn annotation(&mut self, annotation: Annotation) -> Result<(), SpdxError> {
+        Ok(())
+    }
+}
+
+/// Collects the visited parts into the document, replacing its document creation information.
+impl SpdxVisitor for SPDX {
+    fn document_creation_information(
+        &mut self,
+        document_creation_information: DocumentCreationInformation,
+    ) -> Result<(), SpdxError> {
+        self.document_creation_information = document_creation_information;
+        Ok(())
+    }
+
+    fn package(&mut self, package: PackageInformation) -> Result<(), SpdxError> {
+        self.package_information.push(package);
+        Ok(())
+    }
+
+    fn file(&mut self, file: FileInformation) -> Result<(), SpdxError> {
+        self.file_information.push(file);
+        Ok(())
+    }
+
+    fn snippet(&mut self, snippet: Snippet) -> Result<(), SpdxError> {
+        self.snippet_information.push(snippet);
+        Ok(())
+    }
+
+    fn other_licensing_information_detected(
+        &mut self,
+        license: OtherLicensingInformationDetected,
+    ) -> Result<(), SpdxError> {
+        self.other_licensing_information_detected.push(license);
+        Ok(())
+    }
+
+    fn relationship(&mut self, relationship: Relationship) -> Result<(), SpdxError> {
+        self.relationships.push(relationship);
+        Ok(())
+    }
+
+    fn annotation(&mut self, annotation: Annotation) -> Result<(), SpdxError> {
+        self.annotations.push(annotation);
+        Ok(())
+    }
+}
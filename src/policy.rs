@@ -0,0 +1,550 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Evaluation of the licenses of an SPDX document against a license policy.
+//!
+//! A policy lists allowed, denied and review-required licenses, and exceptions that allow
+//! licenses for specific packages. The license expressions are evaluated as a whole: `OR` picks
+//! the best option and `AND` requires every license to be acceptable. A license with an exception,
+//! e.g. `GPL-2.0-only WITH Classpath-exception-2.0`, can be listed separately from the license
+//! alone.
+//!
+//! Policies can be written in TOML or JSON:
+//!
+//! ```toml
+//! allowed = ["MIT", "Apache-2.0", "GPL-2.0-only WITH Classpath-exception-2.0"]
+//! denied = ["GPL-2.0-only", "GPL-3.0-only"]
+//! review = ["LGPL-2.1-only"]
+//! # Decision for the licenses not listed in the policy, defaults to review.
+//! unknown = "review"
+//!
+//! [[exceptions]]
+//! package = "openssl"
+//! version = "1.1.1"
+//! licenses = ["OpenSSL"]
+//! reason = "Approved by legal"
+//! ```
+
+use std::{fmt, fs, path::Path};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::SpdxError,
+    models::{
+        LicenseTerm, LicenseTree, PackageInformation, RelationshipType, SpdxExpression,
+        SpecialValue, SPDX,
+    },
+};
+
+/// A license policy.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub allowed: Vec<String>,
+
+    #[serde(default)]
+    pub denied: Vec<String>,
+
+    /// Licenses that require a review before use.
+    #[serde(default)]
+    pub review: Vec<String>,
+
+    /// Decision for the licenses not listed in the policy.
+    #[serde(default = "Decision::review_required")]
+    pub unknown: Decision,
+
+    #[serde(default)]
+    pub exceptions: Vec<PackageException>,
+}
+
+/// Licenses allowed for a specific package regardless of the lists of the policy.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageException {
+    /// Name of the package.
+    pub package: String,
+
+    /// Version of the package, the exception applies to all versions if not set.
+    pub version: Option<String>,
+
+    pub licenses: Vec<String>,
+
+    pub reason: Option<String>,
+}
+
+/// Outcome of evaluating a license against the policy, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Decision {
+    Allowed,
+    #[serde(alias = "review")]
+    ReviewRequired,
+    Denied,
+}
+
+impl Decision {
+    const fn review_required() -> Self {
+        Self::ReviewRequired
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Allowed => "allowed",
+            Self::ReviewRequired => "review required",
+            Self::Denied => "denied",
+        })
+    }
+}
+
+/// Types of the evaluated elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ElementType {
+    Package,
+    File,
+    Snippet,
+}
+
+/// Result of evaluating the license of an element.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementVerdict {
+    pub spdx_id: String,
+    pub name: String,
+    pub element_type: ElementType,
+
+    /// The evaluated license expression, `None` if the element has no license.
+    pub license: Option<String>,
+
+    pub decision: Decision,
+
+    /// The licenses the decision is based on. For `OR` expressions these are the licenses of
+    /// the chosen option.
+    pub licenses: Vec<String>,
+
+    /// Human readable explanations of the decision.
+    pub reasons: Vec<String>,
+}
+
+/// Verdicts for all packages, files and snippets of a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyReport {
+    pub verdicts: Vec<ElementVerdict>,
+}
+
+impl PolicyReport {
+    /// The worst decision of all elements, [`Decision::Allowed`] for an empty report.
+    pub fn decision(&self) -> Decision {
+        self.verdicts
+            .iter()
+            .map(|verdict| verdict.decision)
+            .max()
+            .unwrap_or(Decision::Allowed)
+    }
+
+    /// Verdicts with the given decision.
+    pub fn with_decision(&self, decision: Decision) -> Vec<&ElementVerdict> {
+        self.verdicts
+            .iter()
+            .filter(|verdict| verdict.decision == decision)
+            .collect()
+    }
+}
+
+/// Evaluation of a part of a license expression.
+struct Outcome {
+    decision: Decision,
+    licenses: Vec<String>,
+    reasons: Vec<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            allowed: Vec::new(),
+            denied: Vec::new(),
+            review: Vec::new(),
+            unknown: Decision::ReviewRequired,
+            exceptions: Vec::new(),
+        }
+    }
+}
+
+impl Policy {
+    /// Read a policy from a TOML or JSON file, based on the extension of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read or parsed, or if the extension is not
+    /// `toml` or `json`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        let path = path.as_ref();
+        let input = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml_str(&input),
+            Some("json") => Self::from_json_str(&input),
+            _ => Err(SpdxError::PathExtension(path.display().to_string())),
+        }
+    }
+
+    /// Parse a policy from TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Toml`] if the input is not a valid policy.
+    pub fn from_toml_str(input: &str) -> Result<Self, SpdxError> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Parse a policy from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if the input is not a valid policy.
+    pub fn from_json_str(input: &str) -> Result<Self, SpdxError> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Evaluate the licenses of all packages, files and snippets of the document.
+    ///
+    /// The concluded license of a package is evaluated, or the declared license if the concluded
+    /// license is missing or `NOASSERTION`. The exceptions of a package also apply to its files
+    /// and to the snippets of the files.
+    pub fn evaluate(&self, spdx: &SPDX) -> PolicyReport {
+        info!("Evaluating SPDX against the license policy.");
+        let mut report = PolicyReport::default();
+
+        for package in &spdx.package_information {
+            let license = package
+                .concluded_license
+                .as_ref()
+                .filter(|license| !is_noassertion(license))
+                .or(package.declared_license.as_ref());
+            report.verdicts.push(self.verdict(
+                &package.package_spdx_identifier,
+                &package.package_name,
+                ElementType::Package,
+                license,
+                &self.exceptions_for(&[package]),
+            ));
+        }
+
+        for file in &spdx.file_information {
+            let packages = packages_of_file(spdx, &file.file_spdx_identifier);
+            report.verdicts.push(self.verdict(
+                &file.file_spdx_identifier,
+                &file.file_name,
+                ElementType::File,
+                file.concluded_license.as_ref(),
+                &self.exceptions_for(&packages),
+            ));
+        }
+
+        for snippet in &spdx.snippet_information {
+            let packages = packages_of_file(spdx, &snippet.snippet_from_file_spdx_identifier);
+            report.verdicts.push(
+                self.verdict(
+                    &snippet.snippet_spdx_identifier,
+                    snippet
+                        .snippet_name
+                        .as_deref()
+                        .unwrap_or(&snippet.snippet_spdx_identifier),
+                    ElementType::Snippet,
+                    snippet.snippet_concluded_license.as_ref(),
+                    &self.exceptions_for(&packages),
+                ),
+            );
+        }
+
+        report
+    }
+
+    /// Evaluate a license expression. The exceptions are the package exceptions that apply to
+    /// the licensed element.
+    pub fn evaluate_expression(
+        &self,
+        expression: &SpdxExpression,
+        exceptions: &[&PackageException],
+    ) -> (Decision, Vec<String>) {
+        let outcome = self.evaluate_tree(&LicenseTree::from(expression), exceptions);
+        (outcome.decision, outcome.reasons)
+    }
+
+    fn verdict(
+        &self,
+        spdx_id: &str,
+        name: &str,
+        element_type: ElementType,
+        license: Option<&SpdxExpression>,
+        exceptions: &[&PackageException],
+    ) -> ElementVerdict {
+        let outcome = license.map_or_else(
+            || Outcome {
+                decision: self.unknown,
+                licenses: Vec::new(),
+                reasons: vec![format!("No license, {} by default", self.unknown)],
+            },
+            |license| self.evaluate_tree(&LicenseTree::from(license), exceptions),
+        );
+
+        ElementVerdict {
+            spdx_id: spdx_id.to_string(),
+            name: name.to_string(),
+            element_type,
+            license: license.map(ToString::to_string),
+            decision: outcome.decision,
+            licenses: outcome.licenses,
+            reasons: outcome.reasons,
+        }
+    }
+
+    fn evaluate_tree(&self, tree: &LicenseTree, exceptions: &[&PackageException]) -> Outcome {
+        match tree {
+            LicenseTree::License(term) => {
+                let (decision, reason) = self.evaluate_term(term, exceptions);
+                Outcome {
+                    decision,
+                    licenses: vec![term.to_string()],
+                    reasons: vec![reason],
+                }
+            }
+            LicenseTree::And(operands) => {
+                let mut outcome = Outcome {
+                    decision: Decision::Allowed,
+                    licenses: Vec::new(),
+                    reasons: Vec::new(),
+                };
+                for operand in operands {
+                    let operand = self.evaluate_tree(operand, exceptions);
+                    outcome.decision = outcome.decision.max(operand.decision);
+                    outcome.licenses.extend(operand.licenses);
+                    outcome.reasons.extend(operand.reasons);
+                }
+                outcome
+            }
+            LicenseTree::Or(operands) => {
+                let mut best: Option<Outcome> = None;
+                for operand in operands {
+                    let operand = self.evaluate_tree(operand, exceptions);
+                    if best
+                        .as_ref()
+                        .is_none_or(|best| operand.decision < best.decision)
+                    {
+                        best = Some(operand);
+                    }
+                }
+                let mut best = best.unwrap_or(Outcome {
+                    decision: self.unknown,
+                    licenses: Vec::new(),
+                    reasons: Vec::new(),
+                });
+                best.reasons
+                    .push(format!("Chose {} from {tree}", best.licenses.join(" AND ")));
+                best
+            }
+        }
+    }
+
+    fn evaluate_term(
+        &self,
+        term: &LicenseTerm,
+        exceptions: &[&PackageException],
+    ) -> (Decision, String) {
+        let full = term.to_string();
+        if let Some(exception) = exceptions
+            .iter()
+            .find(|exception| contains(&exception.licenses, &full))
+        {
+            let reason = exception
+                .reason
+                .as_ref()
+                .map_or_else(String::new, |reason| format!(": {reason}"));
+            return (
+                Decision::Allowed,
+                format!(
+                    "{full} is allowed for package {}{reason}",
+                    exception.package
+                ),
+            );
+        }
+
+        // A license with an exception can be listed on its own, otherwise the license decides.
+        let mut candidates = vec![full.as_str()];
+        if term.exception.is_some() {
+            candidates.push(&term.license);
+        }
+        for candidate in candidates {
+            let decision = if contains(&self.denied, candidate) {
+                Decision::Denied
+            } else if contains(&self.review, candidate) {
+                Decision::ReviewRequired
+            } else if contains(&self.allowed, candidate) {
+                Decision::Allowed
+            } else {
+                continue;
+            };
+            return (decision, format!("{candidate} is {decision} by the policy"));
+        }
+
+        (
+            self.unknown,
+            format!("{full} is not in the policy, {} by default", self.unknown),
+        )
+    }
+
+    fn exceptions_for(&self, packages: &[&PackageInformation]) -> Vec<&PackageException> {
+        self.exceptions
+            .iter()
+            .filter(|exception| {
+                packages.iter().any(|package| {
+                    package.package_name == exception.package
+                        && exception
+                            .version
+                            .as_ref()
+                            .is_none_or(|version| package.package_version.as_ref() == Some(version))
+                })
+            })
+            .collect()
+    }
+}
+
+/// License identifiers are case insensitive.
+fn contains(licenses: &[String], license: &str) -> bool {
+    licenses
+        .iter()
+        .any(|listed| listed.eq_ignore_ascii_case(license))
+}
+
+fn is_noassertion(license: &SpdxExpression) -> bool {
+    SpecialValue::is_noassertion(&license.to_string())
+}
+
+/// The packages that have the file in their files or `CONTAINS` it.
+fn packages_of_file<'a>(spdx: &'a SPDX, file_id: &str) -> Vec<&'a PackageInformation> {
+    spdx.package_information
+        .iter()
+        .filter(|package| {
+            package.files.iter().any(|file| file == file_id)
+                || spdx
+                    .relationships_for_spdx_id(&package.package_spdx_identifier)
+                    .iter()
+                    .any(|relationship| {
+                        relationship.relationship_type == RelationshipType::Contains
+                            && relationship.related_spdx_element == file_id
+                    })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    fn verdict<'a>(report: &'a PolicyReport, spdx_id: &str) -> &'a ElementVerdict {
+        report
+            .verdicts
+            .iter()
+            .find(|verdict| verdict.spdx_id == spdx_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn policy_is_read_from_toml_and_json() {
+        let toml = Policy::from_file("tests/data/policy/policy.toml").unwrap();
+        let json = Policy::from_file("tests/data/policy/policy.json").unwrap();
+        assert_eq!(toml, json);
+        assert_eq!(toml.unknown, Decision::ReviewRequired);
+        assert_eq!(toml.exceptions[0].package, "Saxon");
+        assert!(Policy::from_toml_str("unknown = \"denied\"\nwhatever = 1").is_err());
+    }
+
+    #[test]
+    fn example_document_is_evaluated() {
+        let policy = Policy::from_file("tests/data/policy/policy.toml").unwrap();
+        let report = policy.evaluate(&example());
+        assert_eq!(report.verdicts.len(), 10);
+        assert_eq!(report.decision(), Decision::Denied);
+
+        // LGPL-2.0-only OR LicenseRef-3
+        let glibc = verdict(&report, "SPDXRef-Package");
+        assert_eq!(glibc.decision, Decision::Allowed);
+        assert_eq!(glibc.licenses, vec!["LGPL-2.0-only"]);
+
+        // MPL-1.0 is denied, but allowed for Saxon.
+        let saxon = verdict(&report, "SPDXRef-Saxon");
+        assert_eq!(saxon.decision, Decision::Allowed);
+        assert_eq!(
+            saxon.reasons,
+            vec!["MPL-1.0 is allowed for package Saxon: Used only in tests"]
+        );
+
+        let jena = verdict(&report, "SPDXRef-JenaLib");
+        assert_eq!(jena.decision, Decision::ReviewRequired);
+        assert_eq!(verdict(&report, "SPDXRef-fromDoap-0").license, None);
+        assert_eq!(
+            verdict(&report, "SPDXRef-Snippet").decision,
+            Decision::Denied
+        );
+        assert_eq!(
+            report
+                .with_decision(Decision::Denied)
+                .iter()
+                .map(|verdict| verdict.spdx_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["SPDXRef-Snippet"]
+        );
+    }
+
+    #[test]
+    fn and_requires_all_licenses() {
+        let policy = Policy::from_file("tests/data/policy/policy.toml").unwrap();
+        let (decision, reasons) = policy.evaluate_expression(
+            &SpdxExpression::parse("MIT AND (GPL-2.0-only OR LicenseRef-1)").unwrap(),
+            &[],
+        );
+        assert_eq!(decision, Decision::ReviewRequired);
+        assert_eq!(
+            reasons,
+            vec![
+                "MIT is allowed by the policy",
+                "LicenseRef-1 is review required by the policy",
+                "Chose LicenseRef-1 from GPL-2.0-only OR LicenseRef-1",
+            ]
+        );
+    }
+
+    #[test]
+    fn with_exceptions_are_respected() {
+        let policy = Policy::from_file("tests/data/policy/policy.toml").unwrap();
+        let evaluate = |expression| {
+            policy
+                .evaluate_expression(&SpdxExpression::parse(expression).unwrap(), &[])
+                .0
+        };
+        assert_eq!(evaluate("GPL-2.0-only"), Decision::Denied);
+        assert_eq!(
+            evaluate("GPL-2.0-only WITH Classpath-exception-2.0"),
+            Decision::Allowed
+        );
+        assert_eq!(
+            evaluate("GPL-2.0-only WITH Autoconf-exception-2.0"),
+            Decision::Denied
+        );
+        assert_eq!(evaluate("mit"), Decision::Allowed);
+        assert_eq!(evaluate("Unlisted"), Decision::ReviewRequired);
+    }
+}
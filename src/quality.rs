@@ -0,0 +1,443 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Checking SPDX documents against SBOM quality profiles.
+//!
+//! A profile is a named set of requirements, e.g. the [NTIA minimum elements]. Most requirements
+//! apply to every package of the document, some to the creation information of the document.
+//! The result is a pass or fail for every package and a score: the percentage of the checks
+//! that passed.
+//!
+//! [NTIA minimum elements]: https://www.ntia.gov/report/2021/minimum-elements-software-bill-materials-sbom
+
+use std::fmt;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Algorithm, PackageInformation, RelationshipType, SpecialValue, SPDX};
+
+/// SBOM quality profiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    /// NTIA minimum elements for a software bill of materials.
+    Ntia,
+
+    /// BSI TR-03183-2, technical guideline for SBOMs of the German Federal Office for
+    /// Information Security.
+    #[serde(rename = "bsi-tr-03183")]
+    BsiTr03183,
+
+    /// [OpenChain Telco SBOM guide](https://github.com/OpenChain-Project/Telco-WG).
+    OpenchainTelco,
+}
+
+/// A requirement of a quality profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Requirement {
+    /// The package has a supplier.
+    Supplier,
+
+    /// The package has a name.
+    Name,
+
+    /// The package has a version.
+    Version,
+
+    /// The package has a purl, CPE or SWID tag in its external references.
+    UniqueIdentifier,
+
+    /// The package has a relationship to another element, other than `DESCRIBES` or
+    /// `DESCRIBED_BY`.
+    Dependencies,
+
+    /// The package has a file name.
+    FileName,
+
+    /// The package has a download location.
+    DownloadLocation,
+
+    /// The package has a SHA-512 checksum.
+    Sha512Checksum,
+
+    /// The package has a concluded license.
+    ConcludedLicense,
+
+    /// The package has a declared license.
+    DeclaredLicense,
+
+    /// The package has a copyright text.
+    CopyrightText,
+
+    /// The document has a creator.
+    Author,
+
+    /// The document has a creation time. Always met by parsed documents.
+    Timestamp,
+
+    /// The document has an `Organization:` creator.
+    CreatorOrganization,
+
+    /// The document has a `Tool:` creator.
+    CreatorTool,
+}
+
+/// Result of checking a package against a profile.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageQuality {
+    pub spdx_id: String,
+    pub name: String,
+
+    /// Whether the package meets all package requirements of the profile.
+    pub passed: bool,
+
+    /// Percentage of the package requirements the package meets. 100 if the profile has no
+    /// package requirements.
+    pub score: f64,
+
+    /// Package requirements the package doesn't meet.
+    pub missing: Vec<Requirement>,
+}
+
+/// Result of checking a document against a profile.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityReport {
+    pub profile: Profile,
+
+    /// Document requirements the document doesn't meet.
+    pub document_missing: Vec<Requirement>,
+
+    pub packages: Vec<PackageQuality>,
+
+    /// Percentage of all checks that passed, counting the document requirements once and the
+    /// package requirements once per package. 100 if there's nothing to check, e.g. for a document
+    /// without packages and a profile without document requirements, as no check failed. This
+    /// differs from the percentages of [`Coverage`](crate::models::Coverage), which are 0 for a
+    /// document without elements of the kind.
+    pub score: f64,
+}
+
+impl QualityReport {
+    /// Whether the document and all packages meet the requirements of the profile.
+    pub fn passed(&self) -> bool {
+        self.document_missing.is_empty() && self.packages.iter().all(|package| package.passed)
+    }
+
+    /// Packages that don't meet the requirements of the profile.
+    pub fn failed_packages(&self) -> Vec<&PackageQuality> {
+        self.packages
+            .iter()
+            .filter(|package| !package.passed)
+            .collect()
+    }
+}
+
+impl Profile {
+    /// All profiles.
+    pub const ALL: [Self; 3] = [Self::Ntia, Self::BsiTr03183, Self::OpenchainTelco];
+
+    /// Requirements of the profile.
+    pub fn requirements(self) -> &'static [Requirement] {
+        use Requirement::{
+            Author, ConcludedLicense, CopyrightText, CreatorOrganization, CreatorTool,
+            DeclaredLicense, Dependencies, DownloadLocation, FileName, Name, Sha512Checksum,
+            Supplier, Timestamp, UniqueIdentifier, Version,
+        };
+
+        match self {
+            Self::Ntia => &[
+                Supplier,
+                Name,
+                Version,
+                UniqueIdentifier,
+                Dependencies,
+                Author,
+                Timestamp,
+            ],
+            Self::BsiTr03183 => &[
+                Supplier,
+                Name,
+                Version,
+                FileName,
+                Dependencies,
+                ConcludedLicense,
+                Sha512Checksum,
+                Author,
+                Timestamp,
+            ],
+            Self::OpenchainTelco => &[
+                Supplier,
+                Name,
+                Version,
+                UniqueIdentifier,
+                Dependencies,
+                DownloadLocation,
+                ConcludedLicense,
+                DeclaredLicense,
+                CopyrightText,
+                Author,
+                Timestamp,
+                CreatorOrganization,
+                CreatorTool,
+            ],
+        }
+    }
+
+    /// Check the document against the profile.
+    pub fn check(self, spdx: &SPDX) -> QualityReport {
+        info!("Checking SPDX against the {self} profile.");
+        let (document_requirements, package_requirements): (Vec<Requirement>, Vec<Requirement>) =
+            self.requirements()
+                .iter()
+                .partition(|requirement| requirement.is_document_requirement());
+
+        let document_missing = document_requirements
+            .iter()
+            .copied()
+            .filter(|requirement| !requirement.is_met_by_document(spdx))
+            .collect::<Vec<_>>();
+
+        let packages = spdx
+            .package_information
+            .iter()
+            .map(|package| {
+                let missing = package_requirements
+                    .iter()
+                    .copied()
+                    .filter(|requirement| !requirement.is_met_by_package(spdx, package))
+                    .collect::<Vec<_>>();
+                PackageQuality {
+                    spdx_id: package.package_spdx_identifier.clone(),
+                    name: package.package_name.clone(),
+                    passed: missing.is_empty(),
+                    score: percentage(
+                        package_requirements.len() - missing.len(),
+                        package_requirements.len(),
+                    ),
+                    missing,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let checks = document_requirements.len() + package_requirements.len() * packages.len();
+        let failed = document_missing.len()
+            + packages
+                .iter()
+                .map(|package| package.missing.len())
+                .sum::<usize>();
+
+        QualityReport {
+            profile: self,
+            document_missing,
+            packages,
+            score: percentage(checks - failed, checks),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ntia => "NTIA minimum elements",
+            Self::BsiTr03183 => "BSI TR-03183",
+            Self::OpenchainTelco => "OpenChain Telco",
+        })
+    }
+}
+
+impl Requirement {
+    /// Whether the requirement applies to the document instead of the packages.
+    pub const fn is_document_requirement(self) -> bool {
+        matches!(
+            self,
+            Self::Author | Self::Timestamp | Self::CreatorOrganization | Self::CreatorTool
+        )
+    }
+
+    fn is_met_by_document(self, spdx: &SPDX) -> bool {
+        let creators = &spdx.document_creation_information.creation_info.creators;
+        let has_creator = |prefix: &str| {
+            creators
+                .iter()
+                .any(|creator| creator.trim_start().starts_with(prefix))
+        };
+        match self {
+            Self::Author => creators.iter().any(|creator| has_value(creator)),
+            Self::CreatorOrganization => has_creator("Organization:"),
+            Self::CreatorTool => has_creator("Tool:"),
+            _ => true,
+        }
+    }
+
+    fn is_met_by_package(self, spdx: &SPDX, package: &PackageInformation) -> bool {
+        match self {
+            Self::Supplier => package.package_supplier.as_deref().is_some_and(has_value),
+            Self::Name => has_value(&package.package_name),
+            Self::Version => package.package_version.as_deref().is_some_and(has_value),
+            Self::UniqueIdentifier => package.external_reference.iter().any(|reference| {
+                matches!(
+                    reference.reference_type.as_str(),
+                    "purl" | "cpe22Type" | "cpe23Type" | "swid"
+                )
+            }),
+            Self::Dependencies => spdx.relationships.iter().any(|relationship| {
+                let id = &package.package_spdx_identifier;
+                (&relationship.spdx_element_id == id || &relationship.related_spdx_element == id)
+                    && !matches!(
+                        relationship.relationship_type,
+                        RelationshipType::Describes | RelationshipType::DescribedBy
+                    )
+            }),
+            Self::FileName => package.package_file_name.as_deref().is_some_and(has_value),
+            Self::DownloadLocation => has_value(&package.package_download_location),
+            Self::Sha512Checksum => package
+                .package_checksum
+                .iter()
+                .any(|checksum| checksum.algorithm == Algorithm::SHA512),
+            Self::ConcludedLicense => package
+                .concluded_license
+                .as_ref()
+                .is_some_and(|license| has_value(&license.to_string())),
+            Self::DeclaredLicense => package
+                .declared_license
+                .as_ref()
+                .is_some_and(|license| has_value(&license.to_string())),
+            Self::CopyrightText => package.copyright_text.as_deref().is_some_and(has_value),
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Supplier => "supplier",
+            Self::Name => "name",
+            Self::Version => "version",
+            Self::UniqueIdentifier => "unique identifier",
+            Self::Dependencies => "dependency relationships",
+            Self::FileName => "file name",
+            Self::DownloadLocation => "download location",
+            Self::Sha512Checksum => "SHA-512 checksum",
+            Self::ConcludedLicense => "concluded license",
+            Self::DeclaredLicense => "declared license",
+            Self::CopyrightText => "copyright text",
+            Self::Author => "author",
+            Self::Timestamp => "timestamp",
+            Self::CreatorOrganization => "creator organization",
+            Self::CreatorTool => "creator tool",
+        })
+    }
+}
+
+/// Whether the field has a value other than `NOASSERTION`. `NONE` counts as a value, as it's an
+/// assertion that there is none.
+fn has_value(value: &str) -> bool {
+    !SpecialValue::is_noassertion(value)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    fn package<'a>(report: &'a QualityReport, spdx_id: &str) -> &'a PackageQuality {
+        report
+            .packages
+            .iter()
+            .find(|package| package.spdx_id == spdx_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn ntia_minimum_elements() {
+        let report = Profile::Ntia.check(&example());
+        assert!(report.document_missing.is_empty());
+        assert!(!report.passed());
+
+        let glibc = package(&report, "SPDXRef-Package");
+        assert!(glibc.passed);
+        assert!((glibc.score - 100.0).abs() < f64::EPSILON);
+
+        let saxon = package(&report, "SPDXRef-Saxon");
+        assert_eq!(
+            saxon.missing,
+            vec![Requirement::Supplier, Requirement::UniqueIdentifier]
+        );
+        assert!((saxon.score - 60.0).abs() < f64::EPSILON);
+
+        assert_eq!(
+            package(&report, "SPDXRef-fromDoap-1").missing,
+            vec![
+                Requirement::Supplier,
+                Requirement::Version,
+                Requirement::UniqueIdentifier,
+                Requirement::Dependencies
+            ]
+        );
+        assert_eq!(report.failed_packages().len(), 3);
+
+        // 2 document checks and 5 checks for each of the 4 packages, 7 checks failed.
+        assert!((report.score - 15.0 * 100.0 / 22.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bsi_and_openchain_telco() {
+        let spdx = example();
+
+        let bsi = Profile::BsiTr03183.check(&spdx);
+        let glibc = package(&bsi, "SPDXRef-Package");
+        assert_eq!(glibc.missing, vec![Requirement::Sha512Checksum]);
+
+        let telco = Profile::OpenchainTelco.check(&spdx);
+        assert!(telco.document_missing.is_empty());
+        assert!(package(&telco, "SPDXRef-Package").passed);
+        assert!(package(&telco, "SPDXRef-fromDoap-0")
+            .missing
+            .contains(&Requirement::ConcludedLicense));
+
+        let mut spdx = spdx;
+        spdx.document_creation_information.creation_info.creators =
+            vec!["Person: Jane Doe".to_string()];
+        assert_eq!(
+            Profile::OpenchainTelco.check(&spdx).document_missing,
+            vec![Requirement::CreatorOrganization, Requirement::CreatorTool]
+        );
+    }
+
+    #[test]
+    fn empty_document_passes() {
+        let report = Profile::Ntia.check(&SPDX::new("empty"));
+        assert!(report.packages.is_empty());
+        assert!(report.passed());
+        assert!((report.score - 100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn report_is_serializable() {
+        let report = Profile::BsiTr03183.check(&example());
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["profile"], "bsi-tr-03183");
+        assert_eq!(json["packages"][0]["missing"][0], "sha512Checksum");
+    }
+}
@@ -0,0 +1,783 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! A query language for finding packages, files and snippets of an SPDX document.
+//!
+//! A query selects the type of the elements, optionally filters them with a `where` clause and
+//! optionally picks the fields to return with a `select` clause:
+//!
+//! ```text
+//! packages where license contains "GPL-*" and not has supplier
+//!     and from "SPDXRef-App" via DEPENDS_ON
+//!     select name, versionInfo
+//! ```
+//!
+//! - The element types are `packages`, `files`, `snippets` and `elements` for all of them.
+//! - Fields are named as in JSON documents, e.g. `name`, `versionInfo` or `fileName`. Nested
+//!   fields are separated with dots, e.g. `checksums.algorithm`.
+//! - `field = value` and `field != value` compare the value of a field. A field with multiple
+//!   values is equal to the value if any of the values is.
+//! - `field contains value` checks that the value is a substring of the field, ignoring case.
+//!   For license expression fields it checks that the license or exception is used in the
+//!   expression instead, and a value ending in `*` matches the identifiers with the prefix. The
+//!   field `license` stands for all license expressions of the element.
+//! - `has field` checks that the field has a value other than `NOASSERTION`.
+//! - `from "ID" via TYPE` matches the elements that the element `ID` has a relationship of the
+//!   type with, and `to "ID" via TYPE` the elements that have a relationship of the type with the
+//!   element `ID`. `via TYPE` can be left out to allow any type. `reachable from` and
+//!   `reachable to` follow the relationships transitively.
+//! - Conditions are combined with `and`, `or`, `not` and parentheses.
+//!
+//! Values are either quoted with `"` or written without quotes if they contain no whitespace.
+//! Keywords are case-insensitive.
+//!
+//! ```
+//! # use spdx_rs::{models::SPDX, query::Query};
+//! # let spdx: SPDX = serde_json::from_str(
+//! #     &std::fs::read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap(),
+//! # ).unwrap();
+//! let query: Query = r#"packages where license contains "MPL-*" select name"#.parse().unwrap();
+//! let matches = query.evaluate(&spdx);
+//! assert_eq!(matches[0].values["name"], "Saxon");
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use log::info;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    error::SpdxError,
+    models::{LicenseTree, RelationshipType, SpdxExpression, SPDX},
+    policy::ElementType,
+};
+
+/// Fields containing license expressions.
+const LICENSE_FIELDS: [&str; 5] = [
+    "licenseConcluded",
+    "licenseDeclared",
+    "licenseInfoInFiles",
+    "licenseInfoFromFiles",
+    "licenseInfoInSnippets",
+];
+
+/// A parsed query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    selector: Selector,
+    condition: Option<Condition>,
+    projection: Vec<String>,
+}
+
+/// An element that matched a query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryMatch {
+    pub element_type: ElementType,
+    pub spdx_id: String,
+
+    /// The selected fields of the element, or all fields if the query has no `select` clause.
+    /// Selected fields that the element doesn't have are `null`.
+    pub values: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Selector {
+    Elements,
+    Packages,
+    Files,
+    Snippets,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Equal(String, String),
+    NotEqual(String, String),
+    Contains(String, String),
+    Has(String),
+    Related(Traversal),
+}
+
+/// Relationships leading from or to an element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Traversal {
+    direction: Direction,
+    spdx_id: String,
+    relationship_type: Option<RelationshipType>,
+    transitive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Direction {
+    From,
+    To,
+}
+
+impl Query {
+    /// Parse a query.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Query`] if the query is not valid.
+    pub fn parse(input: &str) -> Result<Self, SpdxError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        let query = parser.query()?;
+        parser
+            .next()
+            .map_or(Ok(query), |token| Err(unexpected(&token)))
+    }
+
+    /// The fields picked by the `select` clause.
+    pub fn projection(&self) -> &[String] {
+        &self.projection
+    }
+
+    /// Find the elements of the document that match the query, packages first, then files and
+    /// snippets, each in the order of the document.
+    pub fn evaluate(&self, spdx: &SPDX) -> Vec<QueryMatch> {
+        info!("Evaluating a query against SPDX.");
+        let context = Context {
+            spdx,
+            traversals: RefCell::new(HashMap::new()),
+        };
+
+        elements(spdx, self.selector)
+            .into_iter()
+            .filter(|element| {
+                self.condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.matches(element, &context))
+            })
+            .map(|element| QueryMatch {
+                element_type: element.element_type,
+                values: if self.projection.is_empty() {
+                    element.values
+                } else {
+                    self.projection
+                        .iter()
+                        .map(|field| {
+                            let values = field_values(&element.values, field);
+                            let value = match values.as_slice() {
+                                [] => Value::Null,
+                                [value] => (*value).clone(),
+                                _ => Value::Array(values.into_iter().cloned().collect()),
+                            };
+                            (field.clone(), value)
+                        })
+                        .collect()
+                },
+                spdx_id: element.spdx_id,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Query {
+    type Err = SpdxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// A package, file or snippet in its JSON representation, to be matched against a query.
+struct Candidate {
+    element_type: ElementType,
+    spdx_id: String,
+    values: Map<String, Value>,
+}
+
+/// Data shared by the evaluation of all elements.
+struct Context<'a> {
+    spdx: &'a SPDX,
+
+    /// IDs of the elements found by the traversals evaluated so far.
+    traversals: RefCell<HashMap<Traversal, HashSet<String>>>,
+}
+
+impl Condition {
+    fn matches(&self, element: &Candidate, context: &Context) -> bool {
+        match self {
+            Self::And(left, right) => {
+                left.matches(element, context) && right.matches(element, context)
+            }
+            Self::Or(left, right) => {
+                left.matches(element, context) || right.matches(element, context)
+            }
+            Self::Not(condition) => !condition.matches(element, context),
+            Self::Equal(field, value) => field_values(&element.values, field)
+                .into_iter()
+                .any(|field_value| value_eq(field_value, value)),
+            Self::NotEqual(field, value) => !field_values(&element.values, field)
+                .into_iter()
+                .any(|field_value| value_eq(field_value, value)),
+            Self::Contains(field, value) => {
+                if field == "license" || LICENSE_FIELDS.contains(&field.as_str()) {
+                    license_identifiers(element, field)
+                        .iter()
+                        .any(|license| license_matches(license, value))
+                } else {
+                    let value = value.to_lowercase();
+                    field_values(&element.values, field)
+                        .into_iter()
+                        .filter_map(value_str)
+                        .any(|field_value| field_value.to_lowercase().contains(&value))
+                }
+            }
+            Self::Has(field) => field_values(&element.values, field)
+                .into_iter()
+                .any(|value| match value {
+                    Value::Null => false,
+                    Value::String(value) => !value.is_empty() && value != "NOASSERTION",
+                    Value::Array(values) => !values.is_empty(),
+                    _ => true,
+                }),
+            Self::Related(traversal) => context
+                .traversals
+                .borrow_mut()
+                .entry(traversal.clone())
+                .or_insert_with(|| traversal.elements(context.spdx))
+                .contains(&element.spdx_id),
+        }
+    }
+}
+
+impl Traversal {
+    /// IDs of the elements reached by following the relationships.
+    fn elements(&self, spdx: &SPDX) -> HashSet<String> {
+        let mut found = HashSet::new();
+        let mut queue = vec![self.spdx_id.as_str()];
+        while let Some(current) = queue.pop() {
+            for relationship in &spdx.relationships {
+                if self
+                    .relationship_type
+                    .as_ref()
+                    .is_some_and(|relationship_type| {
+                        *relationship_type != relationship.relationship_type
+                    })
+                {
+                    continue;
+                }
+                let (source, target) = match self.direction {
+                    Direction::From => (
+                        &relationship.spdx_element_id,
+                        &relationship.related_spdx_element,
+                    ),
+                    Direction::To => (
+                        &relationship.related_spdx_element,
+                        &relationship.spdx_element_id,
+                    ),
+                };
+                if source == current && found.insert(target.clone()) && self.transitive {
+                    queue.push(target);
+                }
+            }
+        }
+        found
+    }
+}
+
+/// The packages, files and snippets of the document selected by the selector.
+fn elements(spdx: &SPDX, selector: Selector) -> Vec<Candidate> {
+    let mut elements = Vec::new();
+    let mut push = |element_type, spdx_id: &str, value: serde_json::Result<Value>| {
+        if let Ok(Value::Object(values)) = value {
+            elements.push(Candidate {
+                element_type,
+                spdx_id: spdx_id.to_string(),
+                values,
+            });
+        }
+    };
+
+    if matches!(selector, Selector::Elements | Selector::Packages) {
+        for package in &spdx.package_information {
+            push(
+                ElementType::Package,
+                &package.package_spdx_identifier,
+                serde_json::to_value(package),
+            );
+        }
+    }
+    if matches!(selector, Selector::Elements | Selector::Files) {
+        for file in &spdx.file_information {
+            push(
+                ElementType::File,
+                &file.file_spdx_identifier,
+                serde_json::to_value(file),
+            );
+        }
+    }
+    if matches!(selector, Selector::Elements | Selector::Snippets) {
+        for snippet in &spdx.snippet_information {
+            push(
+                ElementType::Snippet,
+                &snippet.snippet_spdx_identifier,
+                serde_json::to_value(snippet),
+            );
+        }
+    }
+    elements
+}
+
+/// Values of a possibly nested field. Arrays on the path are flattened.
+fn field_values<'a>(values: &'a Map<String, Value>, field: &str) -> Vec<&'a Value> {
+    let mut parts = field.split('.');
+    let mut current = parts
+        .next()
+        .and_then(|part| values.get(part))
+        .map_or_else(Vec::new, flatten);
+    for part in parts {
+        current = current
+            .into_iter()
+            .filter_map(|value| value.get(part))
+            .flat_map(flatten)
+            .collect();
+    }
+    current
+}
+
+fn flatten(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    }
+}
+
+fn value_str(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn value_eq(field_value: &Value, value: &str) -> bool {
+    value_str(field_value).is_some_and(|field_value| field_value == value)
+}
+
+/// License and exception identifiers used in the license expressions of the field, or of all
+/// license fields for `license`.
+fn license_identifiers(element: &Candidate, field: &str) -> Vec<String> {
+    let fields = if field == "license" {
+        LICENSE_FIELDS.to_vec()
+    } else {
+        vec![field]
+    };
+    fields
+        .into_iter()
+        .flat_map(|field| field_values(&element.values, field))
+        .filter_map(Value::as_str)
+        .filter_map(|expression| SpdxExpression::parse(expression).ok())
+        .flat_map(|expression| {
+            LicenseTree::from(&expression)
+                .terms()
+                .into_iter()
+                .flat_map(|term| {
+                    std::iter::once(term.license.clone()).chain(term.exception.clone())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Compare identifiers ignoring case. A pattern ending in `*` matches the prefix.
+fn license_matches(license: &str, pattern: &str) -> bool {
+    pattern.strip_suffix('*').map_or_else(
+        || license.eq_ignore_ascii_case(pattern),
+        |prefix| {
+            license
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        },
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A keyword, a field or an unquoted value.
+    Word(String),
+    Quoted(String),
+    Equal,
+    NotEqual,
+    Comma,
+    OpenParen,
+    CloseParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, SpdxError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '=' => {
+                chars.next();
+                tokens.push(Token::Equal);
+            }
+            '!' => {
+                chars.next();
+                if chars.next() != Some('=') {
+                    return Err(SpdxError::Query("expected `!=`".to_string()));
+                }
+                tokens.push(Token::NotEqual);
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::OpenParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::CloseParen);
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) => value.push(c),
+                            None => break,
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(SpdxError::Query("unterminated string".to_string())),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '=' | '!' | ',' | '(' | ')' | '"') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn unexpected(token: &Token) -> SpdxError {
+    SpdxError::Query(match token {
+        Token::Word(word) => format!("unexpected `{word}`"),
+        Token::Quoted(value) => format!("unexpected \"{value}\""),
+        Token::Equal => "unexpected `=`".to_string(),
+        Token::NotEqual => "unexpected `!=`".to_string(),
+        Token::Comma => "unexpected `,`".to_string(),
+        Token::OpenParen => "unexpected `(`".to_string(),
+        Token::CloseParen => "unexpected `)`".to_string(),
+    })
+}
+
+/// Recursive descent parser of queries. `not` binds tighter than `and`, which binds tighter
+/// than `or`.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    /// Consume the next token if it is the keyword.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found =
+            matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn error(&self, expected: &str) -> SpdxError {
+        self.peek().map_or_else(
+            || SpdxError::Query(format!("{expected} at the end of the query")),
+            |token| SpdxError::Query(format!("{expected}, {}", unexpected(token))),
+        )
+    }
+
+    fn query(&mut self) -> Result<Query, SpdxError> {
+        let Some(Token::Word(word)) = self.next() else {
+            self.position -= 1;
+            return Err(self.error("expected an element type"));
+        };
+        let selector = match word.to_lowercase().as_str() {
+            "elements" | "element" | "*" => Selector::Elements,
+            "packages" | "package" => Selector::Packages,
+            "files" | "file" => Selector::Files,
+            "snippets" | "snippet" => Selector::Snippets,
+            _ => {
+                return Err(SpdxError::Query(format!(
+                    "unknown element type `{word}`, expected `packages`, `files`, \
+                         `snippets` or `elements`"
+                )))
+            }
+        };
+
+        let condition = if self.keyword("where") {
+            Some(self.or()?)
+        } else {
+            None
+        };
+
+        let mut projection = Vec::new();
+        if self.keyword("select") {
+            projection.push(self.field()?);
+            while self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+                projection.push(self.field()?);
+            }
+        }
+
+        Ok(Query {
+            selector,
+            condition,
+            projection,
+        })
+    }
+
+    fn or(&mut self) -> Result<Condition, SpdxError> {
+        let mut condition = self.and()?;
+        while self.keyword("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, SpdxError> {
+        let mut condition = self.not()?;
+        while self.keyword("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, SpdxError> {
+        if self.keyword("not") {
+            Ok(Condition::Not(Box::new(self.not()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Condition, SpdxError> {
+        if self.peek() == Some(&Token::OpenParen) {
+            self.position += 1;
+            let condition = self.or()?;
+            if self.peek() != Some(&Token::CloseParen) {
+                return Err(self.error("expected `)`"));
+            }
+            self.position += 1;
+            return Ok(condition);
+        }
+        if self.keyword("has") {
+            return Ok(Condition::Has(self.field()?));
+        }
+        let transitive = self.keyword("reachable");
+        if self.keyword("from") {
+            return self.traversal(Direction::From, transitive);
+        }
+        if self.keyword("to") {
+            return self.traversal(Direction::To, transitive);
+        }
+        if transitive {
+            return Err(self.error("expected `from` or `to`"));
+        }
+
+        let field = self.field()?;
+        if self.keyword("contains") {
+            return Ok(Condition::Contains(field, self.value()?));
+        }
+        match self.next() {
+            Some(Token::Equal) => Ok(Condition::Equal(field, self.value()?)),
+            Some(Token::NotEqual) => Ok(Condition::NotEqual(field, self.value()?)),
+            _ => {
+                self.position -= 1;
+                Err(self.error("expected `=`, `!=` or `contains`"))
+            }
+        }
+    }
+
+    fn traversal(
+        &mut self,
+        direction: Direction,
+        transitive: bool,
+    ) -> Result<Condition, SpdxError> {
+        let spdx_id = self.value()?;
+        let relationship_type = if self.keyword("via") {
+            let name = self.value()?.to_uppercase();
+            Some(
+                serde_json::from_value(Value::String(name.clone()))
+                    .map_err(|_| SpdxError::Query(format!("unknown relationship type `{name}`")))?,
+            )
+        } else {
+            None
+        };
+        Ok(Condition::Related(Traversal {
+            direction,
+            spdx_id,
+            relationship_type,
+            transitive,
+        }))
+    }
+
+    fn field(&mut self) -> Result<String, SpdxError> {
+        if let Some(Token::Word(word)) = self.next() {
+            Ok(word)
+        } else {
+            self.position -= 1;
+            Err(self.error("expected a field"))
+        }
+    }
+
+    fn value(&mut self) -> Result<String, SpdxError> {
+        if let Some(Token::Word(value) | Token::Quoted(value)) = self.next() {
+            Ok(value)
+        } else {
+            self.position -= 1;
+            Err(self.error("expected a value"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    fn ids(query: &str) -> Vec<String> {
+        Query::parse(query)
+            .unwrap()
+            .evaluate(&example())
+            .into_iter()
+            .map(|found| found.spdx_id)
+            .collect()
+    }
+
+    #[test]
+    fn elements_are_selected_by_type_and_fields() {
+        assert_eq!(ids("packages").len(), 4);
+        assert_eq!(ids("elements").len(), 10);
+        assert_eq!(
+            ids("files where fileName = ./package/foo.c"),
+            ["SPDXRef-File"]
+        );
+        assert_eq!(
+            ids(r#"PACKAGES WHERE name contains "commons" OR name = Saxon"#),
+            ["SPDXRef-fromDoap-1", "SPDXRef-Saxon"]
+        );
+        assert_eq!(
+            ids("packages where checksums.algorithm = SHA256"),
+            ["SPDXRef-Package"]
+        );
+        assert_eq!(
+            ids("packages where not has supplier and name != Jena"),
+            ["SPDXRef-fromDoap-1", "SPDXRef-Saxon"]
+        );
+    }
+
+    #[test]
+    fn license_expressions_are_searched() {
+        assert_eq!(
+            ids("packages where license contains lgpl-2.0-only"),
+            ["SPDXRef-Package"]
+        );
+        assert_eq!(
+            ids("elements where licenseConcluded contains \"Apache-*\""),
+            ["SPDXRef-DoapSource", "SPDXRef-CommonsLangSrc"]
+        );
+        assert_eq!(
+            ids("elements where license contains GPL-2.0-only"),
+            ["SPDXRef-Package", "SPDXRef-File", "SPDXRef-Snippet"]
+        );
+        assert!(ids("packages where licenseDeclared contains GPL-*").is_empty());
+    }
+
+    #[test]
+    fn relationships_are_traversed() {
+        assert_eq!(
+            ids("packages where from SPDXRef-Package via dynamic_link"),
+            ["SPDXRef-Saxon"]
+        );
+        assert_eq!(
+            ids("packages where from SPDXRef-JenaLib via CONTAINS"),
+            ["SPDXRef-Package"]
+        );
+        assert_eq!(
+            ids("packages where reachable from SPDXRef-JenaLib"),
+            ["SPDXRef-Package", "SPDXRef-Saxon"]
+        );
+        assert_eq!(
+            ids("files where to SPDXRef-fromDoap-0 via GENERATED_FROM"),
+            ["SPDXRef-File"]
+        );
+    }
+
+    #[test]
+    fn fields_are_projected() {
+        let matches = Query::parse("packages where name = glibc select name, checksums.algorithm")
+            .unwrap()
+            .evaluate(&example());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].element_type, ElementType::Package);
+        assert_eq!(
+            Value::Object(matches[0].values.clone()),
+            serde_json::json!({ "name": "glibc", "checksums.algorithm": ["MD5", "SHA1", "SHA256", "BLAKE2b-384"] })
+        );
+    }
+
+    #[test]
+    fn invalid_queries_are_rejected() {
+        for query in [
+            "",
+            "documents",
+            "packages where",
+            "packages where name",
+            "packages where (name = a",
+            "packages where from X via NOT_A_TYPE",
+            "packages where name = \"a",
+            "packages select",
+            "packages name = a",
+        ] {
+            assert!(Query::parse(query).is_err(), "{}", query);
+        }
+    }
+}
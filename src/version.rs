@@ -0,0 +1,216 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Comparison of free-form version strings.
+//!
+//! Versions found in SPDX documents and vulnerability feeds don't follow a single scheme, so the
+//! comparison is done by splitting the versions into numeric and alphabetic segments and comparing
+//! them pairwise. Alphabetic segments after the release segments (e.g. `1.0.0-rc1`) are considered
+//! pre-releases and sort before the release.
+
+use std::cmp::Ordering;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Numeric(u64),
+    Alphabetic(&'a str),
+}
+
+fn segments(version: &str) -> Vec<Segment<'_>> {
+    let version = version.trim();
+    let version = version
+        .strip_prefix('v')
+        .or_else(|| version.strip_prefix('V'))
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(version);
+
+    // Build metadata doesn't affect precedence.
+    let version = version.split('+').next().unwrap_or(version);
+
+    let mut segments = Vec::new();
+    let mut current: Option<(usize, bool)> = None;
+
+    for (index, character) in version.char_indices() {
+        let kind = if character.is_ascii_digit() {
+            Some(true)
+        } else if character.is_alphabetic() {
+            Some(false)
+        } else {
+            None
+        };
+
+        match (current, kind) {
+            (Some((_, numeric)), Some(is_numeric)) if numeric == is_numeric => {}
+            (Some((start, numeric)), _) => {
+                segments.push(segment(&version[start..index], numeric));
+                current = kind.map(|is_numeric| (index, is_numeric));
+            }
+            (None, _) => current = kind.map(|is_numeric| (index, is_numeric)),
+        }
+    }
+    if let Some((start, numeric)) = current {
+        segments.push(segment(&version[start..], numeric));
+    }
+
+    segments
+}
+
+fn segment(value: &str, numeric: bool) -> Segment<'_> {
+    if numeric {
+        // Fall back to the maximum for numbers that don't fit, they're most likely dates or hashes.
+        Segment::Numeric(value.parse().unwrap_or(u64::MAX))
+    } else {
+        Segment::Alphabetic(value)
+    }
+}
+
+/// Compare two version strings.
+pub fn compare_versions(left: &str, right: &str) -> Ordering {
+    let left = segments(left);
+    let right = segments(right);
+
+    for (left_segment, right_segment) in left.iter().zip(right.iter()) {
+        let ordering = match (left_segment, right_segment) {
+            (Segment::Numeric(left), Segment::Numeric(right)) => left.cmp(right),
+            (Segment::Alphabetic(left), Segment::Alphabetic(right)) => {
+                left.to_lowercase().cmp(&right.to_lowercase())
+            }
+            (Segment::Numeric(_), Segment::Alphabetic(_)) => Ordering::Greater,
+            (Segment::Alphabetic(_), Segment::Numeric(_)) => Ordering::Less,
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    let common = left.len().min(right.len());
+    match (
+        remainder_ordering(&left[common..]),
+        remainder_ordering(&right[common..]),
+    ) {
+        (Ordering::Equal, Ordering::Equal) => Ordering::Equal,
+        (ordering, Ordering::Equal) => ordering,
+        (_, ordering) => ordering.reverse(),
+    }
+}
+
+/// Compare the segments left over after the common prefix against an empty version. Trailing zeros
+/// don't change the version, a trailing alphabetic segment marks a pre-release.
+fn remainder_ordering(remainder: &[Segment]) -> Ordering {
+    match remainder
+        .iter()
+        .find(|segment| **segment != Segment::Numeric(0))
+    {
+        Some(Segment::Numeric(_)) => Ordering::Greater,
+        Some(Segment::Alphabetic(_)) => Ordering::Less,
+        None => Ordering::Equal,
+    }
+}
+
+/// Compare two versions by [Semantic Versioning](https://semver.org) precedence. Falls back to
+/// [`compare_versions`] if either of the versions is not a valid semantic version.
+pub fn compare_semver(left: &str, right: &str) -> Ordering {
+    match (parse_semver(left), parse_semver(right)) {
+        (Some(left), Some(right)) => left
+            .0
+            .cmp(&right.0)
+            .then_with(|| compare_pre_release(&left.1, &right.1)),
+        _ => compare_versions(left, right),
+    }
+}
+
+type SemanticVersion<'a> = ([u64; 3], Vec<&'a str>);
+
+fn parse_semver(version: &str) -> Option<SemanticVersion<'_>> {
+    let version = version.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let version = version.split('+').next().unwrap_or(version);
+    let (release, pre_release) = version
+        .split_once('-')
+        .map_or((version, None), |(release, pre_release)| {
+            (release, Some(pre_release))
+        });
+
+    let mut numbers = [0; 3];
+    let mut parts = release.split('.');
+    for number in &mut numbers {
+        *number = parts.next()?.parse().ok()?;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let pre_release =
+        pre_release.map_or_else(Vec::new, |pre_release| pre_release.split('.').collect());
+
+    Some((numbers, pre_release))
+}
+
+fn compare_pre_release(left: &[&str], right: &[&str]) -> Ordering {
+    match (left.is_empty(), right.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => {}
+    }
+
+    for (left, right) in left.iter().zip(right.iter()) {
+        let ordering = match (left.parse::<u64>(), right.parse::<u64>()) {
+            (Ok(left), Ok(right)) => left.cmp(&right),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => left.cmp(right),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    left.len().cmp(&right.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn numeric_segments_are_compared_numerically() {
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v2.0", "2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2", "1.2.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn pre_releases_sort_before_releases() {
+        assert_eq!(compare_versions("1.0.0-rc1", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-alpha", "1.0.0-beta"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("1.0.0-rc2", "1.0.0-rc10"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0+build5", "1.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn semver_precedence_is_followed() {
+        assert_eq!(
+            compare_semver("1.0.0-alpha", "1.0.0-alpha.1"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_semver("1.0.0-alpha.1", "1.0.0-alpha.beta"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_semver("1.0.0-beta.11", "1.0.0-rc.1"),
+            Ordering::Less
+        );
+        assert_eq!(compare_semver("1.0.0-rc.1", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_semver("2.1.1", "2.1.0"), Ordering::Greater);
+        assert_eq!(compare_semver("2.1", "2.1.0"), Ordering::Equal);
+    }
+}
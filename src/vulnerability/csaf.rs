@@ -0,0 +1,533 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Documents of the [CSAF](https://docs.oasis-open.org/csaf/csaf/v2.0/csaf-v2.0.html) VEX profile.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::SpdxError;
+
+use super::{VexStatement, VexStatus};
+
+/// A CSAF document. Only the parts used by the VEX profile are supported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafVexDocument {
+    pub document: CsafDocument,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub product_tree: Option<CsafProductTree>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub vulnerabilities: Vec<CsafVulnerability>,
+}
+
+/// Document level metadata.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafDocument {
+    pub category: String,
+    pub csaf_version: String,
+    pub title: String,
+    pub publisher: CsafPublisher,
+    pub tracking: CsafTracking,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafPublisher {
+    pub category: String,
+    pub name: String,
+    pub namespace: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafTracking {
+    pub id: String,
+    pub status: String,
+    pub version: String,
+    pub initial_release_date: DateTime<Utc>,
+    pub current_release_date: DateTime<Utc>,
+    #[serde(default)]
+    pub revision_history: Vec<CsafRevision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafRevision {
+    pub date: DateTime<Utc>,
+    pub number: String,
+    pub summary: String,
+}
+
+/// Products referred to by the vulnerabilities.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct CsafProductTree {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub branches: Vec<CsafBranch>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub full_product_names: Vec<CsafProduct>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub product_groups: Vec<CsafProductGroup>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub relationships: Vec<CsafRelationship>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafBranch {
+    pub category: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub branches: Vec<CsafBranch>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub product: Option<CsafProduct>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafProduct {
+    pub product_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub product_identification_helper: Option<CsafProductIdentificationHelper>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafProductIdentificationHelper {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub purl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cpe: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafProductGroup {
+    pub group_id: String,
+    pub product_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafRelationship {
+    pub category: String,
+    pub full_product_name: CsafProduct,
+    pub product_reference: String,
+    pub relates_to_product_reference: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafVulnerability {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cve: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub ids: Vec<CsafId>,
+
+    #[serde(default)]
+    pub product_status: CsafProductStatus,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub flags: Vec<CsafFlag>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub threats: Vec<CsafNote>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub remediations: Vec<CsafNote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafId {
+    pub system_name: String,
+    pub text: String,
+}
+
+/// Product IDs grouped by their status.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct CsafProductStatus {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub first_affected: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub known_affected: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub last_affected: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub known_not_affected: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub first_fixed: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub fixed: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub under_investigation: Vec<String>,
+}
+
+/// A flag, used by the VEX profile for the justification of `known_not_affected` products.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafFlag {
+    pub label: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub product_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub group_ids: Vec<String>,
+}
+
+/// A threat or a remediation. The VEX profile uses the `impact` threats as the impact statement
+/// and the remediations as the action statement.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsafNote {
+    pub category: String,
+    pub details: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub product_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub group_ids: Vec<String>,
+}
+
+impl CsafVexDocument {
+    /// Create a VEX profile document from statements. The products are identified in the product
+    /// tree by the product strings of the statements.
+    pub fn new(
+        id: &str,
+        publisher: &str,
+        publisher_namespace: &str,
+        timestamp: DateTime<Utc>,
+        statements: &[VexStatement],
+    ) -> Self {
+        let mut products: Vec<CsafProduct> = Vec::new();
+        let mut vulnerabilities: BTreeMap<&str, CsafVulnerability> = BTreeMap::new();
+
+        for statement in statements {
+            for product in &statement.products {
+                if !products
+                    .iter()
+                    .any(|existing| existing.product_id == *product)
+                {
+                    products.push(CsafProduct {
+                        product_id: product.clone(),
+                        name: product.clone(),
+                        product_identification_helper: product.starts_with("pkg:").then(|| {
+                            CsafProductIdentificationHelper {
+                                purl: Some(product.clone()),
+                                cpe: None,
+                            }
+                        }),
+                    });
+                }
+            }
+
+            vulnerabilities
+                .entry(&statement.vulnerability)
+                .or_insert_with(|| CsafVulnerability::new(&statement.vulnerability))
+                .add_statement(statement);
+        }
+
+        Self {
+            document: CsafDocument {
+                category: "csaf_vex".to_string(),
+                csaf_version: "2.0".to_string(),
+                title: id.to_string(),
+                publisher: CsafPublisher {
+                    category: "vendor".to_string(),
+                    name: publisher.to_string(),
+                    namespace: publisher_namespace.to_string(),
+                },
+                tracking: CsafTracking {
+                    id: id.to_string(),
+                    status: "final".to_string(),
+                    version: "1".to_string(),
+                    initial_release_date: timestamp,
+                    current_release_date: timestamp,
+                    revision_history: vec![CsafRevision {
+                        date: timestamp,
+                        number: "1".to_string(),
+                        summary: "Initial version.".to_string(),
+                    }],
+                },
+            },
+            product_tree: Some(CsafProductTree {
+                full_product_names: products,
+                ..CsafProductTree::default()
+            }),
+            vulnerabilities: vulnerabilities.into_values().collect(),
+        }
+    }
+
+    /// Parse a CSAF document from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if the input is not a valid CSAF document.
+    pub fn from_json(input: &str) -> Result<Self, SpdxError> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Serialize the document to JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, SpdxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The statements of the document in the format independent representation, one for every
+    /// vulnerability and status. The products are identified by the purl of the product
+    /// identification helper, or by the product ID if the product doesn't have one.
+    pub fn statements(&self) -> Vec<VexStatement> {
+        let identifiers = self.product_identifiers();
+        let groups: HashMap<&str, &[String]> = self
+            .product_tree
+            .iter()
+            .flat_map(|tree| &tree.product_groups)
+            .map(|group| (group.group_id.as_str(), group.product_ids.as_slice()))
+            .collect();
+
+        // Product IDs of a flag or a note, including the members of its groups.
+        let product_ids = |product_ids: &[String], group_ids: &[String]| -> Vec<String> {
+            product_ids
+                .iter()
+                .chain(
+                    group_ids
+                        .iter()
+                        .filter_map(|group_id| groups.get(group_id.as_str()))
+                        .flat_map(|ids| ids.iter()),
+                )
+                .cloned()
+                .collect()
+        };
+
+        let mut statements = Vec::new();
+        for vulnerability in &self.vulnerabilities {
+            let Some(name) = vulnerability
+                .cve
+                .clone()
+                .or_else(|| vulnerability.ids.first().map(|id| id.text.clone()))
+            else {
+                continue;
+            };
+
+            let status = &vulnerability.product_status;
+            for (status, ids) in [
+                (
+                    VexStatus::Affected,
+                    [
+                        status.first_affected.as_slice(),
+                        &status.known_affected,
+                        &status.last_affected,
+                    ]
+                    .concat(),
+                ),
+                (VexStatus::NotAffected, status.known_not_affected.clone()),
+                (
+                    VexStatus::Fixed,
+                    [status.first_fixed.as_slice(), &status.fixed].concat(),
+                ),
+                (
+                    VexStatus::UnderInvestigation,
+                    status.under_investigation.clone(),
+                ),
+            ] {
+                // Statements are split by product, as the justification and the notes may differ.
+                for id in ids {
+                    let flag = vulnerability
+                        .flags
+                        .iter()
+                        .find(|flag| product_ids(&flag.product_ids, &flag.group_ids).contains(&id));
+                    let note = |notes: &[CsafNote], category: Option<&str>| {
+                        notes
+                            .iter()
+                            .filter(|note| {
+                                category.is_none_or(|category| note.category == category)
+                            })
+                            .find(|note| {
+                                product_ids(&note.product_ids, &note.group_ids).contains(&id)
+                            })
+                            .map(|note| note.details.clone())
+                    };
+
+                    statements.push(VexStatement {
+                        vulnerability: name.clone(),
+                        products: identifiers
+                            .get(&id)
+                            .cloned()
+                            .unwrap_or_else(|| vec![id.clone()]),
+                        status,
+                        justification: flag.and_then(|flag| flag.label.parse().ok()),
+                        impact_statement: note(&vulnerability.threats, Some("impact")),
+                        action_statement: note(&vulnerability.remediations, None),
+                        timestamp: Some(self.document.tracking.current_release_date),
+                    });
+                }
+            }
+        }
+
+        statements
+    }
+
+    /// Map product IDs to the identifiers used in [`VexStatement`]s.
+    fn product_identifiers(&self) -> HashMap<String, Vec<String>> {
+        fn collect(product: &CsafProduct, identifiers: &mut HashMap<String, Vec<String>>) {
+            let purl = product
+                .product_identification_helper
+                .as_ref()
+                .and_then(|helper| helper.purl.clone());
+            identifiers.insert(
+                product.product_id.clone(),
+                vec![purl.unwrap_or_else(|| product.product_id.clone())],
+            );
+        }
+
+        fn collect_branches(
+            branches: &[CsafBranch],
+            identifiers: &mut HashMap<String, Vec<String>>,
+        ) {
+            for branch in branches {
+                if let Some(product) = &branch.product {
+                    collect(product, identifiers);
+                }
+                collect_branches(&branch.branches, identifiers);
+            }
+        }
+
+        let mut identifiers = HashMap::new();
+        if let Some(tree) = &self.product_tree {
+            collect_branches(&tree.branches, &mut identifiers);
+            for product in tree.full_product_names.iter().chain(
+                tree.relationships
+                    .iter()
+                    .map(|relationship| &relationship.full_product_name),
+            ) {
+                collect(product, &mut identifiers);
+            }
+        }
+        identifiers
+    }
+}
+
+impl CsafVulnerability {
+    fn new(name: &str) -> Self {
+        let is_cve = name.starts_with("CVE-");
+        Self {
+            cve: is_cve.then(|| name.to_string()),
+            ids: if is_cve {
+                Vec::new()
+            } else {
+                vec![CsafId {
+                    system_name: "VEX".to_string(),
+                    text: name.to_string(),
+                }]
+            },
+            product_status: CsafProductStatus::default(),
+            flags: Vec::new(),
+            threats: Vec::new(),
+            remediations: Vec::new(),
+        }
+    }
+
+    fn add_statement(&mut self, statement: &VexStatement) {
+        let status = &mut self.product_status;
+        let list = match statement.status {
+            VexStatus::NotAffected => &mut status.known_not_affected,
+            VexStatus::Affected => &mut status.known_affected,
+            VexStatus::Fixed => &mut status.fixed,
+            VexStatus::UnderInvestigation => &mut status.under_investigation,
+        };
+        list.extend(statement.products.iter().cloned());
+
+        if let Some(justification) = statement.justification {
+            self.flags.push(CsafFlag {
+                label: justification.to_string(),
+                product_ids: statement.products.clone(),
+                group_ids: Vec::new(),
+            });
+        }
+        if let Some(impact) = &statement.impact_statement {
+            self.threats.push(CsafNote {
+                category: "impact".to_string(),
+                details: impact.clone(),
+                product_ids: statement.products.clone(),
+                group_ids: Vec::new(),
+            });
+        }
+        if let Some(action) = &statement.action_statement {
+            self.remediations.push(CsafNote {
+                category: if statement.status == VexStatus::Fixed {
+                    "vendor_fix"
+                } else {
+                    "workaround"
+                }
+                .to_string(),
+                details: action.clone(),
+                product_ids: statement.products.clone(),
+                group_ids: Vec::new(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use chrono::TimeZone;
+
+    use crate::vulnerability::VexJustification;
+
+    use super::*;
+
+    #[test]
+    fn csaf_vex_document_is_parsed() {
+        let document =
+            CsafVexDocument::from_json(&read_to_string("tests/data/vex/csaf.json").unwrap())
+                .unwrap();
+        let statements = document.statements();
+        assert_eq!(statements.len(), 2);
+
+        let saxon = &statements[0];
+        assert_eq!(saxon.products, vec!["SPDXRef-Saxon".to_string()]);
+        assert_eq!(saxon.status, VexStatus::Affected);
+        assert_eq!(saxon.action_statement, Some("Update to 9.0.".to_string()));
+
+        let jena = &statements[1];
+        assert_eq!(jena.vulnerability, "CVE-2022-28890");
+        assert_eq!(
+            jena.products,
+            vec!["pkg:maven/org.apache.jena/apache-jena@3.12.0".to_string()]
+        );
+        assert_eq!(jena.status, VexStatus::NotAffected);
+        assert_eq!(
+            jena.justification,
+            Some(VexJustification::VulnerableCodeNotInExecutePath)
+        );
+        assert!(jena.impact_statement.is_some());
+        assert_eq!(
+            jena.timestamp,
+            Some(Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn csaf_vex_document_roundtrips() {
+        let timestamp = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let mut statement = VexStatement::new(
+            "CVE-2022-28890",
+            vec!["pkg:maven/org.apache.jena/apache-jena@3.12.0".to_string()],
+            VexStatus::NotAffected,
+        );
+        statement.justification = Some(VexJustification::ComponentNotPresent);
+        statement.impact_statement = Some("Not used.".to_string());
+        statement.timestamp = Some(timestamp);
+
+        let document = CsafVexDocument::new(
+            "example-vex-1",
+            "Example",
+            "https://example.com",
+            timestamp,
+            &[statement.clone()],
+        );
+        let parsed = CsafVexDocument::from_json(&document.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, document);
+        assert_eq!(parsed.statements(), vec![statement]);
+    }
+}
@@ -0,0 +1,354 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Matching of SPDX packages against vulnerability records.
+//!
+//! Packages are identified by the purls and CPEs in their external references. The records are
+//! read from a local copy of an [OSV](https://osv.dev) database, so no network access is needed.
+//!
+//! The exploitability of the found vulnerabilities can be recorded with VEX statements, read from
+//! and written to [OpenVEX](https://openvex.dev) and CSAF VEX documents.
+
+use std::path::Path;
+
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::{
+    error::SpdxError,
+    models::{
+        CpeComponent, ExternalPackageReference, ExternalPackageReferenceCategory,
+        PackageInformation, SPDX,
+    },
+};
+
+mod csaf;
+mod openvex;
+mod osv;
+mod vex;
+
+pub use csaf::*;
+pub use openvex::*;
+pub use osv::*;
+pub use vex::*;
+
+/// A collection of OSV records to match packages against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OsvDatabase {
+    vulnerabilities: Vec<OsvVulnerability>,
+}
+
+/// How a package was matched to a vulnerability.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "type", content = "locator")]
+pub enum MatchSource {
+    Purl(String),
+    Cpe(String),
+}
+
+/// Whether the version of a matched package is known to be affected.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum FindingStatus {
+    /// The version is enumerated in the record or inside one of its ranges.
+    Affected,
+    /// The version couldn't be evaluated against a `GIT` range of the record, so it needs to be
+    /// checked manually.
+    Undetermined,
+}
+
+/// A package affected, or possibly affected, by a vulnerability.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VulnerabilityFinding {
+    /// SPDX ID of the affected package.
+    pub spdx_id: String,
+    pub package_name: String,
+    pub package_version: Option<String>,
+    pub vulnerability_id: String,
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    pub status: FindingStatus,
+    /// The external reference of the package that matched the record.
+    pub matched_by: MatchSource,
+    /// Versions in which the vulnerability has been fixed, if known.
+    pub fixed_versions: Vec<String>,
+}
+
+impl OsvDatabase {
+    /// Create a database from already parsed records. Withdrawn records are ignored.
+    pub fn new(vulnerabilities: Vec<OsvVulnerability>) -> Self {
+        Self {
+            vulnerabilities: vulnerabilities
+                .into_iter()
+                .filter(|vulnerability| vulnerability.withdrawn.is_none())
+                .collect(),
+        }
+    }
+
+    /// Read all OSV records from a directory, e.g. an extracted export of osv.dev.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the directory or any of the records can't be read.
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Self, SpdxError> {
+        Ok(Self::new(OsvVulnerability::from_dir(path)?))
+    }
+
+    /// All records in the database.
+    pub fn vulnerabilities(&self) -> &[OsvVulnerability] {
+        &self.vulnerabilities
+    }
+
+    /// Find the vulnerabilities affecting the packages of the SPDX. Packages whose version can't
+    /// be evaluated against a record are included as [`FindingStatus::Undetermined`].
+    pub fn find_vulnerabilities(&self, spdx: &SPDX) -> Vec<VulnerabilityFinding> {
+        info!(
+            "Matching packages against {} OSV records.",
+            self.vulnerabilities.len()
+        );
+
+        spdx.package_information
+            .iter()
+            .flat_map(|package| self.find_vulnerabilities_for_package(package))
+            .collect()
+    }
+
+    /// Find the vulnerabilities affecting a single package. The version is read from
+    /// `package_version`, falling back to the version in the matching locator.
+    pub fn find_vulnerabilities_for_package(
+        &self,
+        package: &PackageInformation,
+    ) -> Vec<VulnerabilityFinding> {
+        let mut purls = Vec::new();
+        let mut cpes = Vec::new();
+        for reference in &package.external_reference {
+            match (reference.purl(), reference.cpe()) {
+                (Some(Ok(purl)), _) => purls.push(purl),
+                (_, Some(Ok(cpe))) => cpes.push(cpe),
+                (Some(Err(error)), _) | (_, Some(Err(error))) => warn!(
+                    "Skipping reference of package {}: {}",
+                    package.package_spdx_identifier, error
+                ),
+                (None, None) => {}
+            }
+        }
+
+        let mut findings: Vec<VulnerabilityFinding> = Vec::new();
+        for vulnerability in &self.vulnerabilities {
+            // A definite match is preferred over one that couldn't be evaluated.
+            let matched_by = vulnerability
+                .affected
+                .iter()
+                .flat_map(|affected| {
+                    let purl_matches = purls.iter().filter_map(move |purl| {
+                        let version = package.package_version.as_ref().or(purl.version.as_ref())?;
+                        if !affected.matches_purl(purl) {
+                            return None;
+                        }
+                        let status = finding_status(affected.affects_version(version))?;
+                        Some((status, MatchSource::Purl(purl.to_string())))
+                    });
+
+                    let criteria = affected.cpes();
+                    let cpe_matches = cpes.iter().filter_map(move |cpe| {
+                        let version = package
+                            .package_version
+                            .clone()
+                            .or_else(|| cpe.version.unquoted())?;
+                        // The version is checked against the ranges instead of the criteria.
+                        let matches = criteria.iter().any(|criteria| {
+                            let mut criteria = criteria.clone();
+                            criteria.version = CpeComponent::Any;
+                            criteria.matches(cpe)
+                        });
+                        if !matches {
+                            return None;
+                        }
+                        let status = finding_status(affected.affects_version(&version))?;
+                        Some((status, MatchSource::Cpe(cpe.to_string())))
+                    });
+
+                    purl_matches.chain(cpe_matches)
+                })
+                .min_by_key(|(status, _)| *status);
+
+            if let Some((status, matched_by)) = matched_by {
+                findings.push(VulnerabilityFinding {
+                    spdx_id: package.package_spdx_identifier.clone(),
+                    package_name: package.package_name.clone(),
+                    package_version: package.package_version.clone(),
+                    vulnerability_id: vulnerability.id.clone(),
+                    aliases: vulnerability.aliases.clone(),
+                    summary: vulnerability.summary.clone(),
+                    status,
+                    matched_by,
+                    fixed_versions: vulnerability.fixed_versions(),
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+fn finding_status(affected: Option<bool>) -> Option<FindingStatus> {
+    match affected {
+        Some(true) => Some(FindingStatus::Affected),
+        Some(false) => None,
+        None => Some(FindingStatus::Undetermined),
+    }
+}
+
+/// Add a `SECURITY advisory` external reference pointing to osv.dev to every package with a
+/// finding. Existing references with the same locator are not duplicated.
+pub fn add_advisory_references(spdx: &mut SPDX, findings: &[VulnerabilityFinding]) {
+    for finding in findings {
+        let locator = format!("https://osv.dev/vulnerability/{}", finding.vulnerability_id);
+        for package in spdx
+            .package_information
+            .iter_mut()
+            .filter(|package| package.package_spdx_identifier == finding.spdx_id)
+        {
+            if !package
+                .external_reference
+                .iter()
+                .any(|reference| reference.reference_locator == locator)
+            {
+                package
+                    .external_reference
+                    .push(ExternalPackageReference::new(
+                        ExternalPackageReferenceCategory::Security,
+                        "advisory".to_string(),
+                        locator.clone(),
+                        finding.summary.clone(),
+                    ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+
+    fn spdx() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn withdrawn_records_are_ignored() {
+        let database = OsvDatabase::from_dir("tests/data/osv").unwrap();
+        assert_eq!(database.vulnerabilities().len(), 3);
+    }
+
+    #[test]
+    fn packages_are_matched_by_purl() {
+        let database = OsvDatabase::from_dir("tests/data/osv").unwrap();
+        let findings = database.find_vulnerabilities(&spdx());
+
+        let jena = findings
+            .iter()
+            .filter(|finding| finding.package_name == "Jena")
+            .collect::<Vec<_>>();
+        assert_eq!(jena.len(), 1);
+        assert_eq!(jena[0].vulnerability_id, "GHSA-xxxx-jena-0001");
+        assert_eq!(jena[0].aliases, vec!["CVE-2022-28890".to_string()]);
+        assert_eq!(jena[0].fixed_versions, vec!["4.5.0".to_string()]);
+        assert_eq!(jena[0].status, FindingStatus::Affected);
+        assert_eq!(
+            jena[0].matched_by,
+            MatchSource::Purl("pkg:maven/org.apache.jena/apache-jena@3.12.0".to_string())
+        );
+    }
+
+    #[test]
+    fn packages_are_matched_by_cpe() {
+        let database = OsvDatabase::from_dir("tests/data/osv").unwrap();
+        let mut spdx = spdx();
+
+        // The version of glibc in the example is outside the affected range.
+        assert!(!database
+            .find_vulnerabilities(&spdx)
+            .iter()
+            .any(|finding| finding.package_name == "glibc"));
+
+        let glibc = spdx
+            .package_information
+            .iter_mut()
+            .find(|package| package.package_name == "glibc")
+            .unwrap();
+        glibc.package_version = Some("4.1.0".to_string());
+        let findings = database.find_vulnerabilities_for_package(glibc);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].vulnerability_id, "OSV-2022-spring-0001");
+        assert!(matches!(findings[0].matched_by, MatchSource::Cpe(_)));
+    }
+
+    #[test]
+    fn unevaluable_git_ranges_are_undetermined() {
+        let record: OsvVulnerability = serde_json::from_str(
+            r#"{
+                "id": "OSV-git-0001",
+                "modified": "2023-01-10T12:00:00Z",
+                "affected": [
+                    {
+                        "package": {
+                            "ecosystem": "Maven",
+                            "name": "org.apache.jena:apache-jena"
+                        },
+                        "ranges": [
+                            {
+                                "type": "GIT",
+                                "repo": "https://github.com/apache/jena",
+                                "events": [{ "introduced": "a1b2c3" }, { "fixed": "d4e5f6" }]
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let database = OsvDatabase::new(vec![record]);
+
+        let findings = database.find_vulnerabilities(&spdx());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].package_name, "Jena");
+        assert_eq!(findings[0].vulnerability_id, "OSV-git-0001");
+        assert_eq!(findings[0].status, FindingStatus::Undetermined);
+    }
+
+    #[test]
+    fn advisory_references_are_added() {
+        let database = OsvDatabase::from_dir("tests/data/osv").unwrap();
+        let mut spdx = spdx();
+        let findings = database.find_vulnerabilities(&spdx);
+
+        add_advisory_references(&mut spdx, &findings);
+        add_advisory_references(&mut spdx, &findings);
+
+        let jena = spdx
+            .package_information
+            .iter()
+            .find(|package| package.package_name == "Jena")
+            .unwrap();
+        let advisories = jena
+            .external_reference
+            .iter()
+            .filter(|reference| reference.reference_type == "advisory")
+            .collect::<Vec<_>>();
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(
+            advisories[0].reference_locator,
+            "https://osv.dev/vulnerability/GHSA-xxxx-jena-0001"
+        );
+        assert_eq!(
+            advisories[0].reference_category,
+            ExternalPackageReferenceCategory::Security
+        );
+    }
+}
@@ -0,0 +1,291 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! [OpenVEX](https://github.com/openvex/spec) documents.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::SpdxError;
+
+use super::{VexJustification, VexStatement, VexStatus};
+
+const OPENVEX_CONTEXT: &str = "https://openvex.dev/ns/v0.2.0";
+
+/// A document in the `OpenVEX` format.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpenVexDocument {
+    #[serde(rename = "@context")]
+    pub context: String,
+
+    #[serde(rename = "@id")]
+    pub id: String,
+
+    pub author: String,
+
+    pub timestamp: DateTime<Utc>,
+
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    #[serde(default)]
+    pub statements: Vec<OpenVexStatement>,
+}
+
+const fn default_version() -> u32 {
+    1
+}
+
+/// A statement of an [`OpenVexDocument`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpenVexStatement {
+    pub vulnerability: OpenVexVulnerability,
+
+    #[serde(default)]
+    pub products: Vec<OpenVexProduct>,
+
+    pub status: VexStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub justification: Option<VexJustification>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub impact_statement: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub action_statement: Option<String>,
+
+    /// Defaults to the timestamp of the document.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Vulnerability of an [`OpenVexStatement`]. Versions before 0.2.0 of the spec used a plain
+/// string.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OpenVexVulnerability {
+    Name(String),
+    Object {
+        name: String,
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        aliases: Vec<String>,
+    },
+}
+
+impl OpenVexVulnerability {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Object { name, .. } => name,
+        }
+    }
+}
+
+/// Product of an [`OpenVexStatement`]. Versions before 0.2.0 of the spec used a plain string.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OpenVexProduct {
+    Id(String),
+    Object {
+        #[serde(rename = "@id")]
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        identifiers: Option<OpenVexIdentifiers>,
+    },
+}
+
+/// Alternative identifiers of an [`OpenVexProduct`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpenVexIdentifiers {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub purl: Option<String>,
+}
+
+impl OpenVexProduct {
+    /// The identifiers of the product, the `@id` first.
+    pub fn identifiers(&self) -> Vec<String> {
+        match self {
+            Self::Id(id) => vec![id.clone()],
+            Self::Object { id, identifiers } => {
+                let mut result = vec![id.clone()];
+                if let Some(purl) = identifiers
+                    .as_ref()
+                    .and_then(|identifiers| identifiers.purl.as_ref())
+                {
+                    if purl != id {
+                        result.push(purl.clone());
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+impl OpenVexDocument {
+    /// Create a document from statements. Statements with the same timestamp as the document are
+    /// written without one.
+    pub fn new(
+        id: &str,
+        author: &str,
+        timestamp: DateTime<Utc>,
+        statements: &[VexStatement],
+    ) -> Self {
+        Self {
+            context: OPENVEX_CONTEXT.to_string(),
+            id: id.to_string(),
+            author: author.to_string(),
+            timestamp,
+            version: 1,
+            statements: statements
+                .iter()
+                .map(|statement| OpenVexStatement {
+                    vulnerability: OpenVexVulnerability::Object {
+                        name: statement.vulnerability.clone(),
+                        aliases: Vec::new(),
+                    },
+                    products: statement
+                        .products
+                        .iter()
+                        .map(|product| OpenVexProduct::Object {
+                            id: product.clone(),
+                            identifiers: None,
+                        })
+                        .collect(),
+                    status: statement.status,
+                    justification: statement.justification,
+                    impact_statement: statement.impact_statement.clone(),
+                    action_statement: statement.action_statement.clone(),
+                    timestamp: statement
+                        .timestamp
+                        .filter(|statement_timestamp| *statement_timestamp != timestamp),
+                })
+                .collect(),
+        }
+    }
+
+    /// Parse a document from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if the input is not a valid document.
+    pub fn from_json(input: &str) -> Result<Self, SpdxError> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Serialize the document to JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Json`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, SpdxError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The statements of the document in the format independent representation.
+    pub fn statements(&self) -> Vec<VexStatement> {
+        self.statements
+            .iter()
+            .map(|statement| VexStatement {
+                vulnerability: statement.vulnerability.name().to_string(),
+                products: statement
+                    .products
+                    .iter()
+                    .flat_map(OpenVexProduct::identifiers)
+                    .collect(),
+                status: statement.status,
+                justification: statement.justification,
+                impact_statement: statement.impact_statement.clone(),
+                action_statement: statement.action_statement.clone(),
+                timestamp: Some(statement.timestamp.unwrap_or(self.timestamp)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn openvex_document_is_parsed() {
+        let document =
+            OpenVexDocument::from_json(&read_to_string("tests/data/vex/openvex.json").unwrap())
+                .unwrap();
+        let statements = document.statements();
+        assert_eq!(statements.len(), 2);
+
+        assert_eq!(statements[0].vulnerability, "CVE-2022-28890");
+        assert_eq!(
+            statements[0].products,
+            vec!["pkg:maven/org.apache.jena/apache-jena@3.12.0".to_string()]
+        );
+        assert_eq!(statements[0].status, VexStatus::UnderInvestigation);
+        assert_eq!(
+            statements[0].timestamp,
+            Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap())
+        );
+
+        assert_eq!(statements[1].vulnerability, "CVE-2022-22965");
+        assert_eq!(
+            statements[1].justification,
+            Some(VexJustification::VulnerableCodeNotPresent)
+        );
+        assert_eq!(
+            statements[1].timestamp,
+            Some(Utc.with_ymd_and_hms(2023, 1, 15, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn legacy_string_fields_are_parsed() {
+        let document = OpenVexDocument::from_json(
+            r#"{
+                "@context": "https://openvex.dev/ns",
+                "@id": "https://example.com/vex-1",
+                "author": "Example",
+                "timestamp": "2023-01-01T00:00:00Z",
+                "statements": [{
+                    "vulnerability": "CVE-2023-0001",
+                    "products": ["pkg:cargo/example@1.0.0"],
+                    "status": "fixed"
+                }]
+            }"#,
+        )
+        .unwrap();
+        let statement = &document.statements()[0];
+        assert_eq!(statement.vulnerability, "CVE-2023-0001");
+        assert_eq!(
+            statement.products,
+            vec!["pkg:cargo/example@1.0.0".to_string()]
+        );
+        assert_eq!(statement.status, VexStatus::Fixed);
+    }
+
+    #[test]
+    fn openvex_document_roundtrips() {
+        let timestamp = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let mut statement = VexStatement::new(
+            "CVE-2022-28890",
+            vec!["SPDXRef-fromDoap-0".to_string()],
+            VexStatus::NotAffected,
+        );
+        statement.justification = Some(VexJustification::ComponentNotPresent);
+        statement.timestamp = Some(timestamp);
+
+        let document = OpenVexDocument::new(
+            "https://example.com/vex-1",
+            "Example",
+            timestamp,
+            &[statement.clone()],
+        );
+        let parsed = OpenVexDocument::from_json(&document.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, document);
+        assert_eq!(parsed.statements(), vec![statement]);
+    }
+}
@@ -0,0 +1,504 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Subset of the [OSV schema](https://ossf.github.io/osv-schema/) needed for matching packages.
+
+use std::{cmp::Ordering, fs, path::Path};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::SpdxError,
+    models::{Cpe, Purl},
+    version::{compare_semver, compare_versions},
+};
+
+/// A vulnerability record in the OSV format.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsvVulnerability {
+    /// Identifier of the vulnerability, e.g. `GHSA-xxxx-xxxx-xxxx`.
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub modified: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub published: Option<String>,
+
+    /// Set if the record has been withdrawn and should be ignored.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub withdrawn: Option<String>,
+
+    /// Other identifiers of the same vulnerability, e.g. CVE IDs.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub aliases: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub summary: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub details: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub affected: Vec<OsvAffected>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub references: Vec<OsvReference>,
+}
+
+/// A package affected by an [`OsvVulnerability`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsvAffected {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub package: Option<OsvPackage>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub ranges: Vec<OsvRange>,
+
+    /// Explicitly enumerated affected versions.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub versions: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ecosystem_specific: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub database_specific: Option<Value>,
+}
+
+/// Identification of an affected package.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsvPackage {
+    pub ecosystem: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub purl: Option<String>,
+}
+
+/// A range of affected versions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsvRange {
+    #[serde(rename = "type")]
+    pub range_type: OsvRangeType,
+
+    /// Repository of `GIT` ranges.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub repo: Option<String>,
+
+    pub events: Vec<OsvEvent>,
+}
+
+/// Type of an [`OsvRange`], which determines how the versions are compared.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OsvRangeType {
+    Semver,
+    Ecosystem,
+    Git,
+}
+
+/// A single event of an [`OsvRange`]. Exactly one of the fields should be set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct OsvEvent {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub introduced: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub fixed: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_affected: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub limit: Option<String>,
+}
+
+/// A reference to more information about an [`OsvVulnerability`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsvReference {
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    pub url: String,
+}
+
+impl OsvVulnerability {
+    /// Read vulnerabilities from a JSON file containing either a single record or an array of
+    /// records.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if the file can't be read or parsed.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Vec<Self>, SpdxError> {
+        let content = fs::read_to_string(path)?;
+        let value: Value = serde_json::from_str(&content)?;
+        let vulnerabilities = if value.is_array() {
+            serde_json::from_value(value)?
+        } else {
+            vec![serde_json::from_value(value)?]
+        };
+        Ok(vulnerabilities)
+    }
+
+    /// Read all `.json` files from the directory and its subdirectories.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if any of the files can't be read or parsed.
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Vec<Self>, SpdxError> {
+        info!("Reading OSV records from {}.", path.as_ref().display());
+
+        let mut paths = Vec::new();
+        collect_json_files(path.as_ref(), &mut paths)?;
+        paths.sort();
+
+        let mut vulnerabilities = Vec::new();
+        for path in paths {
+            vulnerabilities.extend(Self::from_file(path)?);
+        }
+        Ok(vulnerabilities)
+    }
+
+    /// Versions in which the vulnerability has been fixed.
+    pub fn fixed_versions(&self) -> Vec<String> {
+        let mut fixed = Vec::new();
+        for range in self.affected.iter().flat_map(|affected| &affected.ranges) {
+            if range.range_type == OsvRangeType::Git {
+                continue;
+            }
+            for version in range.events.iter().filter_map(|event| event.fixed.as_ref()) {
+                if !fixed.contains(version) {
+                    fixed.push(version.clone());
+                }
+            }
+        }
+        fixed
+    }
+}
+
+fn collect_json_files(path: &Path, paths: &mut Vec<std::path::PathBuf>) -> Result<(), SpdxError> {
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_json_files(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+impl OsvAffected {
+    /// Check if the given version is affected, either by being enumerated in the versions or by
+    /// being inside one of the ranges.
+    ///
+    /// Returns `None` if the version isn't enumerated and a `GIT` range can't be evaluated for
+    /// it, as the answer depends on the commit graph.
+    pub fn affects_version(&self, version: &str) -> Option<bool> {
+        if self.versions.iter().any(|affected| affected == version) {
+            return Some(true);
+        }
+
+        let mut affected = Some(false);
+        for range in &self.ranges {
+            match range.affects_version(version) {
+                Some(true) => return Some(true),
+                Some(false) => {}
+                None => affected = None,
+            }
+        }
+        affected
+    }
+
+    /// Check if the entry refers to the package identified by the purl.
+    pub fn matches_purl(&self, purl: &Purl) -> bool {
+        let Some(package) = &self.package else {
+            return false;
+        };
+
+        if let Some(affected_purl) = package
+            .purl
+            .as_ref()
+            .and_then(|affected_purl| affected_purl.parse::<Purl>().ok())
+        {
+            return affected_purl.same_package(purl);
+        }
+
+        let Some((ecosystem, name)) = osv_package_for_purl(purl) else {
+            return false;
+        };
+
+        let affected_ecosystem = package
+            .ecosystem
+            .split(':')
+            .next()
+            .unwrap_or(&package.ecosystem);
+
+        affected_ecosystem == ecosystem
+            && if ecosystem == "PyPI" {
+                package.name.to_lowercase().replace('_', "-") == name
+            } else {
+                package.name == name
+            }
+    }
+
+    /// CPE match criteria listed in the ecosystem or database specific data under `cpe` or
+    /// `cpes`.
+    pub fn cpes(&self) -> Vec<Cpe> {
+        let mut cpes = Vec::new();
+        for specific in [&self.ecosystem_specific, &self.database_specific]
+            .iter()
+            .filter_map(|specific| specific.as_ref())
+        {
+            for key in ["cpe", "cpes"] {
+                match specific.get(key) {
+                    Some(Value::String(cpe)) => cpes.extend(cpe.parse::<Cpe>().ok()),
+                    Some(Value::Array(values)) => cpes.extend(
+                        values
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .filter_map(|cpe| cpe.parse::<Cpe>().ok()),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        cpes
+    }
+}
+
+impl OsvRange {
+    /// Check if the version is inside the range.
+    ///
+    /// `GIT` ranges can't be evaluated without the commit graph, so only commits equal to an
+    /// introduced, last affected or fixed commit give an answer, and `None` is returned for other
+    /// versions. Records usually enumerate the affected tags in [`OsvAffected::versions`] for this
+    /// reason.
+    pub fn affects_version(&self, version: &str) -> Option<bool> {
+        let compare: fn(&str, &str) -> Ordering = match self.range_type {
+            OsvRangeType::Semver => compare_semver,
+            OsvRangeType::Ecosystem => compare_versions,
+            OsvRangeType::Git => {
+                return self.events.iter().find_map(|event| {
+                    if event.introduced.as_deref() == Some(version)
+                        || event.last_affected.as_deref() == Some(version)
+                    {
+                        Some(true)
+                    } else if event.fixed.as_deref() == Some(version) {
+                        Some(false)
+                    } else {
+                        None
+                    }
+                });
+            }
+        };
+
+        let event_version = |event: &OsvEvent| {
+            event
+                .introduced
+                .clone()
+                .or_else(|| event.fixed.clone())
+                .or_else(|| event.last_affected.clone())
+                .or_else(|| event.limit.clone())
+                .unwrap_or_default()
+        };
+
+        let mut events = self.events.clone();
+        events.sort_by(|left, right| {
+            let (left, right) = (event_version(left), event_version(right));
+            match (left.as_str(), right.as_str()) {
+                ("0", "0") => Ordering::Equal,
+                ("0", _) => Ordering::Less,
+                (_, "0") => Ordering::Greater,
+                (left, right) => compare(left, right),
+            }
+        });
+
+        let mut affected = false;
+        for event in &events {
+            if let Some(introduced) = &event.introduced {
+                if introduced == "0" || compare(version, introduced) != Ordering::Less {
+                    affected = true;
+                }
+            } else if let Some(fixed) = &event.fixed {
+                if compare(version, fixed) != Ordering::Less {
+                    affected = false;
+                }
+            } else if let Some(last_affected) = &event.last_affected {
+                if compare(version, last_affected) == Ordering::Greater {
+                    affected = false;
+                }
+            }
+        }
+
+        let mut limits = events.iter().filter_map(|event| event.limit.as_deref());
+        let mut limits_peek = limits.clone().peekable();
+        if limits_peek.peek().is_some() {
+            affected &=
+                limits.any(|limit| limit == "*" || compare(version, limit) == Ordering::Less);
+        }
+
+        Some(affected)
+    }
+}
+
+/// Map a purl to the OSV ecosystem and package name.
+fn osv_package_for_purl(purl: &Purl) -> Option<(&'static str, String)> {
+    let ecosystem = match purl.purl_type.as_str() {
+        "cargo" => "crates.io",
+        "npm" => "npm",
+        "pypi" => "PyPI",
+        "maven" => "Maven",
+        "golang" => "Go",
+        "nuget" => "NuGet",
+        "gem" => "RubyGems",
+        "composer" => "Packagist",
+        "hex" => "Hex",
+        "pub" => "Pub",
+        "hackage" => "Hackage",
+        "cran" => "CRAN",
+        "conan" => "ConanCenter",
+        "swift" => "SwiftURL",
+        "github" => "GitHub Actions",
+        "deb" => "Debian",
+        "apk" => "Alpine",
+        _ => return None,
+    };
+
+    let name = match (&purl.namespace, purl.purl_type.as_str()) {
+        (Some(namespace), "maven") => format!("{namespace}:{}", purl.name),
+        (Some(_), "deb" | "apk") | (None, _) => purl.name.clone(),
+        (Some(namespace), _) => format!("{namespace}/{}", purl.name),
+    };
+
+    Some((ecosystem, name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn range(range_type: OsvRangeType, events: &[(&str, &str)]) -> OsvRange {
+        OsvRange {
+            range_type,
+            repo: None,
+            events: events
+                .iter()
+                .map(|(kind, version)| {
+                    let version = Some((*version).to_string());
+                    match *kind {
+                        "introduced" => OsvEvent {
+                            introduced: version,
+                            ..OsvEvent::default()
+                        },
+                        "fixed" => OsvEvent {
+                            fixed: version,
+                            ..OsvEvent::default()
+                        },
+                        "last_affected" => OsvEvent {
+                            last_affected: version,
+                            ..OsvEvent::default()
+                        },
+                        _ => OsvEvent {
+                            limit: version,
+                            ..OsvEvent::default()
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn semver_ranges_are_evaluated() {
+        let range = range(
+            OsvRangeType::Semver,
+            &[
+                ("fixed", "1.2.3"),
+                ("introduced", "0"),
+                ("introduced", "2.0.0"),
+                ("fixed", "2.0.5"),
+            ],
+        );
+        assert_eq!(range.affects_version("1.0.0"), Some(true));
+        assert_eq!(range.affects_version("1.2.3-rc.1"), Some(true));
+        assert_eq!(range.affects_version("1.2.3"), Some(false));
+        assert_eq!(range.affects_version("1.9.0"), Some(false));
+        assert_eq!(range.affects_version("2.0.4"), Some(true));
+        assert_eq!(range.affects_version("2.0.5"), Some(false));
+    }
+
+    #[test]
+    fn last_affected_and_limit_are_evaluated() {
+        let last_affected = range(
+            OsvRangeType::Ecosystem,
+            &[("introduced", "1.0"), ("last_affected", "1.4")],
+        );
+        assert_eq!(last_affected.affects_version("1.4"), Some(true));
+        assert_eq!(last_affected.affects_version("1.4.1"), Some(false));
+        assert_eq!(last_affected.affects_version("0.9"), Some(false));
+
+        let limited = range(
+            OsvRangeType::Ecosystem,
+            &[("introduced", "0"), ("limit", "2.0")],
+        );
+        assert_eq!(limited.affects_version("1.9"), Some(true));
+        assert_eq!(limited.affects_version("2.1"), Some(false));
+    }
+
+    #[test]
+    fn git_ranges_are_unknown_between_commits() {
+        let range = range(
+            OsvRangeType::Git,
+            &[("introduced", "a1b2c3"), ("fixed", "d4e5f6")],
+        );
+        assert_eq!(range.affects_version("a1b2c3"), Some(true));
+        assert_eq!(range.affects_version("d4e5f6"), Some(false));
+        assert_eq!(range.affects_version("b7c8d9"), None);
+
+        let affected = OsvAffected {
+            package: None,
+            ranges: vec![range],
+            versions: vec!["v1.0.1".to_string()],
+            ecosystem_specific: None,
+            database_specific: None,
+        };
+        assert_eq!(affected.affects_version("a1b2c3"), Some(true));
+        assert_eq!(affected.affects_version("v1.0.1"), Some(true));
+        assert_eq!(affected.affects_version("b7c8d9"), None);
+    }
+
+    #[test]
+    fn records_are_read_from_directory() {
+        let vulnerabilities = OsvVulnerability::from_dir("tests/data/osv").unwrap();
+        assert_eq!(vulnerabilities.len(), 4);
+        let jena = vulnerabilities
+            .iter()
+            .find(|vulnerability| vulnerability.id == "GHSA-xxxx-jena-0001")
+            .unwrap();
+        assert_eq!(jena.aliases, vec!["CVE-2022-28890".to_string()]);
+        assert_eq!(jena.fixed_versions(), vec!["4.5.0".to_string()]);
+    }
+
+    #[test]
+    fn purls_are_mapped_to_osv_packages() {
+        let affected = OsvAffected {
+            package: Some(OsvPackage {
+                ecosystem: "PyPI".to_string(),
+                name: "Django_Allauth".to_string(),
+                purl: None,
+            }),
+            ranges: Vec::new(),
+            versions: Vec::new(),
+            ecosystem_specific: None,
+            database_specific: None,
+        };
+        assert!(affected.matches_purl(&"pkg:pypi/django-allauth@0.1".parse().unwrap()));
+        assert!(!affected.matches_purl(&"pkg:npm/django-allauth@0.1".parse().unwrap()));
+    }
+}
@@ -0,0 +1,458 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Format independent representation of VEX (Vulnerability Exploitability eXchange) statements.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    Annotation, AnnotationType, ExternalPackageReference, ExternalPackageReferenceCategory,
+    PackageInformation, Purl, SPDX,
+};
+
+use super::VulnerabilityFinding;
+
+/// Exploitability status of a vulnerability in a product.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum VexStatus {
+    NotAffected,
+    Affected,
+    Fixed,
+    UnderInvestigation,
+}
+
+impl VexStatus {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NotAffected => "not_affected",
+            Self::Affected => "affected",
+            Self::Fixed => "fixed",
+            Self::UnderInvestigation => "under_investigation",
+        }
+    }
+}
+
+impl Display for VexStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Reason for a [`VexStatus::NotAffected`] status.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum VexJustification {
+    ComponentNotPresent,
+    VulnerableCodeNotPresent,
+    VulnerableCodeNotInExecutePath,
+    VulnerableCodeCannotBeControlledByAdversary,
+    InlineMitigationsAlreadyExist,
+}
+
+impl VexJustification {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ComponentNotPresent => "component_not_present",
+            Self::VulnerableCodeNotPresent => "vulnerable_code_not_present",
+            Self::VulnerableCodeNotInExecutePath => "vulnerable_code_not_in_execute_path",
+            Self::VulnerableCodeCannotBeControlledByAdversary => {
+                "vulnerable_code_cannot_be_controlled_by_adversary"
+            }
+            Self::InlineMitigationsAlreadyExist => "inline_mitigations_already_exist",
+        }
+    }
+}
+
+impl Display for VexJustification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VexJustification {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "component_not_present" => Ok(Self::ComponentNotPresent),
+            "vulnerable_code_not_present" => Ok(Self::VulnerableCodeNotPresent),
+            "vulnerable_code_not_in_execute_path" => Ok(Self::VulnerableCodeNotInExecutePath),
+            "vulnerable_code_cannot_be_controlled_by_adversary" => {
+                Ok(Self::VulnerableCodeCannotBeControlledByAdversary)
+            }
+            "inline_mitigations_already_exist" => Ok(Self::InlineMitigationsAlreadyExist),
+            _ => Err(format!("unknown VEX justification {s}")),
+        }
+    }
+}
+
+/// A statement about the status of a vulnerability in one or more products.
+///
+/// Products are identified either by a purl or by an SPDX ID. The SPDX ID may be prefixed with the
+/// document namespace, e.g. `http://spdx.org/spdxdocs/example#SPDXRef-Package`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VexStatement {
+    pub vulnerability: String,
+    pub products: Vec<String>,
+    pub status: VexStatus,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub justification: Option<VexJustification>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub impact_statement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub action_statement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl VexStatement {
+    /// Create a statement without justification, impact or action statements.
+    pub fn new(vulnerability: &str, products: Vec<String>, status: VexStatus) -> Self {
+        Self {
+            vulnerability: vulnerability.to_string(),
+            products,
+            status,
+            justification: None,
+            impact_statement: None,
+            action_statement: None,
+            timestamp: None,
+        }
+    }
+
+    /// Check if the product identifier refers to the package. Purls without a version match all
+    /// versions of the package.
+    pub fn product_matches(
+        product: &str,
+        package: &PackageInformation,
+        document_namespace: &str,
+    ) -> bool {
+        if product.starts_with("pkg:") {
+            let product = match product.parse::<Purl>() {
+                Ok(product) => product,
+                Err(error) => {
+                    warn!("Skipping VEX product: {error}");
+                    return false;
+                }
+            };
+            package
+                .external_reference
+                .iter()
+                .filter_map(ExternalPackageReference::purl)
+                .filter_map(Result::ok)
+                .any(|purl| {
+                    purl.same_package(&product)
+                        && (product.version.is_none() || product.version == purl.version)
+                })
+        } else {
+            match product.rsplit_once('#') {
+                Some((namespace, spdx_id)) => {
+                    namespace == document_namespace && spdx_id == package.package_spdx_identifier
+                }
+                None => product == package.package_spdx_identifier,
+            }
+        }
+    }
+}
+
+/// Create [`VexStatus::UnderInvestigation`] statements for vulnerability findings, to be completed
+/// once the findings have been triaged.
+pub fn statements_for_findings(
+    findings: &[VulnerabilityFinding],
+    timestamp: DateTime<Utc>,
+) -> Vec<VexStatement> {
+    findings
+        .iter()
+        .map(|finding| VexStatement {
+            timestamp: Some(timestamp),
+            ..VexStatement::new(
+                &finding.vulnerability_id,
+                vec![finding.spdx_id.clone()],
+                VexStatus::UnderInvestigation,
+            )
+        })
+        .collect()
+}
+
+/// The effective status of a vulnerability in a package after merging all statements.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveVexStatus {
+    /// SPDX ID of the package.
+    pub spdx_id: String,
+    pub vulnerability: String,
+    pub status: VexStatus,
+    pub justification: Option<VexJustification>,
+    pub impact_statement: Option<String>,
+    pub action_statement: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl EffectiveVexStatus {
+    fn description(&self) -> String {
+        let mut description = format!("{}: {}", self.vulnerability, self.status);
+        if let Some(justification) = self.justification {
+            let _ = write!(description, " ({justification})");
+        }
+        for statement in [&self.impact_statement, &self.action_statement]
+            .iter()
+            .filter_map(|statement| statement.as_ref())
+        {
+            let _ = write!(description, ". {statement}");
+        }
+        description
+    }
+}
+
+/// Resolve the statements to the packages of the SPDX and find the effective status for every
+/// package and vulnerability.
+///
+/// The latest statement wins. Statements without a timestamp are older than any statement with
+/// one, and of statements with equal timestamps the last one wins.
+pub fn merge_statements<'a, I>(spdx: &SPDX, statements: I) -> Vec<EffectiveVexStatus>
+where
+    I: IntoIterator<Item = &'a VexStatement>,
+{
+    let namespace = &spdx.document_creation_information.spdx_document_namespace;
+    let mut effective: BTreeMap<(String, String), EffectiveVexStatus> = BTreeMap::new();
+
+    for statement in statements {
+        for package in spdx.package_information.iter().filter(|package| {
+            statement
+                .products
+                .iter()
+                .any(|product| VexStatement::product_matches(product, package, namespace))
+        }) {
+            let key = (
+                package.package_spdx_identifier.clone(),
+                statement.vulnerability.clone(),
+            );
+            if effective
+                .get(&key)
+                .is_some_and(|existing| existing.timestamp > statement.timestamp)
+            {
+                continue;
+            }
+            effective.insert(
+                key,
+                EffectiveVexStatus {
+                    spdx_id: package.package_spdx_identifier.clone(),
+                    vulnerability: statement.vulnerability.clone(),
+                    status: statement.status,
+                    justification: statement.justification,
+                    impact_statement: statement.impact_statement.clone(),
+                    action_statement: statement.action_statement.clone(),
+                    timestamp: statement.timestamp,
+                },
+            );
+        }
+    }
+
+    effective.into_values().collect()
+}
+
+const ANNOTATION_PREFIX: &str = "VEX ";
+
+/// Record the statuses as annotations of the packages. Annotations written earlier for the same
+/// vulnerability are replaced.
+pub fn add_vex_annotations(
+    spdx: &mut SPDX,
+    statuses: &[EffectiveVexStatus],
+    annotator: &str,
+    annotation_date: DateTime<Utc>,
+) {
+    for status in statuses {
+        let previous = format!("{ANNOTATION_PREFIX}{}: ", status.vulnerability);
+        for package in spdx
+            .package_information
+            .iter_mut()
+            .filter(|package| package.package_spdx_identifier == status.spdx_id)
+        {
+            package
+                .annotations
+                .retain(|annotation| !annotation.annotation_comment.starts_with(&previous));
+            package.annotations.push(Annotation::new(
+                annotator.to_string(),
+                annotation_date,
+                AnnotationType::Other,
+                Some(status.spdx_id.clone()),
+                format!("{ANNOTATION_PREFIX}{}", status.description()),
+            ));
+        }
+    }
+}
+
+/// Record the statuses as `SECURITY advisory` external references pointing to the VEX document.
+///
+/// The status is stored in the comment of the reference. References added earlier for the same
+/// document and vulnerability are replaced.
+pub fn add_vex_references(spdx: &mut SPDX, statuses: &[EffectiveVexStatus], vex_locator: &str) {
+    for status in statuses {
+        let previous = format!("{}: ", status.vulnerability);
+        for package in spdx
+            .package_information
+            .iter_mut()
+            .filter(|package| package.package_spdx_identifier == status.spdx_id)
+        {
+            package.external_reference.retain(|reference| {
+                !(reference.reference_category == ExternalPackageReferenceCategory::Security
+                    && reference.reference_locator == vex_locator
+                    && reference
+                        .reference_comment
+                        .as_ref()
+                        .is_some_and(|comment| comment.starts_with(&previous)))
+            });
+            package
+                .external_reference
+                .push(ExternalPackageReference::new(
+                    ExternalPackageReferenceCategory::Security,
+                    "advisory".to_string(),
+                    vex_locator.to_string(),
+                    Some(status.description()),
+                ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use chrono::TimeZone;
+
+    use crate::vulnerability::{CsafVexDocument, OpenVexDocument};
+
+    use super::*;
+
+    fn spdx() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn products_are_matched_by_purl_and_spdx_id() {
+        let spdx = spdx();
+        let namespace = &spdx.document_creation_information.spdx_document_namespace;
+        let jena = &spdx.package_information[2];
+
+        for product in [
+            "pkg:maven/org.apache.jena/apache-jena@3.12.0",
+            "pkg:maven/org.apache.jena/apache-jena",
+            "SPDXRef-fromDoap-0",
+            &format!("{namespace}#SPDXRef-fromDoap-0"),
+        ] {
+            assert!(VexStatement::product_matches(product, jena, namespace));
+        }
+        for product in [
+            "pkg:maven/org.apache.jena/apache-jena@4.0.0",
+            "SPDXRef-Package",
+            "http://example.com/other#SPDXRef-fromDoap-0",
+        ] {
+            assert!(!VexStatement::product_matches(product, jena, namespace));
+        }
+    }
+
+    #[test]
+    fn latest_statement_wins() {
+        let spdx = spdx();
+        let mut older = VexStatement::new(
+            "CVE-2022-28890",
+            vec!["SPDXRef-fromDoap-0".to_string()],
+            VexStatus::Affected,
+        );
+        older.timestamp = Some(Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap());
+        let mut newer = VexStatement::new(
+            "CVE-2022-28890",
+            vec!["pkg:maven/org.apache.jena/apache-jena@3.12.0".to_string()],
+            VexStatus::Fixed,
+        );
+        newer.timestamp = Some(Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap());
+
+        for statements in [[&older, &newer], [&newer, &older]] {
+            let statuses = merge_statements(&spdx, statements.iter().copied());
+            assert_eq!(statuses.len(), 1);
+            assert_eq!(statuses[0].spdx_id, "SPDXRef-fromDoap-0");
+            assert_eq!(statuses[0].status, VexStatus::Fixed);
+        }
+    }
+
+    #[test]
+    fn openvex_and_csaf_statements_are_merged() {
+        let spdx = spdx();
+        let openvex =
+            OpenVexDocument::from_json(&read_to_string("tests/data/vex/openvex.json").unwrap())
+                .unwrap();
+        let csaf = CsafVexDocument::from_json(&read_to_string("tests/data/vex/csaf.json").unwrap())
+            .unwrap();
+        let statements = [openvex.statements(), csaf.statements()].concat();
+
+        let statuses = merge_statements(&spdx, &statements)
+            .into_iter()
+            .map(|status| (status.spdx_id, status.vulnerability, status.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                (
+                    "SPDXRef-Package".to_string(),
+                    "CVE-2022-22965".to_string(),
+                    VexStatus::NotAffected
+                ),
+                (
+                    "SPDXRef-Saxon".to_string(),
+                    "CVE-2022-28890".to_string(),
+                    VexStatus::Affected
+                ),
+                (
+                    "SPDXRef-fromDoap-0".to_string(),
+                    "CVE-2022-28890".to_string(),
+                    VexStatus::NotAffected
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn statuses_are_written_as_annotations_and_references() {
+        let mut spdx = spdx();
+        let mut statement = VexStatement::new(
+            "CVE-2022-28890",
+            vec!["SPDXRef-fromDoap-0".to_string()],
+            VexStatus::NotAffected,
+        );
+        statement.justification = Some(VexJustification::VulnerableCodeNotInExecutePath);
+        let statuses = merge_statements(&spdx, [&statement]);
+        let date = Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap();
+
+        add_vex_annotations(&mut spdx, &statuses, "Tool: spdx-rs", date);
+        add_vex_annotations(&mut spdx, &statuses, "Tool: spdx-rs", date);
+        add_vex_references(&mut spdx, &statuses, "https://example.com/vex.json");
+        add_vex_references(&mut spdx, &statuses, "https://example.com/vex.json");
+
+        let jena = &spdx.package_information[2];
+        let expected =
+            "CVE-2022-28890: not_affected (vulnerable_code_not_in_execute_path)".to_string();
+        assert_eq!(jena.annotations.len(), 1);
+        assert_eq!(
+            jena.annotations[0].annotation_comment,
+            format!("VEX {expected}")
+        );
+        let references = jena
+            .external_reference
+            .iter()
+            .filter(|reference| reference.reference_type == "advisory")
+            .collect::<Vec<_>>();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].reference_comment, Some(expected));
+    }
+}
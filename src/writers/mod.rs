@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! Writers for serializing [`SPDX`] to data formats not supported by [Serde].
+//!
+//! JSON (and YAML) is written with the data format specific crates, e.g.
+//! [`serde_json::to_string_pretty`].
+//!
+//! [Serde]: https://serde.rs
+
+use log::info;
+
+use crate::models::SPDX;
+
+mod stream;
+mod tag_value;
+
+pub use stream::{JsonStreamWriter, TagValueStreamWriter};
+
+/// Write [`SPDX`] as a tag-value SPDX document.
+///
+/// Files that belong to a package, either through the package's `hasFiles` or a `CONTAINS`
+/// relationship, are written after the package, and the other files before the first package.
+/// The annotations of the packages are written with the annotations of the document.
+///
+/// # Usage
+///
+/// ```
+/// # use spdx_rs::error::SpdxError;
+/// use spdx_rs::{parsers::spdx_from_tag_value, writers::spdx_to_tag_value};
+/// # fn main() -> Result<(), SpdxError> {
+///
+/// let spdx_file = std::fs::read_to_string("tests/data/SPDXTagExample-v2.2.spdx")?;
+/// let spdx_document = spdx_from_tag_value(&spdx_file)?;
+///
+/// let tag_value = spdx_to_tag_value(&spdx_document);
+/// assert!(tag_value.starts_with("SPDXVersion: SPDX-2.2\n"));
+/// assert_eq!(
+///     spdx_from_tag_value(&tag_value)?.package_information,
+///     spdx_document.package_information
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn spdx_to_tag_value(spdx: &SPDX) -> String {
+    info!("Writing SPDX as tag-value.");
+    let mut writer = tag_value::TagValueWriter::default();
+    writer.spdx(spdx);
+    writer.finish()
+}
@@ -0,0 +1,443 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::io::Write;
+
+use log::info;
+use serde::Serialize;
+
+use super::tag_value::TagValueWriter;
+use crate::{
+    error::SpdxError,
+    models::{
+        Annotation, DocumentCreationInformation, FileInformation,
+        OtherLicensingInformationDetected, PackageInformation, Relationship, RelationshipType,
+        Snippet,
+    },
+};
+
+/// Writes a JSON SPDX document element by element, without keeping the document in memory.
+///
+/// The document is started with the document creation information, after which the elements are
+/// written as they are pushed, and the document is completed with [`JsonStreamWriter::finish`].
+/// JSON documents list the elements of each type in one array, so all elements of a type must
+/// be pushed one after another: after e.g. a file has been pushed, no more packages can be
+/// pushed. The annotations of packages are written in the packages.
+///
+/// The writer is not buffered, so wrap files in a [`std::io::BufWriter`].
+///
+/// # Usage
+///
+/// ```
+/// # use spdx_rs::error::SpdxError;
+/// use spdx_rs::{
+///     models::{DocumentCreationInformation, PackageInformation, SPDX},
+///     writers::JsonStreamWriter,
+/// };
+/// # fn main() -> Result<(), SpdxError> {
+///
+/// let document = DocumentCreationInformation::default();
+/// let mut writer = JsonStreamWriter::start(Vec::new(), &document)?;
+/// for name in ["first", "second"] {
+///     let mut counter = 0;
+///     writer.package(&PackageInformation::new(name, &mut counter))?;
+/// }
+/// let output = writer.finish()?;
+///
+/// let spdx: SPDX = serde_json::from_slice(&output)?;
+/// assert_eq!(spdx.package_information.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct JsonStreamWriter<W: Write> {
+    writer: W,
+
+    /// Array that elements are currently written to.
+    current: Option<&'static str>,
+
+    /// Arrays that have been completed.
+    completed: Vec<&'static str>,
+}
+
+impl<W: Write> JsonStreamWriter<W> {
+    /// Start a document by writing the document creation information.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn start(
+        mut writer: W,
+        document_creation_information: &DocumentCreationInformation,
+    ) -> Result<Self, SpdxError> {
+        info!("Writing SPDX as a JSON stream.");
+        let document = serde_json::to_string(document_creation_information)?;
+        // The fields are written without the closing brace so that the elements can follow.
+        let fields = document.strip_suffix('}').unwrap_or(&document);
+        writer.write_all(fields.as_bytes())?;
+        Ok(Self {
+            writer,
+            current: None,
+            completed: Vec::new(),
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Write`] if packages can't be written anymore, or another
+    /// [`SpdxError`] if writing fails.
+    pub fn package(&mut self, package: &PackageInformation) -> Result<(), SpdxError> {
+        self.element("packages", package)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Write`] if files can't be written anymore, or another [`SpdxError`]
+    /// if writing fails.
+    pub fn file(&mut self, file: &FileInformation) -> Result<(), SpdxError> {
+        self.element("files", file)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Write`] if snippets can't be written anymore, or another
+    /// [`SpdxError`] if writing fails.
+    pub fn snippet(&mut self, snippet: &Snippet) -> Result<(), SpdxError> {
+        self.element("snippets", snippet)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Write`] if other licensing information can't be written anymore, or
+    /// another [`SpdxError`] if writing fails.
+    pub fn other_licensing_information_detected(
+        &mut self,
+        license: &OtherLicensingInformationDetected,
+    ) -> Result<(), SpdxError> {
+        self.element("hasExtractedLicensingInfos", license)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Write`] if relationships can't be written anymore, or another
+    /// [`SpdxError`] if writing fails.
+    pub fn relationship(&mut self, relationship: &Relationship) -> Result<(), SpdxError> {
+        self.element("relationships", relationship)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError::Write`] if annotations can't be written anymore, or another
+    /// [`SpdxError`] if writing fails.
+    pub fn annotation(&mut self, annotation: &Annotation) -> Result<(), SpdxError> {
+        self.element("annotations", annotation)
+    }
+
+    /// Complete the document and return the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn finish(mut self) -> Result<W, SpdxError> {
+        if self.current.is_some() {
+            self.writer.write_all(b"\n]")?;
+        }
+        self.writer.write_all(b"}\n")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn element<T: Serialize>(&mut self, array: &'static str, element: &T) -> Result<(), SpdxError> {
+        if self.current == Some(array) {
+            self.writer.write_all(b",\n")?;
+        } else {
+            if self.completed.contains(&array) {
+                return Err(SpdxError::Write(format!(
+                    "`{array}` were already written, the elements of a type must be written \
+                     together"
+                )));
+            }
+            if let Some(current) = self.current.replace(array) {
+                self.writer.write_all(b"\n]")?;
+                self.completed.push(current);
+            }
+            writeln!(self.writer, ",\"{array}\":[")?;
+        }
+        serde_json::to_writer(&mut self.writer, element)?;
+        Ok(())
+    }
+}
+
+/// Writes a tag-value SPDX document element by element, without keeping the document in memory.
+///
+/// The document is started with the document creation information, after which the elements are
+/// written as they are pushed, and the document is completed with
+/// [`TagValueStreamWriter::finish`]. The elements can be pushed in any order, but files
+/// written after a package belong to that package in tag-value, so files that don't belong to
+/// any package must be pushed before the first package. The annotations of packages are
+/// written after the packages.
+///
+/// The writer is not buffered, so wrap files in a [`std::io::BufWriter`].
+///
+/// # Usage
+///
+/// ```
+/// # use spdx_rs::error::SpdxError;
+/// use spdx_rs::{
+///     models::{DocumentCreationInformation, FileInformation, PackageInformation},
+///     parsers::spdx_from_tag_value,
+///     writers::TagValueStreamWriter,
+/// };
+/// # fn main() -> Result<(), SpdxError> {
+///
+/// let document = DocumentCreationInformation::default();
+/// let mut writer = TagValueStreamWriter::start(Vec::new(), &document)?;
+/// let mut counter = 0;
+/// writer.package(&PackageInformation::new("package", &mut counter))?;
+/// writer.file(&FileInformation::new("./src/main.rs", &mut counter))?;
+/// let output = writer.finish()?;
+///
+/// let spdx = spdx_from_tag_value(&String::from_utf8(output).unwrap())?;
+/// assert_eq!(spdx.file_information[0].file_name, "./src/main.rs");
+/// # Ok(())
+/// # }
+/// ```
+pub struct TagValueStreamWriter<W: Write> {
+    writer: W,
+    tags: TagValueWriter,
+    document_id: String,
+}
+
+impl<W: Write> TagValueStreamWriter<W> {
+    /// Start a document by writing the document creation information. The elements the document
+    /// describes are written as `DESCRIBES` relationships.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn start(
+        writer: W,
+        document_creation_information: &DocumentCreationInformation,
+    ) -> Result<Self, SpdxError> {
+        info!("Writing SPDX as a tag-value stream.");
+        let document_id = document_creation_information.spdx_identifier.clone();
+        let mut tags = TagValueWriter::default();
+        tags.document_creation_information(document_creation_information);
+        for described in &document_creation_information.document_describes {
+            tags.relationship(&Relationship::new(
+                &document_id,
+                described,
+                RelationshipType::Describes,
+                None,
+            ));
+        }
+
+        let mut stream = Self {
+            writer,
+            tags,
+            document_id,
+        };
+        stream.write()?;
+        Ok(stream)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn package(&mut self, package: &PackageInformation) -> Result<(), SpdxError> {
+        self.tags.package(package);
+        for annotation in &package.annotations {
+            let annotation = Annotation {
+                spdx_identifier_reference: annotation
+                    .spdx_identifier_reference
+                    .clone()
+                    .or_else(|| Some(package.package_spdx_identifier.clone())),
+                ..annotation.clone()
+            };
+            self.tags.annotation(&annotation, &self.document_id);
+        }
+        self.write()
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn file(&mut self, file: &FileInformation) -> Result<(), SpdxError> {
+        self.tags.file(file);
+        self.write()
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn snippet(&mut self, snippet: &Snippet) -> Result<(), SpdxError> {
+        self.tags.snippet(snippet);
+        self.write()
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn other_licensing_information_detected(
+        &mut self,
+        license: &OtherLicensingInformationDetected,
+    ) -> Result<(), SpdxError> {
+        self.tags.other_licensing_information_detected(license);
+        self.write()
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn relationship(&mut self, relationship: &Relationship) -> Result<(), SpdxError> {
+        self.tags.relationship(relationship);
+        self.write()
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn annotation(&mut self, annotation: &Annotation) -> Result<(), SpdxError> {
+        self.tags.annotation(annotation, &self.document_id);
+        self.write()
+    }
+
+    /// Complete the document and return the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpdxError`] if writing fails.
+    pub fn finish(mut self) -> Result<W, SpdxError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Write the tags of the pushed element.
+    fn write(&mut self) -> Result<(), SpdxError> {
+        self.writer.write_all(self.tags.take_output().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use super::*;
+    use crate::{models::SPDX, parsers::spdx_from_tag_value};
+
+    fn example() -> SPDX {
+        serde_json::from_str(&read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn streamed_json_equals_serialized_json() {
+        let spdx = example();
+        let mut writer =
+            JsonStreamWriter::start(Vec::new(), &spdx.document_creation_information).unwrap();
+        for package in &spdx.package_information {
+            writer.package(package).unwrap();
+        }
+        for file in &spdx.file_information {
+            writer.file(file).unwrap();
+        }
+        for snippet in &spdx.snippet_information {
+            writer.snippet(snippet).unwrap();
+        }
+        for license in &spdx.other_licensing_information_detected {
+            writer
+                .other_licensing_information_detected(license)
+                .unwrap();
+        }
+        for relationship in &spdx.relationships {
+            writer.relationship(relationship).unwrap();
+        }
+        for annotation in &spdx.annotations {
+            writer.annotation(annotation).unwrap();
+        }
+        let output = writer.finish().unwrap();
+
+        assert_eq!(serde_json::from_slice::<SPDX>(&output).unwrap(), spdx);
+    }
+
+    #[test]
+    fn json_elements_of_a_type_are_written_together() {
+        let spdx = example();
+        let mut writer =
+            JsonStreamWriter::start(Vec::new(), &spdx.document_creation_information).unwrap();
+        writer.package(&spdx.package_information[0]).unwrap();
+        writer.file(&spdx.file_information[0]).unwrap();
+        assert!(matches!(
+            writer.package(&spdx.package_information[1]),
+            Err(SpdxError::Write(_))
+        ));
+
+        let empty = JsonStreamWriter::start(Vec::new(), &spdx.document_creation_information)
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<SPDX>(&empty)
+                .unwrap()
+                .document_creation_information,
+            spdx.document_creation_information
+        );
+    }
+
+    #[test]
+    fn streamed_tag_value_is_parsed() {
+        let spdx = example();
+        let mut writer =
+            TagValueStreamWriter::start(Vec::new(), &spdx.document_creation_information).unwrap();
+        for file in &spdx.file_information {
+            writer.file(file).unwrap();
+        }
+        for package in &spdx.package_information {
+            writer.package(package).unwrap();
+        }
+        for snippet in &spdx.snippet_information {
+            writer.snippet(snippet).unwrap();
+        }
+        for license in &spdx.other_licensing_information_detected {
+            writer
+                .other_licensing_information_detected(license)
+                .unwrap();
+        }
+        for relationship in &spdx.relationships {
+            writer.relationship(relationship).unwrap();
+        }
+        for annotation in &spdx.annotations {
+            writer.annotation(annotation).unwrap();
+        }
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        let written = spdx_from_tag_value(&output).unwrap();
+        assert_eq!(
+            written.document_creation_information.document_name,
+            spdx.document_creation_information.document_name
+        );
+        assert_eq!(written.file_information, spdx.file_information);
+        assert_eq!(
+            written.snippet_information[0].snippet_spdx_identifier,
+            spdx.snippet_information[0].snippet_spdx_identifier
+        );
+        assert_eq!(
+            written.other_licensing_information_detected,
+            spdx.other_licensing_information_detected
+        );
+        assert_eq!(
+            written.package_information.len(),
+            spdx.package_information.len()
+        );
+        for relationship in &spdx.relationships {
+            assert!(written.relationships.contains(relationship));
+        }
+        assert!(written.relationships.contains(&Relationship::new(
+            "SPDXRef-DOCUMENT",
+            "SPDXRef-File",
+            RelationshipType::Describes,
+            None
+        )));
+    }
+}
@@ -0,0 +1,540 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{
+    Annotation, Checksum, DocumentCreationInformation, FileInformation,
+    OtherLicensingInformationDetected, PackageInformation, Pointer, Relationship, RelationshipType,
+    Snippet, SPDX,
+};
+
+/// Builds a tag-value document tag by tag.
+#[derive(Debug, Default)]
+pub(super) struct TagValueWriter {
+    output: String,
+}
+
+impl TagValueWriter {
+    pub(super) fn finish(self) -> String {
+        self.output
+    }
+
+    /// Take the output written so far.
+    pub(super) fn take_output(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    pub(super) fn spdx(&mut self, spdx: &SPDX) {
+        let document = &spdx.document_creation_information;
+        self.document_creation_information(document);
+
+        let mut written_files = HashSet::new();
+        let package_files = spdx
+            .package_information
+            .iter()
+            .map(|package| {
+                let files = package_files(spdx, package)
+                    .into_iter()
+                    .filter(|file| written_files.insert(file.file_spdx_identifier.as_str()))
+                    .collect::<Vec<_>>();
+                (package, files)
+            })
+            .collect::<Vec<_>>();
+
+        for file in &spdx.file_information {
+            if !written_files.contains(file.file_spdx_identifier.as_str()) {
+                self.file(file);
+            }
+        }
+        for (package, files) in package_files {
+            self.package(package);
+            for file in files {
+                self.file(file);
+            }
+        }
+        for snippet in &spdx.snippet_information {
+            self.snippet(snippet);
+        }
+        for license in &spdx.other_licensing_information_detected {
+            self.other_licensing_information_detected(license);
+        }
+
+        let describes = document.document_describes.iter().map(|id| {
+            Relationship::new(
+                &document.spdx_identifier,
+                id,
+                RelationshipType::Describes,
+                None,
+            )
+        });
+        let mut written_relationships = HashSet::new();
+        for relationship in spdx.relationships.iter().cloned().chain(describes) {
+            if written_relationships.insert(relationship.clone()) {
+                self.relationship(&relationship);
+            }
+        }
+
+        let package_annotations = spdx.package_information.iter().flat_map(|package| {
+            package
+                .annotations
+                .iter()
+                .map(move |annotation| Annotation {
+                    spdx_identifier_reference: annotation
+                        .spdx_identifier_reference
+                        .clone()
+                        .or_else(|| Some(package.package_spdx_identifier.clone())),
+                    ..annotation.clone()
+                })
+        });
+        for annotation in spdx.annotations.iter().cloned().chain(package_annotations) {
+            self.annotation(&annotation, &document.spdx_identifier);
+        }
+    }
+
+    pub(super) fn document_creation_information(&mut self, document: &DocumentCreationInformation) {
+        self.tag("SPDXVersion", &document.spdx_version);
+        self.tag("DataLicense", &document.data_license);
+        self.tag("SPDXID", &document.spdx_identifier);
+        self.tag("DocumentName", &document.document_name);
+        self.tag("DocumentNamespace", &document.spdx_document_namespace);
+        for reference in &document.external_document_references {
+            let id = reference
+                .id_string
+                .strip_prefix("DocumentRef-")
+                .unwrap_or(&reference.id_string);
+            self.tag(
+                "ExternalDocumentRef",
+                &format!(
+                    "DocumentRef-{id} {} {}",
+                    reference.spdx_document_uri,
+                    checksum(&reference.checksum)
+                ),
+            );
+        }
+        self.optional("DocumentComment", document.document_comment.as_deref());
+
+        let creation_info = &document.creation_info;
+        self.optional(
+            "LicenseListVersion",
+            creation_info.license_list_version.as_deref(),
+        );
+        for creator in &creation_info.creators {
+            self.tag("Creator", creator);
+        }
+        self.tag(
+            "Created",
+            &creation_info
+                .created
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string(),
+        );
+        self.optional("CreatorComment", creation_info.creator_comment.as_deref());
+        self.extensions(&document.extensions);
+    }
+
+    pub(super) fn package(&mut self, package: &PackageInformation) {
+        self.section("Package");
+        self.tag("PackageName", &package.package_name);
+        self.tag("SPDXID", &package.package_spdx_identifier);
+        self.optional("PackageVersion", package.package_version.as_deref());
+        self.optional("PackageFileName", package.package_file_name.as_deref());
+        self.optional("PackageSupplier", package.package_supplier.as_deref());
+        self.optional("PackageOriginator", package.package_originator.as_deref());
+        self.tag(
+            "PackageDownloadLocation",
+            &package.package_download_location,
+        );
+        if let Some(files_analyzed) = package.files_analyzed {
+            self.tag("FilesAnalyzed", &files_analyzed.to_string());
+        }
+        if let Some(code) = &package.package_verification_code {
+            if code.excludes.is_empty() {
+                self.tag("PackageVerificationCode", &code.value);
+            } else {
+                self.tag(
+                    "PackageVerificationCode",
+                    &format!("{} (excludes: {})", code.value, code.excludes.join(", ")),
+                );
+            }
+        }
+        for package_checksum in &package.package_checksum {
+            self.tag("PackageChecksum", &checksum(package_checksum));
+        }
+        self.optional("PackageHomePage", package.package_home_page.as_deref());
+        self.optional("PackageSourceInfo", package.source_information.as_deref());
+        self.optional(
+            "PackageLicenseConcluded",
+            package
+                .concluded_license
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+        );
+        for license in &package.all_licenses_information_from_files {
+            self.tag("PackageLicenseInfoFromFiles", license);
+        }
+        self.optional(
+            "PackageLicenseDeclared",
+            package
+                .declared_license
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+        );
+        self.optional(
+            "PackageLicenseComments",
+            package.comments_on_license.as_deref(),
+        );
+        self.optional("PackageCopyrightText", package.copyright_text.as_deref());
+        self.optional(
+            "PackageSummary",
+            package.package_summary_description.as_deref(),
+        );
+        self.optional(
+            "PackageDescription",
+            package.package_detailed_description.as_deref(),
+        );
+        self.optional("PackageComment", package.package_comment.as_deref());
+        for reference in &package.external_reference {
+            self.tag(
+                "ExternalRef",
+                &format!(
+                    "{} {} {}",
+                    serde_name(&reference.reference_category),
+                    reference.reference_type,
+                    reference.reference_locator
+                ),
+            );
+            self.optional("ExternalRefComment", reference.reference_comment.as_deref());
+        }
+        for text in &package.package_attribution_text {
+            self.tag("PackageAttributionText", text);
+        }
+        if let Some(purpose) = &package.primary_package_purpose {
+            self.tag("PrimaryPackagePurpose", &serde_name(purpose));
+        }
+        self.optional("BuiltDate", package.built_date.as_deref());
+        self.optional("ReleaseDate", package.release_date.as_deref());
+        self.optional("ValidUntilDate", package.valid_until_date.as_deref());
+        self.extensions(&package.extensions);
+    }
+
+    pub(super) fn file(&mut self, file: &FileInformation) {
+        self.section("File");
+        self.tag("FileName", &file.file_name);
+        self.tag("SPDXID", &file.file_spdx_identifier);
+        for file_type in &file.file_type {
+            self.tag("FileType", &serde_name(file_type));
+        }
+        for file_checksum in &file.file_checksum {
+            self.tag("FileChecksum", &checksum(file_checksum));
+        }
+        self.optional(
+            "LicenseConcluded",
+            file.concluded_license
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+        );
+        for license in &file.license_information_in_file {
+            self.tag("LicenseInfoInFile", &license.to_string());
+        }
+        self.optional("LicenseComments", file.comments_on_license.as_deref());
+        self.optional("FileCopyrightText", file.copyright_text.as_deref());
+        self.optional("FileComment", file.file_comment.as_deref());
+        self.optional("FileNotice", file.file_notice.as_deref());
+        for contributor in &file.file_contributor {
+            self.tag("FileContributor", contributor);
+        }
+        for text in file.file_attribution_text.iter().flatten() {
+            self.tag("FileAttributionText", text);
+        }
+        self.extensions(&file.extensions);
+    }
+
+    pub(super) fn snippet(&mut self, snippet: &Snippet) {
+        self.section("Snippet");
+        self.tag("SnippetSPDXID", &snippet.snippet_spdx_identifier);
+        self.tag(
+            "SnippetFromFileSPDXID",
+            &snippet.snippet_from_file_spdx_identifier,
+        );
+        for range in &snippet.ranges {
+            match (&range.start_pointer, &range.end_pointer) {
+                (Pointer::Byte { offset: start, .. }, Pointer::Byte { offset: end, .. }) => {
+                    self.tag("SnippetByteRange", &format!("{start}:{end}"));
+                }
+                (
+                    Pointer::Line {
+                        line_number: start, ..
+                    },
+                    Pointer::Line {
+                        line_number: end, ..
+                    },
+                ) => self.tag("SnippetLineRange", &format!("{start}:{end}")),
+                _ => {}
+            }
+        }
+        self.optional(
+            "SnippetLicenseConcluded",
+            snippet
+                .snippet_concluded_license
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+        );
+        for license in &snippet.license_information_in_snippet {
+            self.tag("LicenseInfoInSnippet", license);
+        }
+        self.optional(
+            "SnippetLicenseComments",
+            snippet.snippet_comments_on_license.as_deref(),
+        );
+        self.optional(
+            "SnippetCopyrightText",
+            snippet.snippet_copyright_text.as_deref(),
+        );
+        self.optional("SnippetComment", snippet.snippet_comment.as_deref());
+        self.optional("SnippetName", snippet.snippet_name.as_deref());
+        self.optional(
+            "SnippetAttributionText",
+            snippet.snippet_attribution_text.as_deref(),
+        );
+        self.extensions(&snippet.extensions);
+    }
+
+    pub(super) fn other_licensing_information_detected(
+        &mut self,
+        license: &OtherLicensingInformationDetected,
+    ) {
+        self.section("License");
+        self.tag("LicenseID", &license.license_identifier);
+        self.text("ExtractedText", &license.extracted_text);
+        self.tag("LicenseName", &license.license_name);
+        for reference in &license.license_cross_reference {
+            self.tag("LicenseCrossReference", reference);
+        }
+        self.optional("LicenseComment", license.license_comment.as_deref());
+        self.extensions(&license.extensions);
+    }
+
+    pub(super) fn relationship(&mut self, relationship: &Relationship) {
+        self.tag(
+            "Relationship",
+            &format!(
+                "{} {} {}",
+                relationship.spdx_element_id,
+                relationship.relationship_type,
+                relationship.related_spdx_element
+            ),
+        );
+        self.optional("RelationshipComment", relationship.comment.as_deref());
+        self.extensions(&relationship.extensions);
+    }
+
+    pub(super) fn annotation(&mut self, annotation: &Annotation, document_id: &str) {
+        self.section("Annotation");
+        self.tag("Annotator", &annotation.annotator);
+        self.tag(
+            "AnnotationDate",
+            &annotation
+                .annotation_date
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string(),
+        );
+        self.tag("AnnotationType", &serde_name(&annotation.annotation_type));
+        self.tag(
+            "SPDXREF",
+            annotation
+                .spdx_identifier_reference
+                .as_deref()
+                .unwrap_or(document_id),
+        );
+        self.text("AnnotationComment", &annotation.annotation_comment);
+        self.extensions(&annotation.extensions);
+    }
+
+    /// Start a new section with an empty line and a comment.
+    fn section(&mut self, name: &str) {
+        self.output.push_str("\n## ");
+        self.output.push_str(name);
+        self.output.push('\n');
+    }
+
+    /// Write a tag. Values that span multiple lines are wrapped in `<text>`, and tags with an
+    /// empty value are left out because the empty value can't be parsed.
+    fn tag(&mut self, tag: &str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+        if value.contains('\n') {
+            self.text(tag, value);
+        } else {
+            self.output.push_str(tag);
+            self.output.push_str(": ");
+            self.output.push_str(value);
+            self.output.push('\n');
+        }
+    }
+
+    /// Write a tag with the value wrapped in `<text>`.
+    fn text(&mut self, tag: &str, value: &str) {
+        self.output.push_str(tag);
+        self.output.push_str(": <text>");
+        self.output.push_str(value);
+        self.output.push_str("</text>\n");
+    }
+
+    fn optional(&mut self, tag: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.tag(tag, value);
+        }
+    }
+
+    /// Write the extensions that can be tags, i.e. strings or arrays of strings with an
+    /// alphanumeric name. They are read back as extensions of the element written before them.
+    fn extensions(&mut self, extensions: &BTreeMap<String, Value>) {
+        let tags = extensions
+            .iter()
+            .filter(|(tag, _)| !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric()));
+        for (tag, value) in tags {
+            match value {
+                Value::String(value) => self.tag(tag, value),
+                Value::Array(values) => {
+                    for value in values.iter().filter_map(Value::as_str) {
+                        self.tag(tag, value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Files of the package, from `hasFiles` and `CONTAINS` relationships.
+fn package_files<'a>(spdx: &'a SPDX, package: &PackageInformation) -> Vec<&'a FileInformation> {
+    let id = &package.package_spdx_identifier;
+    spdx.file_information
+        .iter()
+        .filter(|file| {
+            package.files.contains(&file.file_spdx_identifier)
+                || spdx.relationships.iter().any(|relationship| {
+                    relationship.relationship_type == RelationshipType::Contains
+                        && &relationship.spdx_element_id == id
+                        && relationship.related_spdx_element == file.file_spdx_identifier
+                })
+        })
+        .collect()
+}
+
+fn checksum(checksum: &Checksum) -> String {
+    format!("{}: {}", serde_name(&checksum.algorithm), checksum.value)
+}
+
+/// Name of an enum variant in the SPDX specification, as serialized by Serde.
+fn serde_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::{parsers::spdx_from_tag_value, writers::spdx_to_tag_value};
+
+    use super::*;
+
+    fn sorted_relationships(spdx: &SPDX) -> Vec<String> {
+        let mut relationships = spdx
+            .relationships
+            .iter()
+            .map(|relationship| {
+                format!(
+                    "{} {} {}",
+                    relationship.spdx_element_id,
+                    relationship.relationship_type,
+                    relationship.related_spdx_element
+                )
+            })
+            .collect::<Vec<_>>();
+        relationships.sort();
+        relationships
+    }
+
+    #[test]
+    fn tag_value_round_trips() {
+        let spdx =
+            spdx_from_tag_value(&read_to_string("tests/data/SPDXTagExample-v2.2.spdx").unwrap())
+                .unwrap();
+        let written = spdx_from_tag_value(&spdx_to_tag_value(&spdx)).unwrap();
+
+        assert_eq!(
+            written.document_creation_information,
+            spdx.document_creation_information
+        );
+        assert_eq!(written.package_information, spdx.package_information);
+        assert_eq!(written.file_information.len(), spdx.file_information.len());
+        for file in &spdx.file_information {
+            assert!(written.file_information.contains(file));
+        }
+        assert_eq!(written.snippet_information, spdx.snippet_information);
+        assert_eq!(
+            written.other_licensing_information_detected,
+            spdx.other_licensing_information_detected
+        );
+        assert_eq!(sorted_relationships(&written), sorted_relationships(&spdx));
+        assert_eq!(written.annotations, spdx.annotations);
+    }
+
+    #[test]
+    fn json_is_written_as_tag_value() {
+        let spdx: SPDX = serde_json::from_str(
+            &read_to_string("tests/data/SPDXJSONExample-v2.3.spdx.json").unwrap(),
+        )
+        .unwrap();
+        let tag_value = spdx_to_tag_value(&spdx);
+
+        assert!(tag_value
+            .contains("\nPackageChecksum: SHA1: 85ed0817af83a24ad8da68c2b5094de69833983c\n"));
+        assert!(tag_value.contains("\nExternalRef: SECURITY cpe23Type "));
+        assert!(tag_value.contains("\nRelationship: SPDXRef-Package DYNAMIC_LINK SPDXRef-Saxon\n"));
+        assert!(tag_value.contains("\nRelationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-File\n"));
+        assert!(tag_value.contains("\nFileType: SOURCE\n"));
+
+        let written = spdx_from_tag_value(&tag_value).unwrap();
+        assert_eq!(
+            written
+                .package_information
+                .iter()
+                .map(|package| &package.package_spdx_identifier)
+                .collect::<Vec<_>>(),
+            spdx.package_information
+                .iter()
+                .map(|package| &package.package_spdx_identifier)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            written.other_licensing_information_detected,
+            spdx.other_licensing_information_detected
+        );
+    }
+
+    #[test]
+    fn multiline_values_are_wrapped_in_text() {
+        let mut writer = TagValueWriter::default();
+        writer.tag("PackageComment", "one line");
+        writer.tag("PackageCopyrightText", "Copyright 1\nCopyright 2");
+        assert_eq!(
+            writer.finish(),
+            "PackageComment: one line\nPackageCopyrightText: <text>Copyright 1\nCopyright 2</text>\n"
+        );
+    }
+}
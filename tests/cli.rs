@@ -0,0 +1,151 @@
+// SPDX-FileCopyrightText: 2021 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+#![cfg(feature = "cli")]
+
+use serde_json::Value;
+use spdx_rs::models::SPDX;
+use std::env::temp_dir;
+use std::fs::{read_to_string, write};
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const EXAMPLE: &str = "tests/data/SPDXJSONExample-v2.3.spdx.json";
+
+fn spdx(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_spdx"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    temp_dir().join(format!("spdx-cli-{}-{name}", std::process::id()))
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn convert_round_trips_through_tag_value() {
+    let tag_value = temp_path("convert.spdx");
+    let json = temp_path("convert.spdx.json");
+
+    let output = spdx(&["convert", EXAMPLE, tag_value.to_str().unwrap()]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(read_to_string(&tag_value)
+        .unwrap()
+        .starts_with("SPDXVersion: SPDX-2.3\n"));
+
+    let output = spdx(&[
+        "convert",
+        tag_value.to_str().unwrap(),
+        json.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{:?}", output);
+    let converted: SPDX = serde_json::from_str(&read_to_string(&json).unwrap()).unwrap();
+    assert_eq!(
+        converted.package_information.len(),
+        serde_json::from_str::<SPDX>(&read_to_string(EXAMPLE).unwrap())
+            .unwrap()
+            .package_information
+            .len()
+    );
+}
+
+#[test]
+fn canonical_output_does_not_depend_on_input_order() {
+    let mut document: Value = serde_json::from_str(&read_to_string(EXAMPLE).unwrap()).unwrap();
+    for key in ["packages", "files", "relationships"] {
+        document[key].as_array_mut().unwrap().reverse();
+    }
+    let shuffled = temp_path("shuffled.spdx.json");
+    write(&shuffled, document.to_string()).unwrap();
+
+    let canonical = |input: &str| {
+        let output = spdx(&["convert", input, "-", "--to", "json", "--canonical"]);
+        assert!(output.status.success(), "{:?}", output);
+        stdout(&output)
+    };
+    assert_eq!(canonical(EXAMPLE), canonical(shuffled.to_str().unwrap()));
+}
+
+#[test]
+fn validate_sets_exit_code() {
+    let output = spdx(&["validate", EXAMPLE, "--json"]);
+    let result: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(result["valid"].as_bool(), Some(output.status.success()));
+
+    let invalid = temp_path("invalid.spdx.json");
+    let mut document: Value = serde_json::from_str(&read_to_string(EXAMPLE).unwrap()).unwrap();
+    document["packages"][0]["SPDXID"] = Value::from("Package");
+    write(&invalid, document.to_string()).unwrap();
+    let output = spdx(&["validate", invalid.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!stdout(&output).is_empty());
+
+    let output = spdx(&["validate", "tests/data/missing.spdx.json"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn diff_sets_exit_code() {
+    let output = spdx(&["diff", EXAMPLE, EXAMPLE]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).is_empty());
+
+    let changed = temp_path("changed.spdx.json");
+    let mut document: Value = serde_json::from_str(&read_to_string(EXAMPLE).unwrap()).unwrap();
+    document["packages"][0]["versionInfo"] = Value::from("2.12");
+    write(&changed, document.to_string()).unwrap();
+    let output = spdx(&["diff", EXAMPLE, changed.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("versionInfo"));
+}
+
+#[test]
+fn merge_writes_one_document() {
+    let merged = temp_path("merged.spdx.json");
+    let output = spdx(&[
+        "merge",
+        EXAMPLE,
+        EXAMPLE,
+        "-o",
+        merged.to_str().unwrap(),
+        "--name",
+        "merged",
+    ]);
+    assert!(output.status.success(), "{:?}", output);
+    let merged: SPDX = serde_json::from_str(&read_to_string(&merged).unwrap()).unwrap();
+    assert_eq!(merged.document_creation_information.document_name, "merged");
+}
+
+#[test]
+fn summary_and_query() {
+    let output = spdx(&["summary", EXAMPLE, "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let summary: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert!(summary["packages"].as_u64().unwrap() > 0);
+
+    let output = spdx(&["query", EXAMPLE, "packages where name contains saxon"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(stdout(&output), "package SPDXRef-Saxon Saxon\n");
+
+    let output = spdx(&[
+        "query",
+        EXAMPLE,
+        "packages where from SPDXRef-Package via DYNAMIC_LINK select versionInfo",
+    ]);
+    assert_eq!(
+        stdout(&output),
+        "package SPDXRef-Saxon versionInfo=\"8.8\"\n"
+    );
+
+    let output = spdx(&["query", EXAMPLE, "files where SPDXID = SPDXRef-Missing"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = spdx(&["query", EXAMPLE, "packages where"]);
+    assert_eq!(output.status.code(), Some(2));
+}
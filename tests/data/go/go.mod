@@ -0,0 +1,12 @@
+module github.com/example/app
+
+go 1.21
+
+require (
+	github.com/BurntSushi/toml v1.3.2
+	golang.org/x/text v0.13.0 // indirect
+)
+
+require github.com/google/uuid v1.3.1
+
+replace example.com/old => example.com/new v1.0.0